@@ -0,0 +1,179 @@
+//! HTTP 传输的 Bearer 令牌认证
+//!
+//! 网络传输上任何能连接的客户端都可以执行 `run_windbg_cmd`（包括 `.shell`），
+//! 因此配置了令牌时，每个 HTTP 请求都必须携带 `Authorization: Bearer <令牌>`。
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::warn;
+
+/// 认证失败时返回的 JSON-RPC 错误码（实现定义的服务器错误区间）
+pub const UNAUTHORIZED_ERROR_CODE: i32 = -32001;
+
+/// 解析被拒绝请求的 JSON-RPC 消息时读取的最大字节数
+const MAX_REJECTED_BODY_BYTES: usize = 64 * 1024;
+
+/// Bearer 令牌校验器
+#[derive(Clone)]
+pub struct BearerAuth {
+    token: Arc<str>,
+}
+
+impl BearerAuth {
+    /// 创建校验器
+    ///
+    /// # 参数
+    /// * `token` - 客户端必须提供的令牌
+    pub fn new(token: impl Into<Arc<str>>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+
+    /// 检查请求头中的 `Authorization: Bearer` 令牌
+    ///
+    /// # 返回
+    /// 令牌存在且匹配时返回 true
+    pub fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                let (scheme, token) = value.trim().split_once(' ')?;
+                scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+            })
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.token.as_bytes()))
+    }
+}
+
+/// 以与内容无关的时间比较两个字节串，避免通过响应时间猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// axum 中间件：拒绝未携带有效令牌的请求
+///
+/// 被拒绝的请求返回 HTTP 401 和 JSON-RPC 错误（沿用请求的 `id`），
+/// 并记录对端地址和 JSON-RPC 方法。
+pub async fn require_bearer_token(
+    State(auth): State<BearerAuth>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if auth.is_authorized(request.headers()) {
+        return next.run(request).await;
+    }
+
+    let has_credentials = request.headers().contains_key(header::AUTHORIZATION);
+    let http_method = request.method().clone();
+    let message = to_bytes(request.into_body(), MAX_REJECTED_BODY_BYTES)
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_slice::<Value>(&body).ok());
+    let rpc_method = message
+        .as_ref()
+        .and_then(|m| m.get("method"))
+        .and_then(Value::as_str)
+        .unwrap_or("-");
+    let id = message
+        .as_ref()
+        .and_then(|m| m.get("id"))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    warn!(
+        "Rejected unauthenticated {} request from {} (method: {}, {})",
+        http_method,
+        peer,
+        rpc_method,
+        if has_credentials {
+            "invalid bearer token"
+        } else {
+            "missing bearer token"
+        }
+    );
+
+    unauthorized_response(id)
+}
+
+/// 构造 401 响应，正文为 JSON-RPC 错误
+fn unauthorized_response(id: Value) -> Response {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": UNAUTHORIZED_ERROR_CODE,
+            "message": "Unauthorized: missing or invalid bearer token (send 'Authorization: Bearer <token>')",
+        }
+    });
+    (
+        StatusCode::UNAUTHORIZED,
+        [
+            (header::WWW_AUTHENTICATE, "Bearer"),
+            (header::CONTENT_TYPE, "application/json"),
+        ],
+        Body::from(body.to_string()),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_bearer_auth() {
+        let auth = BearerAuth::new("s3cret");
+        assert!(auth.is_authorized(&headers("Bearer s3cret")));
+        assert!(auth.is_authorized(&headers("bearer  s3cret ")));
+        assert!(!auth.is_authorized(&headers("Bearer s3cre")));
+        assert!(!auth.is_authorized(&headers("Bearer s3cret2")));
+        assert!(!auth.is_authorized(&headers("Basic s3cret")));
+        assert!(!auth.is_authorized(&headers("s3cret")));
+        assert!(!auth.is_authorized(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_response() {
+        let response = unauthorized_response(json!(0));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let message: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(message["id"], 0);
+        assert_eq!(message["error"]["code"], UNAUTHORIZED_ERROR_CODE);
+        assert!(message["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Unauthorized"));
+    }
+}
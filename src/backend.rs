@@ -0,0 +1,217 @@
+//! 调试器后端抽象模块
+//!
+//! 定义会话管理器和工具处理器所依赖的调试器后端接口，
+//! 使 cdb.exe 之外的实现（kd.exe、测试用脚本化后端等）可以无缝接入。
+
+use crate::cdb::CdbSession;
+use crate::error::CdbError;
+use crate::timeouts::CommandTimeouts;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// 共享的后端会话句柄
+pub type SharedBackend = Arc<Mutex<Box<dyn DebuggerBackend>>>;
+
+/// 调试目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugTarget {
+    /// 崩溃转储文件
+    Dump(PathBuf),
+    /// 远程调试连接字符串（例如：tcp:Port=5005,Server=192.168.0.100）
+    Remote(String),
+}
+
+impl DebugTarget {
+    /// 生成会话 ID
+    ///
+    /// 转储文件使用绝对路径，远程目标使用连接字符串。
+    pub fn session_id(&self) -> String {
+        match self {
+            DebugTarget::Dump(path) => path
+                .canonicalize()
+                .unwrap_or_else(|_| path.clone())
+                .to_string_lossy()
+                .to_string(),
+            DebugTarget::Remote(connection_string) => connection_string.clone(),
+        }
+    }
+}
+
+/// 后端启动选项
+#[derive(Debug, Clone)]
+pub struct SpawnOptions {
+    /// 可选的自定义调试器路径
+    pub cdb_path: Option<PathBuf>,
+    /// 可选的符号路径
+    pub symbols_path: Option<String>,
+    /// 传递给调试器的额外命令行参数
+    pub extra_args: Vec<String>,
+    /// 命令执行超时时间
+    pub timeout: Duration,
+    /// 初始化超时时间
+    pub init_timeout: Duration,
+    /// 是否启用详细日志
+    pub verbose: bool,
+    /// 命令类别超时表（按命令选择超时，未匹配时使用 `timeout`）
+    pub command_timeouts: CommandTimeouts,
+}
+
+/// 调试器后端
+///
+/// 表示一个已启动的调试器实例。会话管理器只通过该 trait 与调试器交互。
+#[async_trait]
+pub trait DebuggerBackend: Send + std::fmt::Debug {
+    /// 获取会话 ID
+    fn session_id(&self) -> &str;
+
+    /// 发送命令并等待输出
+    ///
+    /// # 参数
+    /// * `command` - 要执行的调试器命令
+    ///
+    /// # 返回
+    /// 返回命令输出的行列表
+    async fn send_command(&mut self, command: &str) -> Result<Vec<String>, CdbError>;
+
+    /// 发送命令并等待输出，超时或取消令牌触发时停止等待
+    ///
+    /// 默认实现只放弃等待输出；能够中断调试器的后端（例如 [`CdbSession`]）应覆盖该方法，
+    /// 中断正在执行的命令并使会话保持可用。
+    ///
+    /// # 参数
+    /// * `command` - 要执行的调试器命令
+    /// * `timeout` - 本次命令的超时时间；为 None 时使用后端的默认超时
+    /// * `cancel` - 取消令牌
+    ///
+    /// # 错误
+    /// 取消时返回 [`CdbError::Cancelled`]，超时返回 [`CdbError::CommandTimeout`]
+    async fn send_command_cancellable(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>, CdbError> {
+        let send = async {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, self.send_command(command))
+                    .await
+                    .unwrap_or_else(|_| Err(CdbError::timeout(timeout))),
+                None => self.send_command(command).await,
+            }
+        };
+        tokio::select! {
+            result = send => result,
+            _ = cancel.cancelled() => Err(CdbError::Cancelled),
+        }
+    }
+
+    /// 调试器进程 ID（不对应独立进程的后端返回 None）
+    fn process_id(&self) -> Option<u32> {
+        None
+    }
+
+    /// 调试器进程累计消耗的 CPU 时间（无法获取时返回 None）
+    fn cpu_time(&self) -> Option<Duration> {
+        self.process_id().and_then(crate::utils::process_cpu_time)
+    }
+
+    /// 调试器进程是否仍在运行（无法判断的后端返回 true）
+    fn is_alive(&mut self) -> bool {
+        true
+    }
+
+    /// 当前的 stderr 位置标记
+    ///
+    /// 与 [`stderr_since`](Self::stderr_since) 配合，获取某段时间内调试器写入 stderr 的行。
+    fn stderr_mark(&self) -> u64 {
+        0
+    }
+
+    /// 指定位置标记之后调试器写入 stderr 的行（不捕获 stderr 的后端返回空列表）
+    ///
+    /// # 参数
+    /// * `mark` - [`stderr_mark`](Self::stderr_mark) 返回的位置标记
+    fn stderr_since(&self, _mark: u64) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 等待调试器已写入 stderr 的内容进入缓冲区
+    ///
+    /// 在 [`stderr_mark`](Self::stderr_mark) 之前调用，可避免上一条命令迟到的 stderr
+    /// 被计入下一条命令；在 [`stderr_since`](Self::stderr_since) 之前调用，可收齐本条命令的输出。
+    async fn sync_stderr(&mut self) {}
+
+    /// 取出会话重启提示
+    ///
+    /// 会话因调试器进程退出而被重新启动后，第一次调用返回说明文本，之后返回 None。
+    fn take_restart_notice(&mut self) -> Option<String> {
+        None
+    }
+
+    /// 关闭后端并释放调试器进程
+    async fn shutdown(self: Box<Self>) -> Result<(), CdbError>;
+}
+
+/// 调试器后端启动器
+///
+/// 负责为指定的调试目标创建新的后端实例。
+#[async_trait]
+pub trait BackendLauncher: Send + Sync {
+    /// 启动新的后端
+    ///
+    /// # 参数
+    /// * `target` - 调试目标
+    /// * `options` - 启动选项
+    ///
+    /// # 错误
+    /// 如果调试器未找到或启动失败，返回错误
+    async fn spawn(
+        &self,
+        target: &DebugTarget,
+        options: &SpawnOptions,
+    ) -> Result<Box<dyn DebuggerBackend>, CdbError>;
+}
+
+/// 基于 cdb.exe 的默认启动器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CdbLauncher;
+
+#[async_trait]
+impl BackendLauncher for CdbLauncher {
+    async fn spawn(
+        &self,
+        target: &DebugTarget,
+        options: &SpawnOptions,
+    ) -> Result<Box<dyn DebuggerBackend>, CdbError> {
+        let session = match target {
+            DebugTarget::Dump(path) => CdbSession::new_dump(path, options).await?,
+            DebugTarget::Remote(connection_string) => {
+                CdbSession::new_remote(connection_string, options).await?
+            }
+        };
+
+        Ok(Box::new(session))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_target_session_id_remote() {
+        let target = DebugTarget::Remote("tcp:Port=5005,Server=10.0.0.1".to_string());
+        assert_eq!(target.session_id(), "tcp:Port=5005,Server=10.0.0.1");
+    }
+
+    #[test]
+    fn test_debug_target_session_id_dump_nonexistent() {
+        // 无法规范化的路径保持原样
+        let target = DebugTarget::Dump(PathBuf::from("nonexistent.dmp"));
+        assert_eq!(target.session_id(), "nonexistent.dmp");
+    }
+}
@@ -2,17 +2,162 @@
 //!
 //! 提供 CDB 进程的启动、命令执行和输出解析功能。
 
+use crate::backend::{DebugTarget, DebuggerBackend, SpawnOptions};
 use crate::error::CdbError;
+use crate::framing::{CommandFramer, Sentinel};
+use crate::timeouts::CommandTimeouts;
 use crate::utils;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
 use std::path::Path;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, ChildStdout, Command};
-use tokio::sync::Mutex;
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
+/// stderr 环形缓冲区保留的行数
+const STDERR_BUFFER_LINES: usize = 256;
+
+/// 启动失败时附加到错误信息中的 stderr 行数
+const STDERR_TAIL_LINES: usize = 20;
+
+/// 启动失败后等待 stderr 读取任务收集剩余输出的时间
+const STDERR_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 同步 stderr 时，管道在该时间内没有新数据即认为已读完
+const STDERR_SYNC_QUIET_TIME: Duration = Duration::from_millis(20);
+
+/// 同步 stderr 的最长时间（调试器持续写入 stderr 时不会无限等待）
+const STDERR_SYNC_MAX_TIME: Duration = Duration::from_millis(500);
+
+/// stderr 同步请求：读取任务读完管道中已有的内容后通过该通道应答
+type StderrSyncRequest = oneshot::Sender<()>;
+
+/// 中断命令后等待 CDB 输出该命令结束哨兵的时间
+const RESYNC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// CDB stderr 环形缓冲区
+///
+/// 后台任务持续读取 stderr，避免管道写满后 CDB 阻塞；只保留最近的 [`STDERR_BUFFER_LINES`] 行。
+/// 位置标记是累计收到的行数，被挤出缓冲区的行不再返回。
+#[derive(Debug, Clone, Default)]
+pub struct StderrBuffer {
+    inner: Arc<std::sync::Mutex<StderrLines>>,
+}
+
+#[derive(Debug, Default)]
+struct StderrLines {
+    lines: VecDeque<String>,
+    /// 累计收到的行数
+    total: u64,
+}
+
+impl StderrBuffer {
+    /// 创建空缓冲区
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加一行，超出容量时丢弃最早的行
+    pub fn push(&self, line: String) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.lines.len() == STDERR_BUFFER_LINES {
+            inner.lines.pop_front();
+        }
+        inner.lines.push_back(line);
+        inner.total += 1;
+    }
+
+    /// 当前位置标记（累计收到的行数）
+    pub fn mark(&self) -> u64 {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).total
+    }
+
+    /// 指定位置标记之后收到的行
+    ///
+    /// # 参数
+    /// * `mark` - [`mark`](Self::mark) 返回的位置标记
+    pub fn since(&self, mark: u64) -> Vec<String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let first = inner.total - inner.lines.len() as u64;
+        let skip = mark.saturating_sub(first) as usize;
+        inner.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// 最近收到的 `count` 行
+    pub fn tail(&self, count: usize) -> Vec<String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = inner.lines.len().saturating_sub(count);
+        inner.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// 启动后台任务，持续读取 stderr 直到管道关闭
+    ///
+    /// 收到同步请求时读完管道中已有的内容再应答，使调试器在此之前写入的行都进入缓冲区。
+    ///
+    /// # 参数
+    /// * `stderr` - CDB 子进程的 stderr
+    /// * `sync` - 同步请求通道
+    /// * `session_id` - 会话 ID（用于日志）
+    fn spawn_drain(
+        &self,
+        stderr: ChildStderr,
+        mut sync: mpsc::UnboundedReceiver<StderrSyncRequest>,
+        session_id: String,
+    ) -> JoinHandle<()> {
+        let buffer = self.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stderr);
+            // 被同步请求打断的半行保留在 line 中，下次读取时继续
+            let mut line = Vec::new();
+            let push_line = |line: &mut Vec<u8>| {
+                // stderr 可能使用本地代码页，按有损 UTF-8 解码
+                let text = String::from_utf8_lossy(line).trim_end().to_string();
+                debug!("CDB stderr [{}]: {}", session_id, text);
+                buffer.push(text);
+                line.clear();
+            };
+            loop {
+                tokio::select! {
+                    read = reader.read_until(b'\n', &mut line) => match read {
+                        Ok(0) => break,
+                        Ok(_) => push_line(&mut line),
+                        Err(e) => {
+                            debug!("Stopped reading CDB stderr [{}]: {}", session_id, e);
+                            break;
+                        }
+                    },
+                    Some(ack) = sync.recv() => {
+                        let deadline = tokio::time::Instant::now() + STDERR_SYNC_MAX_TIME;
+                        let mut closed = false;
+                        while tokio::time::Instant::now() < deadline {
+                            let read = reader.read_until(b'\n', &mut line);
+                            match tokio::time::timeout(STDERR_SYNC_QUIET_TIME, read).await {
+                                Ok(Ok(0)) | Ok(Err(_)) => {
+                                    closed = true;
+                                    break;
+                                }
+                                Ok(Ok(_)) => push_line(&mut line),
+                                Err(_) => break,
+                            }
+                        }
+                        let _ = ack.send(());
+                        if closed {
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
 /// 会话类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SessionType {
@@ -22,6 +167,23 @@ enum SessionType {
     Remote,
 }
 
+/// 单条命令的读取状态
+///
+/// 读取被超时或取消打断后保留，中断命令后继续读取到该命令的结束哨兵。
+#[derive(Debug, Default)]
+struct FrameState {
+    /// 已收集的输出行
+    output: Vec<String>,
+    /// 是否已读到开始哨兵
+    started: bool,
+    /// 已读取的行数
+    lines_read: usize,
+    /// 开始哨兵之前丢弃的行数
+    discarded: usize,
+    /// 尚未读完的行
+    pending: Vec<u8>,
+}
+
 /// CDB 会话
 ///
 /// 表示一个活跃的 CDB 进程实例，用于调试转储文件或远程目标。
@@ -34,14 +196,24 @@ pub struct CdbSession {
     stdin: ChildStdin,
     /// 标准输出读取器（使用 Arc<Mutex> 以支持并发读取）
     stdout_reader: Arc<Mutex<BufReader<ChildStdout>>>,
-    /// 命令执行超时时间
+    /// 命令执行超时时间（默认值）
     timeout: Duration,
+    /// 命令类别超时表
+    command_timeouts: CommandTimeouts,
     /// 初始化超时时间（用于启动和符号加载）
     init_timeout: Duration,
     /// 是否启用详细日志
     verbose: bool,
     /// 会话类型
     session_type: SessionType,
+    /// 命令分帧器（生成和识别哨兵）
+    framer: CommandFramer,
+    /// 最近的 stderr 输出
+    stderr: StderrBuffer,
+    /// stderr 读取任务
+    stderr_task: Option<JoinHandle<()>>,
+    /// stderr 同步请求通道
+    stderr_sync: mpsc::UnboundedSender<StderrSyncRequest>,
 }
 
 impl CdbSession {
@@ -49,84 +221,30 @@ impl CdbSession {
     ///
     /// # 参数
     /// * `dump_path` - 转储文件路径
-    /// * `cdb_path` - 可选的自定义 CDB 路径
-    /// * `symbols_path` - 可选的符号路径
-    /// * `timeout` - 命令执行超时时间
-    /// * `init_timeout` - 初始化超时时间
-    /// * `verbose` - 是否启用详细日志
+    /// * `options` - 启动选项（CDB 路径、符号路径、额外参数和超时设置）
     ///
     /// # 返回
     /// 返回新创建的 CDB 会话
     ///
     /// # 错误
     /// 如果 CDB 可执行文件未找到或进程启动失败，返回错误
-    pub async fn new_dump(
-        dump_path: &Path,
-        cdb_path: Option<&Path>,
-        symbols_path: Option<&str>,
-        timeout: Duration,
-        init_timeout: Duration,
-        verbose: bool,
-    ) -> Result<Self, CdbError> {
-        // 查找 CDB 可执行文件
-        let cdb_exe = utils::find_cdb_executable(cdb_path).ok_or(CdbError::ExecutableNotFound)?;
-
-        info!("Using CDB: {}", cdb_exe.display());
+    pub async fn new_dump(dump_path: &Path, options: &SpawnOptions) -> Result<Self, CdbError> {
         info!("Opening dump file: {}", dump_path.display());
 
-        // 构建命令
-        let mut cmd = Command::new(&cdb_exe);
-        cmd.arg("-z") // 打开转储文件
-            .arg(dump_path)
-            .arg("-c") // 初始命令
-            .arg(".echo CDB_READY") // 启动完成标记
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // 设置符号路径
-        if let Some(sym_path) = symbols_path {
-            cmd.env("_NT_SYMBOL_PATH", sym_path);
-        }
-
-        // 启动进程
-        let mut process = cmd
-            .spawn()
-            .map_err(|e| CdbError::ProcessStartFailed(e.to_string()))?;
-
-        // 获取 stdin 和 stdout
-        let stdin = process
-            .stdin
-            .take()
-            .ok_or_else(|| CdbError::ProcessStartFailed("Failed to get stdin".to_string()))?;
-
-        let stdout = process
-            .stdout
-            .take()
-            .ok_or_else(|| CdbError::ProcessStartFailed("Failed to get stdout".to_string()))?;
-
-        let stdout_reader = Arc::new(Mutex::new(BufReader::new(stdout)));
-
         // 生成会话 ID（使用绝对路径）
-        let session_id = dump_path
-            .canonicalize()
-            .unwrap_or_else(|_| dump_path.to_path_buf())
-            .to_string_lossy()
-            .to_string();
+        let session_id = DebugTarget::Dump(dump_path.to_path_buf()).session_id();
 
-        let mut session = Self {
+        let mut session = Self::launch(
+            &[OsStr::new("-z"), dump_path.as_os_str()], // 打开转储文件
             session_id,
-            process,
-            stdin,
-            stdout_reader,
-            timeout,
-            init_timeout,
-            verbose,
-            session_type: SessionType::Dump,
-        };
+            SessionType::Dump,
+            options,
+        )?;
 
         // 等待 CDB 启动完成
-        session.wait_for_ready().await?;
+        if let Err(e) = session.wait_for_ready().await {
+            return Err(session.startup_error(e).await);
+        }
 
         info!("CDB session started");
 
@@ -137,11 +255,7 @@ impl CdbSession {
     ///
     /// # 参数
     /// * `connection_string` - 远程连接字符串（例如：tcp:Port=5005,Server=192.168.0.100）
-    /// * `cdb_path` - 可选的自定义 CDB 路径
-    /// * `symbols_path` - 可选的符号路径
-    /// * `timeout` - 命令执行超时时间
-    /// * `init_timeout` - 初始化超时时间
-    /// * `verbose` - 是否启用详细日志
+    /// * `options` - 启动选项（CDB 路径、符号路径、额外参数和超时设置）
     ///
     /// # 返回
     /// 返回新创建的 CDB 会话
@@ -150,33 +264,69 @@ impl CdbSession {
     /// 如果 CDB 可执行文件未找到或进程启动失败，返回错误
     pub async fn new_remote(
         connection_string: &str,
-        cdb_path: Option<&Path>,
-        symbols_path: Option<&str>,
-        timeout: Duration,
-        init_timeout: Duration,
-        verbose: bool,
+        options: &SpawnOptions,
+    ) -> Result<Self, CdbError> {
+        info!("Connecting to remote target: {}", connection_string);
+
+        // 使用连接字符串作为会话 ID
+        let session_id = connection_string.to_string();
+
+        let mut session = Self::launch(
+            &[OsStr::new("-remote"), OsStr::new(connection_string)], // 远程调试
+            session_id,
+            SessionType::Remote,
+            options,
+        )?;
+
+        // 等待 CDB 启动完成
+        if let Err(e) = session.wait_for_ready().await {
+            return Err(session.startup_error(e).await);
+        }
+
+        info!("CDB remote session started");
+
+        Ok(session)
+    }
+
+    /// 启动 CDB 进程（内部辅助函数）
+    ///
+    /// # 参数
+    /// * `target_args` - 指定调试目标的参数（`-z <dump>` 或 `-remote <conn>`）
+    /// * `session_id` - 会话 ID
+    /// * `session_type` - 会话类型
+    /// * `options` - 启动选项
+    fn launch(
+        target_args: &[&OsStr],
+        session_id: String,
+        session_type: SessionType,
+        options: &SpawnOptions,
     ) -> Result<Self, CdbError> {
         // 查找 CDB 可执行文件
-        let cdb_exe = utils::find_cdb_executable(cdb_path).ok_or(CdbError::ExecutableNotFound)?;
+        let cdb_exe = utils::find_cdb_executable(options.cdb_path.as_deref())
+            .ok_or(CdbError::ExecutableNotFound)?;
 
         info!("Using CDB: {}", cdb_exe.display());
-        info!("Connecting to remote target: {}", connection_string);
 
-        // 构建命令
+        // 构建命令（额外参数放在目标参数之前）
+        let framer = CommandFramer::new();
         let mut cmd = Command::new(&cdb_exe);
-        cmd.arg("-remote") // 远程调试
-            .arg(connection_string)
+        cmd.args(&options.extra_args)
+            .args(target_args)
             .arg("-c") // 初始命令
-            .arg(".echo CDB_READY") // 启动完成标记
+            .arg(format!(".echo {}", framer.ready_marker())) // 启动完成哨兵
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
         // 设置符号路径
-        if let Some(sym_path) = symbols_path {
+        if let Some(sym_path) = &options.symbols_path {
             cmd.env("_NT_SYMBOL_PATH", sym_path);
         }
 
+        // 使用独立的进程组，以便向 CDB 单独发送 Ctrl+Break 中断命令
+        #[cfg(windows)]
+        cmd.creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+
         // 启动进程
         let mut process = cmd
             .spawn()
@@ -195,26 +345,29 @@ impl CdbSession {
 
         let stdout_reader = Arc::new(Mutex::new(BufReader::new(stdout)));
 
-        // 使用连接字符串作为会话 ID
-        let session_id = connection_string.to_string();
+        // 持续读取 stderr，避免管道写满后 CDB 阻塞
+        let stderr = StderrBuffer::new();
+        let (stderr_sync, sync_requests) = mpsc::unbounded_channel();
+        let stderr_task = process
+            .stderr
+            .take()
+            .map(|pipe| stderr.spawn_drain(pipe, sync_requests, session_id.clone()));
 
-        let mut session = Self {
+        Ok(Self {
             session_id,
             process,
             stdin,
             stdout_reader,
-            timeout,
-            init_timeout,
-            verbose,
-            session_type: SessionType::Remote,
-        };
-
-        // 等待 CDB 启动完成
-        session.wait_for_ready().await?;
-
-        info!("CDB remote session started");
-
-        Ok(session)
+            timeout: options.timeout,
+            command_timeouts: options.command_timeouts.clone(),
+            init_timeout: options.init_timeout,
+            verbose: options.verbose,
+            session_type,
+            framer,
+            stderr,
+            stderr_task,
+            stderr_sync,
+        })
     }
 
     /// 获取会话 ID
@@ -222,11 +375,62 @@ impl CdbSession {
         &self.session_id
     }
 
+    /// 获取 stderr 缓冲区
+    pub fn stderr(&self) -> &StderrBuffer {
+        &self.stderr
+    }
+
+    /// 等待 CDB 已写入 stderr 的内容进入缓冲区
+    ///
+    /// stderr 由后台任务异步读取，命令结束时它写入的行可能还留在管道中。
+    /// 读取任务已退出时立即返回。
+    pub async fn sync_stderr(&self) {
+        let (ack, done) = oneshot::channel();
+        if self.stderr_sync.send(ack).is_ok() {
+            let _ = done.await;
+        }
+    }
+
+    /// 为启动失败补充诊断信息
+    ///
+    /// CDB 在启动阶段退出时，返回包含退出状态和 stderr 最后几行的
+    /// [`CdbError::ProcessStartFailed`]；其他错误原样返回，stderr 内容写入日志。
+    async fn startup_error(&mut self, error: CdbError) -> CdbError {
+        if !matches!(error, CdbError::ProcessTerminated) {
+            let tail = self.stderr.tail(STDERR_TAIL_LINES);
+            if !tail.is_empty() {
+                warn!("CDB stderr before startup failure:\n{}", tail.join("\n"));
+            }
+            return error;
+        }
+
+        // 进程已退出，等待 stderr 读取任务收集剩余输出
+        if let Some(task) = self.stderr_task.take() {
+            let _ = tokio::time::timeout(STDERR_FLUSH_TIMEOUT, task).await;
+        }
+        let status = match tokio::time::timeout(STDERR_FLUSH_TIMEOUT, self.process.wait()).await {
+            Ok(Ok(status)) => status.to_string(),
+            _ => "exit status unknown".to_string(),
+        };
+
+        let tail = self.stderr.tail(STDERR_TAIL_LINES);
+        let mut message = format!("cdb exited during startup ({})", status);
+        if !tail.is_empty() {
+            message.push_str("; stderr:\n");
+            message.push_str(&tail.join("\n"));
+        }
+        warn!("{}", message);
+        CdbError::ProcessStartFailed(message)
+    }
+
     /// 等待 CDB 启动完成
     ///
-    /// 读取输出直到看到 "CDB_READY" 标记
+    /// 读取输出直到看到启动完成哨兵
     async fn wait_for_ready(&mut self) -> Result<(), CdbError> {
-        debug!("Waiting for CDB to start (timeout: {:?})...", self.init_timeout);
+        debug!(
+            "Waiting for CDB to start (timeout: {:?})...",
+            self.init_timeout
+        );
 
         let mut reader = self.stdout_reader.lock().await;
         let mut line = String::new();
@@ -245,7 +449,7 @@ impl CdbSession {
                         if self.verbose {
                             debug!("CDB output: {}", line.trim());
                         }
-                        if line.contains("CDB_READY") {
+                        if self.framer.classify(&line) == Sentinel::Ready {
                             return Ok(());
                         }
                     }
@@ -261,13 +465,15 @@ impl CdbSession {
             Ok(result) => result,
             Err(_) => {
                 warn!("CDB initialization timeout after {:?}", self.init_timeout);
-                Err(CdbError::CommandTimeout(self.init_timeout))
+                Err(CdbError::timeout(self.init_timeout))
             }
         }
     }
 
     /// 发送命令并等待输出
     ///
+    /// 命令前后各附加一个哨兵，只返回两个哨兵之间的输出。
+    ///
     /// # 参数
     /// * `command` - 要执行的 WinDbg 命令
     ///
@@ -277,22 +483,51 @@ impl CdbSession {
     /// # 错误
     /// 如果命令发送失败、超时或进程终止，返回错误
     pub async fn send_command(&mut self, command: &str) -> Result<Vec<String>, CdbError> {
-        debug!("Executing command: {}", command);
-
-        // 构建完整命令（包含完成标记）
-        // 使用唯一的标记以避免与输出内容冲突
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-        let marker = format!("CMD_DONE_{}", timestamp);
-        
-        let full_command = format!("{}\n.echo {}\n", command.trim(), marker);
-
-        // 发送命令
+        self.execute(command, None, None).await
+    }
+
+    /// 发送命令并等待输出，取消令牌触发时中断命令
+    ///
+    /// 取消或超时后向 CDB 发送中断（Ctrl+Break），并读取到该命令的结束哨兵，
+    /// 使会话可以继续执行后续命令。
+    ///
+    /// # 参数
+    /// * `command` - 要执行的 CDB 命令
+    /// * `timeout` - 本次命令的超时时间；为 None 时按命令类别选择
+    /// * `cancel` - 取消令牌
+    ///
+    /// # 错误
+    /// 取消时返回 [`CdbError::Cancelled`]；命令发送失败、超时或进程终止时返回相应错误
+    pub async fn send_command_cancellable(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>, CdbError> {
+        self.execute(command, timeout, Some(cancel)).await
+    }
+
+    /// 命令的超时时间（按命令类别选择）
+    ///
+    /// # 参数
+    /// * `command` - CDB 命令行
+    pub fn timeout_for(&self, command: &str) -> Duration {
+        self.command_timeouts.timeout_for(command, self.timeout)
+    }
+
+    /// 发送命令并读取输出（可选超时和取消令牌）
+    async fn execute(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Vec<String>, CdbError> {
+        let frame = self.framer.next_frame();
+        debug!("Executing command #{}: {}", frame.id, command);
+
+        // 发送命令（包含开始和结束哨兵）
         self.stdin
-            .write_all(full_command.as_bytes())
+            .write_all(frame.wrap(command).as_bytes())
             .await
             .map_err(|e| CdbError::CommandSendFailed(e.to_string()))?;
 
@@ -301,83 +536,185 @@ impl CdbSession {
             .await
             .map_err(|e| CdbError::CommandSendFailed(e.to_string()))?;
 
-        // 读取输出直到看到标记
-        let output = self.read_until_marker(&marker).await?;
+        // 读取两个哨兵之间的输出（超时或取消时读取状态保留，用于重新同步）
+        let timeout = timeout.unwrap_or_else(|| self.timeout_for(command));
+        let mut state = FrameState::default();
+        let read = tokio::time::timeout(timeout, self.read_frame(frame.id, &mut state));
+        // None 表示命令被取消
+        let result = match cancel {
+            Some(cancel) => tokio::select! {
+                result = read => Some(result),
+                _ = cancel.cancelled() => None,
+            },
+            None => Some(read.await),
+        };
+
+        let reason = match result {
+            Some(Ok(output)) => {
+                let output = output?;
+                debug!(
+                    "Command execution completed, {} lines of output",
+                    output.len()
+                );
+                return Ok(output);
+            }
+            Some(Err(_)) => CdbError::CommandTimeout {
+                timeout,
+                partial_output: state.output.clone(),
+            },
+            None => CdbError::Cancelled,
+        };
+
+        warn!(
+            "Command #{} interrupted ({}) after reading {} lines",
+            frame.id, reason, state.lines_read
+        );
+        self.interrupt_and_resync(frame.id, &mut state).await?;
+        Err(reason)
+    }
+
+    /// 中断正在执行的命令并重新同步输出
+    ///
+    /// 向 CDB 发送中断后读取到该命令的结束哨兵。中断无法发送，或 CDB 在 [`RESYNC_TIMEOUT`]
+    /// 内没有结束命令时，CDB 仍在执行失控的命令，会话无法继续使用：此时结束 CDB 进程，
+    /// 会话被视为已终止，下次使用时由会话管理器重新启动。
+    ///
+    /// Windows 上的中断是 Ctrl+Break 控制台事件，只有服务器与 CDB 共用控制台时才能送达；
+    /// 由编辑器启动、没有控制台的服务器发送中断会失败或不起作用，最终同样结束 CDB 进程。
+    ///
+    /// # 参数
+    /// * `frame_id` - 被中断的命令序号
+    /// * `state` - 该命令的读取状态
+    ///
+    /// # 错误
+    /// 如果重新同步期间进程终止，返回错误
+    async fn interrupt_and_resync(
+        &mut self,
+        frame_id: u64,
+        state: &mut FrameState,
+    ) -> Result<(), CdbError> {
+        let pid = self.process.id().ok_or(CdbError::ProcessTerminated)?;
+        if let Err(e) = utils::interrupt_process(pid) {
+            warn!("Failed to interrupt CDB process {}: {}", pid, e);
+            self.kill_unresponsive().await;
+            return Ok(());
+        }
 
-        debug!("Command execution completed, {} lines of output", output.len());
+        match tokio::time::timeout(RESYNC_TIMEOUT, self.read_frame(frame_id, state)).await {
+            Ok(Ok(output)) => {
+                info!(
+                    "Resynchronized after command #{} ({} lines of output discarded)",
+                    frame_id,
+                    output.len()
+                );
+                Ok(())
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => {
+                warn!(
+                    "CDB did not finish command #{} within {:?} after the interrupt",
+                    frame_id, RESYNC_TIMEOUT
+                );
+                self.kill_unresponsive().await;
+                Ok(())
+            }
+        }
+    }
 
-        Ok(output)
+    /// 结束无法中断的 CDB 进程
+    ///
+    /// 等待进程退出，使 [`DebuggerBackend::is_alive`] 返回 false。
+    async fn kill_unresponsive(&mut self) {
+        warn!(
+            "Killing unresponsive CDB process for session {}",
+            self.session_id
+        );
+        if let Err(e) = self.process.kill().await {
+            warn!("Failed to kill CDB process: {}", e);
+        }
     }
 
-    /// 读取输出直到看到指定标记
+    /// 读取指定命令的输出
+    ///
+    /// 开始哨兵之前的行（超时命令的残留输出）被丢弃，结束哨兵之前的行作为输出返回。
+    /// 读取进度保存在 `state` 中，读取被超时或取消打断后可以继续读取同一命令的输出。
     ///
     /// # 参数
-    /// * `marker` - 完成标记字符串
+    /// * `frame_id` - 命令序号
+    /// * `state` - 该命令的读取状态
     ///
     /// # 返回
-    /// 返回读取到的输出行列表（不包含标记行）
+    /// 返回读取到的输出行列表（不包含哨兵行）
     ///
     /// # 错误
-    /// 如果读取超时或进程终止，返回错误
-    async fn read_until_marker(&mut self, marker: &str) -> Result<Vec<String>, CdbError> {
-        let mut output = Vec::new();
+    /// 如果进程终止或输出过大，返回错误
+    async fn read_frame(
+        &mut self,
+        frame_id: u64,
+        state: &mut FrameState,
+    ) -> Result<Vec<String>, CdbError> {
         let mut reader = self.stdout_reader.lock().await;
-        let mut line = String::new();
-        let mut lines_read = 0;
 
-        debug!("Waiting for marker: {}", marker);
+        debug!("Waiting for output of command #{}", frame_id);
+
+        loop {
+            // read_until 可以安全地被打断：已读取的字节保留在 pending 中
+            match reader.read_until(b'\n', &mut state.pending).await {
+                Ok(0) => {
+                    // EOF - 进程终止
+                    warn!(
+                        "CDB process terminated unexpectedly (read {} lines)",
+                        state.lines_read
+                    );
+                    return Err(CdbError::ProcessTerminated);
+                }
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&state.pending).into_owned();
+                    state.pending.clear();
+                    state.lines_read += 1;
 
-        // 使用超时读取输出
-        let read_result = tokio::time::timeout(self.timeout, async {
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => {
-                        // EOF - 进程终止
-                        warn!("CDB process terminated unexpectedly (read {} lines)", lines_read);
-                        return Err(CdbError::ProcessTerminated);
+                    if self.verbose {
+                        debug!("CDB[{}]: {}", state.lines_read, line.trim());
                     }
-                    Ok(_) => {
-                        lines_read += 1;
-                        let trimmed = line.trim();
 
-                        if self.verbose {
-                            debug!("CDB[{}]: {}", lines_read, trimmed);
+                    match self.framer.classify(&line) {
+                        Sentinel::Begin(id) if id == frame_id => {
+                            if state.discarded > 0 {
+                                info!(
+                                    "Discarded {} stale output lines before command #{}",
+                                    state.discarded, frame_id
+                                );
+                            }
+                            state.started = true;
+                            continue;
                         }
-
-                        // 检查是否是完成标记
-                        if trimmed.contains(marker) {
-                            debug!("Found marker after {} lines", lines_read);
-                            return Ok(output);
+                        Sentinel::End(id) if id == frame_id && state.started => {
+                            debug!("Found end sentinel after {} lines", state.lines_read);
+                            return Ok(std::mem::take(&mut state.output));
                         }
-
-                        // 添加到输出（保留原始行，包括空行）
-                        output.push(line.trim_end().to_string());
-                        
-                        // 防止无限输出导致内存溢出
-                        if output.len() > 100000 {
-                            warn!("Output exceeded 100k lines, stopping read");
-                            return Err(CdbError::CommandSendFailed(
-                                "Output too large".to_string()
-                            ));
+                        Sentinel::None if state.started => {}
+                        // 开始哨兵之前的输出和其他命令的哨兵
+                        _ => {
+                            state.discarded += 1;
+                            continue;
                         }
                     }
-                    Err(e) => {
-                        warn!("IO error after reading {} lines: {}", lines_read, e);
-                        return Err(CdbError::IoError(e));
+
+                    // 添加到输出（保留原始行，包括空行）
+                    state.output.push(line.trim_end().to_string());
+
+                    // 防止无限输出导致内存溢出
+                    if state.output.len() > 100000 {
+                        warn!("Output exceeded 100k lines, stopping read");
+                        return Err(CdbError::CommandSendFailed("Output too large".to_string()));
                     }
                 }
+                Err(e) => {
+                    warn!("IO error after reading {} lines: {}", state.lines_read, e);
+                    return Err(CdbError::IoError(e));
+                }
             }
-        })
-        .await;
-
-        read_result.map_err(|_| {
-            warn!(
-                "Command execution timeout ({:?}) after reading {} lines",
-                self.timeout, lines_read
-            );
-            CdbError::CommandTimeout(self.timeout)
-        })?
+        }
     }
 
     /// 关闭会话
@@ -427,24 +764,74 @@ impl CdbSession {
                 warn!("Failed to wait for process exit: {}", e);
                 // 尝试强制终止
                 let _ = self.process.kill().await;
-                Err(CdbError::ProcessStartFailed(format!("Failed to terminate process: {}", e)))
+                Err(CdbError::ProcessStartFailed(format!(
+                    "Failed to terminate process: {}",
+                    e
+                )))
             }
             Err(_) => {
                 warn!("Timeout waiting for process to exit, forcing termination");
                 // 超时，强制终止进程
                 let _ = self.process.kill().await;
-                Err(CdbError::CommandTimeout(Duration::from_secs(5)))
+                Err(CdbError::timeout(Duration::from_secs(5)))
             }
         }
     }
 }
 
+#[async_trait]
+impl DebuggerBackend for CdbSession {
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    async fn send_command(&mut self, command: &str) -> Result<Vec<String>, CdbError> {
+        CdbSession::send_command(self, command).await
+    }
+
+    async fn send_command_cancellable(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>, CdbError> {
+        CdbSession::send_command_cancellable(self, command, timeout, cancel).await
+    }
+
+    fn process_id(&self) -> Option<u32> {
+        self.process.id()
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.process.try_wait(), Ok(None))
+    }
+
+    fn stderr_mark(&self) -> u64 {
+        self.stderr.mark()
+    }
+
+    fn stderr_since(&self, mark: u64) -> Vec<String> {
+        self.stderr.since(mark)
+    }
+
+    async fn sync_stderr(&mut self) {
+        CdbSession::sync_stderr(self).await
+    }
+
+    async fn shutdown(self: Box<Self>) -> Result<(), CdbError> {
+        CdbSession::shutdown(*self).await
+    }
+}
+
 /// 实现 Drop trait 以确保资源正确释放
 impl Drop for CdbSession {
     fn drop(&mut self) {
         // 尝试终止进程（如果还在运行）
         // 注意：这是同步的 drop，所以我们只能尝试 kill
         let _ = self.process.start_kill();
+        if let Some(task) = &self.stderr_task {
+            task.abort();
+        }
         debug!("CDB session Drop: {}", self.session_id);
     }
 }
@@ -455,6 +842,7 @@ impl std::fmt::Debug for CdbSession {
         f.debug_struct("CdbSession")
             .field("session_id", &self.session_id)
             .field("timeout", &self.timeout)
+            .field("command_timeouts", &self.command_timeouts)
             .field("init_timeout", &self.init_timeout)
             .field("verbose", &self.verbose)
             .field("session_type", &self.session_type)
@@ -464,10 +852,54 @@ impl std::fmt::Debug for CdbSession {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_new_dump_executable_not_found() {
+        // 实际的 CDB 会话测试需要真实的 CDB 环境和转储文件，这里只验证错误路径
+        let options = SpawnOptions {
+            cdb_path: Some(Path::new("nonexistent_cdb.exe").to_path_buf()),
+            symbols_path: None,
+            extra_args: Vec::new(),
+            timeout: Duration::from_secs(1),
+            init_timeout: Duration::from_secs(1),
+            verbose: false,
+            command_timeouts: CommandTimeouts::default(),
+        };
+        let result = CdbSession::new_dump(Path::new("test.dmp"), &options).await;
+        assert!(matches!(result, Err(CdbError::ExecutableNotFound)));
+    }
+
+    #[test]
+    fn test_stderr_buffer_marks_and_tail() {
+        let buffer = StderrBuffer::new();
+        buffer.push("first".to_string());
+        let mark = buffer.mark();
+        assert_eq!(mark, 1);
+        buffer.push("second".to_string());
+        buffer.push("third".to_string());
+
+        assert_eq!(buffer.since(mark), vec!["second", "third"]);
+        assert!(buffer.since(buffer.mark()).is_empty());
+        assert_eq!(buffer.tail(2), vec!["second", "third"]);
+        assert_eq!(buffer.tail(10).len(), 3);
+    }
+
     #[test]
-    fn test_placeholder() {
-        // 实际的 CDB 会话测试需要真实的 CDB 环境和转储文件
-        // 这些测试将在集成测试中进行
-        assert!(true);
+    fn test_stderr_buffer_is_bounded() {
+        let buffer = StderrBuffer::new();
+        for i in 0..STDERR_BUFFER_LINES + 10 {
+            buffer.push(format!("line {}", i));
+        }
+        assert_eq!(buffer.mark(), (STDERR_BUFFER_LINES + 10) as u64);
+
+        // 被挤出缓冲区的行不再返回
+        let lines = buffer.since(0);
+        assert_eq!(lines.len(), STDERR_BUFFER_LINES);
+        assert_eq!(lines[0], "line 10");
+        assert_eq!(
+            buffer.since(buffer.mark() - 1),
+            vec![format!("line {}", STDERR_BUFFER_LINES + 9)]
+        );
     }
 }
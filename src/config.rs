@@ -0,0 +1,494 @@
+//! 配置文件模块
+//!
+//! 解析 `config.toml`（格式见 `config.example.toml`）并应用到 [`ServerConfig`]。
+//! 配置优先级：配置文件 < 环境变量 < 命令行参数。
+
+use crate::error::ConfigError;
+use crate::policy::PolicyProfile;
+use crate::server::{ServerConfig, Transport};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 默认配置文件名
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// 配置文件内容
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// [server] 段
+    #[serde(default)]
+    pub server: ServerSection,
+    /// [cdb] 段
+    #[serde(default)]
+    pub cdb: CdbSection,
+    /// [policy] 段
+    #[serde(default)]
+    pub policy: PolicySection,
+    /// [command_timeouts] 段：命令模式 -> 超时时间（秒）
+    #[serde(default)]
+    pub command_timeouts: BTreeMap<String, u64>,
+}
+
+/// [server] 段
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerSection {
+    /// 命令执行超时时间（秒）
+    pub timeout_seconds: Option<u64>,
+    /// 初始化超时时间（秒）
+    pub init_timeout_seconds: Option<u64>,
+    /// 是否启用详细日志
+    pub verbose: Option<bool>,
+    /// MCP 传输方式（`stdio` 或 `http`）
+    pub transport: Option<Transport>,
+    /// HTTP 传输的监听地址（例如 `127.0.0.1:8765`）
+    pub listen: Option<String>,
+    /// HTTP 传输要求的 Bearer 令牌
+    pub auth_token: Option<String>,
+    /// 保存 Bearer 令牌的文件
+    pub auth_token_file: Option<PathBuf>,
+    /// 空闲会话的存活时间（秒）
+    pub session_idle_ttl_seconds: Option<u64>,
+    /// 最大会话数
+    pub max_sessions: Option<u64>,
+}
+
+/// [cdb] 段
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CdbSection {
+    /// 自定义 cdb.exe 路径
+    pub path: Option<PathBuf>,
+    /// 符号路径
+    pub symbols_path: Option<String>,
+    /// 每个会话默认附加的 CDB 命令行参数
+    pub extra_args: Option<Vec<String>>,
+}
+
+/// [policy] 段
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicySection {
+    /// 内置策略配置（`unrestricted` 或 `safe`）
+    pub profile: Option<PolicyProfile>,
+    /// 允许的命令模式
+    pub allow: Option<Vec<String>>,
+    /// 拒绝的命令模式
+    pub deny: Option<Vec<String>>,
+    /// 是否所有远程会话都是只读的
+    pub read_only_remote: Option<bool>,
+}
+
+impl FileConfig {
+    /// 从文件加载配置
+    ///
+    /// # 参数
+    /// * `path` - 配置文件路径
+    ///
+    /// # 错误
+    /// 如果文件无法读取、语法错误、包含未知键或值无效，返回错误
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| {
+            if source.kind() == std::io::ErrorKind::NotFound {
+                ConfigError::FileNotFound(path.to_path_buf())
+            } else {
+                ConfigError::ReadFailed {
+                    path: path.to_path_buf(),
+                    source,
+                }
+            }
+        })?;
+
+        Self::parse(&text).map_err(|e| match e {
+            ConfigError::ParseFailed { message, .. } => ConfigError::ParseFailed {
+                path: path.to_path_buf(),
+                message,
+            },
+            other => other,
+        })
+    }
+
+    /// 解析配置文本
+    ///
+    /// # 错误
+    /// 如果语法错误、包含未知键或值无效，返回错误
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(text).map_err(|e| ConfigError::ParseFailed {
+            path: PathBuf::new(),
+            message: e.to_string(),
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 校验配置值
+    fn validate(&self) -> Result<(), ConfigError> {
+        let non_zero = [
+            ("server.timeout_seconds", self.server.timeout_seconds),
+            (
+                "server.init_timeout_seconds",
+                self.server.init_timeout_seconds,
+            ),
+            (
+                "server.session_idle_ttl_seconds",
+                self.server.session_idle_ttl_seconds,
+            ),
+            ("server.max_sessions", self.server.max_sessions),
+        ];
+        for (key, value) in non_zero {
+            if value == Some(0) {
+                return Err(ConfigError::InvalidValue {
+                    key: key.to_string(),
+                    message: "must be greater than 0".to_string(),
+                });
+            }
+        }
+
+        if let Some(listen) = &self.server.listen {
+            if let Err(e) = listen.parse::<SocketAddr>() {
+                return Err(ConfigError::InvalidValue {
+                    key: "server.listen".to_string(),
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        if matches!(&self.server.auth_token, Some(token) if token.trim().is_empty()) {
+            return Err(ConfigError::InvalidValue {
+                key: "server.auth_token".to_string(),
+                message: "must not be empty".to_string(),
+            });
+        }
+
+        let patterns = [
+            ("policy.allow", &self.policy.allow),
+            ("policy.deny", &self.policy.deny),
+        ];
+        for (key, patterns) in patterns {
+            if patterns.iter().flatten().any(|p| p.trim().is_empty()) {
+                return Err(ConfigError::InvalidValue {
+                    key: key.to_string(),
+                    message: "patterns must not be empty".to_string(),
+                });
+            }
+        }
+
+        for (pattern, secs) in &self.command_timeouts {
+            let message = if pattern.trim().is_empty() {
+                "patterns must not be empty"
+            } else if *secs == 0 {
+                "must be greater than 0"
+            } else {
+                continue;
+            };
+            return Err(ConfigError::InvalidValue {
+                key: format!("command_timeouts.\"{}\"", pattern),
+                message: message.to_string(),
+            });
+        }
+
+        if matches!(&self.cdb.path, Some(path) if path.as_os_str().is_empty()) {
+            return Err(ConfigError::InvalidValue {
+                key: "cdb.path".to_string(),
+                message: "must not be empty".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 将配置文件中的值应用到服务器配置
+    pub fn apply(&self, config: &mut ServerConfig) {
+        if let Some(secs) = self.server.timeout_seconds {
+            config.timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.server.init_timeout_seconds {
+            config.init_timeout = Duration::from_secs(secs);
+        }
+        if let Some(verbose) = self.server.verbose {
+            config.verbose = verbose;
+        }
+        if let Some(transport) = self.server.transport {
+            config.transport = transport;
+        }
+        if let Some(listen) = self.server.listen.as_deref().and_then(|s| s.parse().ok()) {
+            config.listen = listen;
+        }
+        if let Some(token) = &self.server.auth_token {
+            config.auth_token = Some(token.clone());
+        }
+        if let Some(path) = &self.server.auth_token_file {
+            config.auth_token_file = Some(path.clone());
+        }
+        if let Some(secs) = self.server.session_idle_ttl_seconds {
+            config.session_idle_ttl = Some(Duration::from_secs(secs));
+        }
+        if let Some(max_sessions) = self.server.max_sessions {
+            config.max_sessions = Some(max_sessions as usize);
+        }
+        if let Some(path) = &self.cdb.path {
+            config.cdb_path = Some(path.clone());
+        }
+        if let Some(symbols_path) = &self.cdb.symbols_path {
+            config.symbols_path = Some(symbols_path.clone());
+        }
+        if let Some(extra_args) = &self.cdb.extra_args {
+            config.cdb_args = extra_args.clone();
+        }
+        if let Some(profile) = self.policy.profile {
+            config.policy.profile = profile;
+        }
+        if let Some(allow) = &self.policy.allow {
+            config.policy.allow = allow.clone();
+        }
+        if let Some(deny) = &self.policy.deny {
+            config.policy.deny = deny.clone();
+        }
+        if let Some(read_only) = self.policy.read_only_remote {
+            config.read_only_remote = read_only;
+        }
+        if !self.command_timeouts.is_empty() {
+            config.command_timeouts.overrides = self
+                .command_timeouts
+                .iter()
+                .map(|(pattern, secs)| (pattern.clone(), Duration::from_secs(*secs)))
+                .collect();
+        }
+    }
+}
+
+/// 查找默认配置文件
+///
+/// 只检查用户配置目录
+/// （Windows 上为 `%APPDATA%\mcp-windbg-rs`，其他平台为 `$XDG_CONFIG_HOME/mcp-windbg-rs` 或 `~/.config/mcp-windbg-rs`）。
+/// 不读取当前目录中的 `config.toml`：配置可以指定 `cdb.path` 和 `extra_args`，
+/// 在不受信任的目录（例如他人的代码库或转储目录）中启动服务器时会执行其中指定的程序。
+/// 其他位置的配置文件需要通过 `--config` 显式指定。
+///
+/// # 返回
+/// 如果用户配置目录中存在配置文件，返回其路径；否则返回 None
+pub fn default_config_path() -> Option<PathBuf> {
+    user_config_path(|key| std::env::var_os(key)).filter(|path| path.is_file())
+}
+
+/// 用户配置目录中的配置文件路径（环境变量通过 `var` 读取）
+fn user_config_path(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let config_dir = if cfg!(windows) {
+        var("APPDATA").map(PathBuf::from)
+    } else {
+        var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    config_dir
+        // 相对路径同样取决于当前目录
+        .filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join("mcp-windbg-rs").join(CONFIG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_example_config() {
+        let text = include_str!("../config.example.toml");
+        let config = FileConfig::parse(text).unwrap();
+        assert_eq!(config.server.timeout_seconds, Some(30));
+        assert_eq!(config.server.verbose, Some(false));
+        assert!(config.cdb.path.is_none());
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let text = r#"
+            [server]
+            timeout_seconds = 60
+            init_timeout_seconds = 300
+            verbose = true
+            transport = "http"
+            listen = "0.0.0.0:9000"
+            auth_token_file = 'C:\ProgramData\mcp-windbg-rs\token'
+            session_idle_ttl_seconds = 900
+            max_sessions = 4
+
+            [cdb]
+            path = 'C:\Debuggers\cdb.exe'
+            symbols_path = "SRV*C:\\Symbols*https://msdl.microsoft.com/download/symbols"
+            extra_args = ["-lines", "-n"]
+
+            [policy]
+            profile = "safe"
+            deny = [".reload /f*"]
+            read_only_remote = true
+
+            [command_timeouts]
+            "!analyze*" = 900
+            "!address*" = 120
+        "#;
+        let file = FileConfig::parse(text).unwrap();
+
+        let mut config = ServerConfig::default();
+        file.apply(&mut config);
+        assert_eq!(config.timeout, Duration::from_secs(60));
+        assert_eq!(config.init_timeout, Duration::from_secs(300));
+        assert!(config.verbose);
+        assert_eq!(config.transport, Transport::Http);
+        assert_eq!(config.listen.to_string(), "0.0.0.0:9000");
+        assert_eq!(
+            config.auth_token_file,
+            Some(PathBuf::from(r"C:\ProgramData\mcp-windbg-rs\token"))
+        );
+        assert_eq!(config.session_idle_ttl, Some(Duration::from_secs(900)));
+        assert_eq!(config.max_sessions, Some(4));
+        assert_eq!(
+            config.cdb_path,
+            Some(PathBuf::from(r"C:\Debuggers\cdb.exe"))
+        );
+        assert!(config.symbols_path.unwrap().starts_with("SRV*"));
+        assert_eq!(
+            config.cdb_args,
+            vec!["-lines".to_string(), "-n".to_string()]
+        );
+        assert_eq!(config.policy.profile, PolicyProfile::Safe);
+        assert_eq!(config.policy.deny, vec![".reload /f*".to_string()]);
+        assert!(config.policy.allow.is_empty());
+        assert!(config.read_only_remote);
+        assert_eq!(
+            config.command_timeouts.overrides,
+            vec![
+                ("!address*".to_string(), Duration::from_secs(120)),
+                ("!analyze*".to_string(), Duration::from_secs(900)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_is_named() {
+        let err = FileConfig::parse("[server]\ntimeout = 10\n").unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, ConfigError::ParseFailed { .. }));
+        assert!(message.contains("unknown field `timeout`"), "{}", message);
+    }
+
+    #[test]
+    fn test_unknown_section_is_named() {
+        let err = FileConfig::parse("[debugger]\npath = 'x'\n").unwrap_err();
+        assert!(err.to_string().contains("unknown field `debugger`"));
+    }
+
+    #[test]
+    fn test_zero_timeout_rejected() {
+        let err = FileConfig::parse("[server]\ntimeout_seconds = 0\n").unwrap_err();
+        match err {
+            ConfigError::InvalidValue { key, .. } => assert_eq!(key, "server.timeout_seconds"),
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_transport_and_listen_rejected() {
+        let err = FileConfig::parse("[server]\ntransport = 'sse'\n").unwrap_err();
+        assert!(err.to_string().contains("unknown variant `sse`"), "{}", err);
+
+        let err = FileConfig::parse("[server]\nlisten = 'localhost'\n").unwrap_err();
+        match err {
+            ConfigError::InvalidValue { key, .. } => assert_eq!(key, "server.listen"),
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+
+        let err = FileConfig::parse("[server]\nauth_token = ''\n").unwrap_err();
+        assert!(err.to_string().contains("server.auth_token"));
+    }
+
+    #[test]
+    fn test_invalid_policy_rejected() {
+        let err = FileConfig::parse("[policy]\nprofile = 'strict'\n").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown variant `strict`"),
+            "{}",
+            err
+        );
+
+        let err = FileConfig::parse("[policy]\nallow = ['k', ' ']\n").unwrap_err();
+        assert!(err.to_string().contains("policy.allow"));
+    }
+
+    #[test]
+    fn test_invalid_command_timeouts_rejected() {
+        let err = FileConfig::parse("[command_timeouts]\n\"!heap*\" = 0\n").unwrap_err();
+        match err {
+            ConfigError::InvalidValue { key, message } => {
+                assert_eq!(key, "command_timeouts.\"!heap*\"");
+                assert_eq!(message, "must be greater than 0");
+            }
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+
+        let err = FileConfig::parse("[command_timeouts]\n\" \" = 10\n").unwrap_err();
+        assert!(
+            err.to_string().contains("patterns must not be empty"),
+            "{}",
+            err
+        );
+
+        let err = FileConfig::parse("[command_timeouts]\n\"!heap*\" = 'long'\n").unwrap_err();
+        assert!(matches!(err, ConfigError::ParseFailed { .. }));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let err = FileConfig::load(Path::new("nonexistent_config.toml")).unwrap_err();
+        assert!(matches!(err, ConfigError::FileNotFound(_)));
+    }
+
+    #[test]
+    fn test_load_reports_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[cdb]\nsymbol_path = 'x'\n").unwrap();
+
+        let err = FileConfig::load(&path).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("config.toml"));
+        assert!(message.contains("unknown field `symbol_path`"));
+    }
+
+    #[test]
+    fn test_user_config_path() {
+        let lookup = |vars: Vec<(&'static str, &'static str)>| {
+            move |key: &str| {
+                vars.iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+
+        let dir = if cfg!(windows) {
+            r"C:\Users\me\AppData\Roaming"
+        } else {
+            "/home/me/.config"
+        };
+        assert_eq!(
+            user_config_path(lookup(vec![("APPDATA", dir), ("XDG_CONFIG_HOME", dir)])),
+            Some(Path::new(dir).join("mcp-windbg-rs").join(CONFIG_FILE_NAME))
+        );
+
+        // 没有用户配置目录或目录是相对路径时不回退到当前目录
+        assert_eq!(user_config_path(lookup(Vec::new())), None);
+        assert_eq!(
+            user_config_path(lookup(vec![
+                ("APPDATA", "."),
+                ("XDG_CONFIG_HOME", "."),
+                ("HOME", "."),
+            ])),
+            None
+        );
+    }
+}
@@ -19,8 +19,17 @@ pub enum CdbError {
     ProcessStartFailed(String),
 
     /// 命令执行超时
-    #[error("Command timeout after {0:?}")]
-    CommandTimeout(Duration),
+    #[error("Command timeout after {timeout:?}{}", format_partial_output(.partial_output))]
+    CommandTimeout {
+        /// 超时时间
+        timeout: Duration,
+        /// 超时前已收集的输出行
+        partial_output: Vec<String>,
+    },
+
+    /// 命令被客户端取消（CDB 已中断该命令）
+    #[error("Command cancelled")]
+    Cancelled,
 
     /// 向 CDB 进程发送命令失败
     #[error("Failed to send command: {0}")]
@@ -35,6 +44,34 @@ pub enum CdbError {
     IoError(#[from] std::io::Error),
 }
 
+impl CdbError {
+    /// 创建没有部分输出的超时错误
+    pub fn timeout(timeout: Duration) -> Self {
+        CdbError::CommandTimeout {
+            timeout,
+            partial_output: Vec::new(),
+        }
+    }
+}
+
+/// 超时错误信息中最多显示的部分输出行数（保留最后几行）
+const PARTIAL_OUTPUT_DISPLAY_LINES: usize = 50;
+
+/// 格式化超时前已收集的输出，附加到错误信息中
+fn format_partial_output(lines: &[String]) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    let skipped = lines.len().saturating_sub(PARTIAL_OUTPUT_DISPLAY_LINES);
+    let mut text = format!("; partial output ({} lines", lines.len());
+    if skipped > 0 {
+        text.push_str(&format!(", first {} omitted", skipped));
+    }
+    text.push_str("):\n");
+    text.push_str(&lines[skipped..].join("\n"));
+    text
+}
+
 /// 会话管理期间可能发生的错误
 #[derive(Debug, Error)]
 pub enum SessionError {
@@ -53,6 +90,32 @@ pub enum SessionError {
     /// 会话 ID 格式无效
     #[error("Invalid session ID: {0}")]
     InvalidSessionId(String),
+
+    /// 调试器进程退出后重新启动会话失败
+    #[error("Failed to restart session {session_id} after {attempts} attempts: {source}")]
+    RestartFailed {
+        /// 会话 ID
+        session_id: String,
+        /// 已尝试的次数
+        attempts: u32,
+        /// 最后一次启动错误
+        #[source]
+        source: CdbError,
+    },
+
+    /// 调试器进程在滑动窗口内退出次数过多，会话不再自动重新启动
+    #[error(
+        "Debugger for session {session_id} exited {restarts} times within {}s and will not be restarted again; close the session to reset it",
+        window.as_secs()
+    )]
+    RestartLimitReached {
+        /// 会话 ID
+        session_id: String,
+        /// 窗口内的重新启动次数上限
+        restarts: u32,
+        /// 滑动窗口
+        window: Duration,
+    },
 }
 
 /// 处理 MCP 工具调用时可能发生的错误
@@ -77,6 +140,67 @@ pub enum ToolError {
     /// 文件系统错误
     #[error("File system error: {0}")]
     FileSystemError(#[from] std::io::Error),
+
+    /// 读取 minidump 文件失败
+    #[error("Minidump error: {0}")]
+    MinidumpError(#[from] MinidumpError),
+
+    /// 命令被命令策略拒绝
+    #[error("Command rejected by policy: `{command}` {reason}")]
+    PolicyViolation { command: String, reason: String },
+}
+
+impl ToolError {
+    /// 是否因为找不到 CDB 可执行文件而失败
+    ///
+    /// 此时可以改用纯 Rust 的 minidump 读取器回答部分问题。
+    pub fn is_cdb_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::CdbError(CdbError::ExecutableNotFound)
+                | Self::SessionError(SessionError::CreationFailed(CdbError::ExecutableNotFound))
+        )
+    }
+}
+
+/// 加载配置文件时可能发生的错误
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// 通过 --config 指定的配置文件不存在
+    #[error("Config file not found: {0}")]
+    FileNotFound(PathBuf),
+
+    /// 读取配置文件失败
+    #[error("Failed to read config file {path}: {source}")]
+    ReadFailed {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// 配置文件语法错误或包含未知键
+    #[error("Invalid config file {path}: {message}")]
+    ParseFailed { path: PathBuf, message: String },
+
+    /// 配置项的值无效
+    #[error("Invalid value for `{key}`: {message}")]
+    InvalidValue { key: String, message: String },
+}
+
+/// 读取 minidump 文件时可能发生的错误
+#[derive(Debug, Error)]
+pub enum MinidumpError {
+    /// 文件签名不是 `MDMP`
+    #[error("Not a minidump file (signature {0:#010x})")]
+    InvalidSignature(u32),
+
+    /// 数据不完整（结构或流超出文件末尾）
+    #[error("Truncated minidump data: {0}")]
+    Truncated(&'static str),
+
+    /// 读取文件时发生 I/O 错误
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// MCP 服务器中可能发生的错误
@@ -97,6 +221,10 @@ pub enum ServerError {
     /// 工具执行错误
     #[error("Tool error: {0}")]
     ToolError(#[from] ToolError),
+
+    /// 配置错误（例如无法读取令牌文件）
+    #[error("Configuration error: {0}")]
+    ConfigError(#[from] ConfigError),
 }
 
 #[cfg(test)]
@@ -108,8 +236,56 @@ mod tests {
         let err = CdbError::ExecutableNotFound;
         assert_eq!(err.to_string(), "CDB executable not found");
 
-        let err = CdbError::CommandTimeout(Duration::from_secs(30));
+        let err = CdbError::timeout(Duration::from_secs(30));
         assert_eq!(err.to_string(), "Command timeout after 30s");
+
+        let err = CdbError::CommandTimeout {
+            timeout: Duration::from_secs(30),
+            partial_output: vec!["line 1".to_string(), "line 2".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Command timeout after 30s; partial output (2 lines):\nline 1\nline 2"
+        );
+
+        let err = CdbError::CommandTimeout {
+            timeout: Duration::from_secs(30),
+            partial_output: (0..60).map(|i| format!("line {}", i)).collect(),
+        };
+        let message = err.to_string();
+        assert!(
+            message.contains("(60 lines, first 10 omitted)"),
+            "{}",
+            message
+        );
+        assert!(message.contains("):\nline 10\n"), "{}", message);
+        assert!(message.ends_with("\nline 59"), "{}", message);
+
+        assert_eq!(CdbError::Cancelled.to_string(), "Command cancelled");
+    }
+
+    #[test]
+    fn test_config_error_display() {
+        let err = ConfigError::InvalidValue {
+            key: "server.timeout_seconds".to_string(),
+            message: "must be greater than 0".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for `server.timeout_seconds`: must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_minidump_error_display() {
+        let err = MinidumpError::InvalidSignature(0x4547_4150);
+        assert_eq!(
+            err.to_string(),
+            "Not a minidump file (signature 0x45474150)"
+        );
+
+        let err = MinidumpError::Truncated("stream directory");
+        assert_eq!(err.to_string(), "Truncated minidump data: stream directory");
     }
 
     #[test]
@@ -126,6 +302,22 @@ mod tests {
         assert!(matches!(tool_err, ToolError::SessionError(_)));
     }
 
+    #[test]
+    fn test_tool_error_cdb_unavailable() {
+        let err: ToolError = SessionError::CreationFailed(CdbError::ExecutableNotFound).into();
+        assert!(err.is_cdb_unavailable());
+        assert!(ToolError::CdbError(CdbError::ExecutableNotFound).is_cdb_unavailable());
+
+        let err: ToolError = SessionError::CreationFailed(CdbError::ProcessTerminated).into();
+        assert!(!err.is_cdb_unavailable());
+
+        let err: ToolError = MinidumpError::Truncated("module list stream").into();
+        assert_eq!(
+            err.to_string(),
+            "Minidump error: Truncated minidump data: module list stream"
+        );
+    }
+
     #[test]
     fn test_server_error_from_tool_error() {
         let tool_err = ToolError::InvalidParams("test param".to_string());
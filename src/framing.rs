@@ -0,0 +1,210 @@
+//! CDB 命令分帧模块
+//!
+//! 每条命令前后各发送一条 `.echo` 哨兵（`MCPWDBG_<随机数>_<序号>_BEGIN` / `_END`），
+//! 输出按哨兵切分。哨兵包含会话级随机数和单调递增的序号，因此：
+//! * 同一时刻发送的两条命令不会共用同一个标记；
+//! * 命令输出中即使出现类似的文本，也无法伪造当前命令的哨兵；
+//! * 超时命令的残留输出位于下一条命令的开始哨兵之前，可以整体丢弃。
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 哨兵前缀
+const SENTINEL_PREFIX: &str = "MCPWDBG";
+
+/// 输出行中识别出的哨兵
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sentinel {
+    /// 不是本会话的哨兵
+    None,
+    /// 启动完成哨兵
+    Ready,
+    /// 指定序号命令的开始哨兵
+    Begin(u64),
+    /// 指定序号命令的结束哨兵
+    End(u64),
+}
+
+/// 一条命令的帧
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// 命令序号（会话内单调递增）
+    pub id: u64,
+    begin: String,
+    end: String,
+}
+
+impl Frame {
+    /// 生成发送给 CDB 的完整输入：开始哨兵、命令、结束哨兵
+    ///
+    /// # 参数
+    /// * `command` - 调试器命令（首尾空白会被去除）
+    pub fn wrap(&self, command: &str) -> String {
+        format!(
+            ".echo {}\n{}\n.echo {}\n",
+            self.begin,
+            command.trim(),
+            self.end
+        )
+    }
+}
+
+/// 命令分帧器
+///
+/// 每个 CDB 会话持有一个分帧器，负责生成哨兵并识别输出中的哨兵行。
+#[derive(Debug, Clone)]
+pub struct CommandFramer {
+    /// 会话级随机数
+    nonce: String,
+    /// 下一条命令的序号
+    next_id: u64,
+}
+
+impl Default for CommandFramer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandFramer {
+    /// 创建使用随机数的分帧器
+    pub fn new() -> Self {
+        Self::with_nonce(random_nonce())
+    }
+
+    /// 创建使用指定随机数的分帧器
+    ///
+    /// # 参数
+    /// * `nonce` - 哨兵中的随机部分（只能包含字母和数字）
+    pub fn with_nonce(nonce: impl Into<String>) -> Self {
+        Self {
+            nonce: nonce.into(),
+            next_id: 1,
+        }
+    }
+
+    /// 启动完成哨兵（通过 `-c ".echo <哨兵>"` 在启动时输出）
+    pub fn ready_marker(&self) -> String {
+        format!("{}_{}_READY", SENTINEL_PREFIX, self.nonce)
+    }
+
+    /// 为下一条命令分配帧
+    pub fn next_frame(&mut self) -> Frame {
+        let id = self.next_id;
+        self.next_id += 1;
+        let prefix = format!("{}_{}_{}", SENTINEL_PREFIX, self.nonce, id);
+        Frame {
+            id,
+            begin: format!("{}_BEGIN", prefix),
+            end: format!("{}_END", prefix),
+        }
+    }
+
+    /// 识别输出行中的哨兵
+    ///
+    /// 哨兵必须独占一行（前面只允许出现 CDB 提示符，例如 `0:000> `），
+    /// 因此命令输出中间夹带的哨兵文本不会被误认。
+    ///
+    /// # 参数
+    /// * `line` - CDB 输出行
+    pub fn classify(&self, line: &str) -> Sentinel {
+        let line = line.trim();
+        let Some(start) = line.find(SENTINEL_PREFIX) else {
+            return Sentinel::None;
+        };
+        let (prompt, sentinel) = line.split_at(start);
+        if !prompt.is_empty() && !prompt.ends_with("> ") {
+            return Sentinel::None;
+        }
+
+        let Some(rest) = sentinel
+            .strip_prefix(SENTINEL_PREFIX)
+            .and_then(|s| s.strip_prefix('_'))
+            .and_then(|s| s.strip_prefix(self.nonce.as_str()))
+            .and_then(|s| s.strip_prefix('_'))
+        else {
+            return Sentinel::None;
+        };
+        if rest == "READY" {
+            return Sentinel::Ready;
+        }
+
+        let Some((id, kind)) = rest.split_once('_') else {
+            return Sentinel::None;
+        };
+        match (id.parse(), kind) {
+            (Ok(id), "BEGIN") => Sentinel::Begin(id),
+            (Ok(id), "END") => Sentinel::End(id),
+            _ => Sentinel::None,
+        }
+    }
+}
+
+/// 生成哨兵随机数（16 位十六进制）
+///
+/// `RandomState` 每次创建时使用不同的随机种子，再混入进程 ID 和当前时间。
+fn random_nonce() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_are_unique_and_monotonic() {
+        let mut framer = CommandFramer::with_nonce("abc123");
+        let first = framer.next_frame();
+        let second = framer.next_frame();
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_ne!(first.wrap("k"), second.wrap("k"));
+        assert_eq!(
+            first.wrap("  kb  "),
+            ".echo MCPWDBG_abc123_1_BEGIN\nkb\n.echo MCPWDBG_abc123_1_END\n"
+        );
+
+        // 不同会话的随机数不同
+        assert_ne!(CommandFramer::new().nonce, CommandFramer::new().nonce);
+        assert_eq!(CommandFramer::new().nonce.len(), 16);
+    }
+
+    #[test]
+    fn test_classify_sentinels() {
+        let framer = CommandFramer::with_nonce("abc123");
+        assert_eq!(
+            framer.classify("MCPWDBG_abc123_7_BEGIN"),
+            Sentinel::Begin(7)
+        );
+        assert_eq!(
+            framer.classify("0:000> MCPWDBG_abc123_7_END\r\n"),
+            Sentinel::End(7)
+        );
+        assert_eq!(
+            framer.classify("1: kd> MCPWDBG_abc123_READY"),
+            Sentinel::Ready
+        );
+        assert_eq!(framer.classify(&framer.ready_marker()), Sentinel::Ready);
+
+        // 其他会话的哨兵、夹在输出中的哨兵文本和普通输出都不是哨兵
+        for line in [
+            "MCPWDBG_zzz999_7_END",
+            "echo: MCPWDBG_abc123_7_END",
+            "MCPWDBG_abc123_7_END trailing",
+            "MCPWDBG_abc123_x_END",
+            "MCPWDBG_abc123_7_MIDDLE",
+            "0:000> k",
+            "",
+        ] {
+            assert_eq!(framer.classify(line), Sentinel::None, "{}", line);
+        }
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! 本库提供了通过 Model Context Protocol 分析 Windows 崩溃转储和执行远程调试的核心功能。
 
+pub mod auth;
+pub mod backend;
 pub mod cdb;
+pub mod config;
 pub mod error;
+pub mod framing;
+pub mod minidump;
+pub mod parser;
+pub mod policy;
 pub mod server;
 pub mod session;
+pub mod timeouts;
 pub mod tools;
+pub mod triage;
 pub mod types;
 pub mod utils;
@@ -1,22 +1,67 @@
 use clap::Parser;
-use mcp_windbg_rs::server::{McpServer, ServerConfig};
+use mcp_windbg_rs::config;
+use mcp_windbg_rs::policy::PolicyProfile;
+use mcp_windbg_rs::server::{McpServer, ServerConfig, Transport};
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::info;
 
 /// MCP WinDbg 服务器 - Windows 崩溃转储分析工具
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// 命令执行超时时间（秒）
-    #[arg(long, default_value = "30")]
-    timeout: u64,
+    /// 配置文件路径（默认只查找用户配置目录，不读取当前目录中的 config.toml）
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
 
-    /// 初始化超时时间（秒）
-    #[arg(long, default_value = "120")]
-    init_timeout: u64,
+    /// 命令执行超时时间（秒，默认 30）
+    #[arg(long, value_parser = parse_non_zero_secs)]
+    timeout: Option<u64>,
+
+    /// 初始化超时时间（秒，默认 120）
+    #[arg(long, value_parser = parse_non_zero_secs)]
+    init_timeout: Option<u64>,
 
     /// 启用详细日志
     #[arg(long, default_value = "false")]
     verbose: bool,
+
+    /// MCP 传输方式（stdio 或 http，默认 stdio）
+    #[arg(long, value_name = "stdio|http")]
+    transport: Option<Transport>,
+
+    /// HTTP 传输的监听地址（默认 127.0.0.1:8765）
+    #[arg(long, value_name = "ADDR")]
+    listen: Option<SocketAddr>,
+
+    /// 保存 HTTP 传输 Bearer 令牌的文件
+    #[arg(long, value_name = "PATH")]
+    auth_token_file: Option<PathBuf>,
+
+    /// run_windbg_cmd 的内置命令策略（unrestricted 或 safe）
+    #[arg(long, value_name = "unrestricted|safe")]
+    policy: Option<PolicyProfile>,
+
+    /// 所有远程会话以只读模式打开
+    #[arg(long, default_value = "false")]
+    read_only: bool,
+
+    /// 空闲会话的存活时间（秒，0 表示不回收）
+    #[arg(long, value_name = "SECS")]
+    session_idle_ttl: Option<u64>,
+
+    /// 最大会话数，超过时关闭最久未使用的会话（0 表示不限制）
+    #[arg(long, value_name = "N")]
+    max_sessions: Option<usize>,
+}
+
+/// 解析大于 0 的秒数（0 会使每条命令立即超时）
+fn parse_non_zero_secs(value: &str) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(0) => Err("must be greater than 0".to_string()),
+        Ok(secs) => Ok(secs),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 #[tokio::main]
@@ -24,9 +69,47 @@ async fn main() -> anyhow::Result<()> {
     // 解析命令行参数
     let args = Args::parse();
 
+    // 读取配置文件和环境变量（配置文件 < 环境变量）
+    let config_path = args.config.clone().or_else(config::default_config_path);
+    let mut config = ServerConfig::load(config_path.as_deref())?;
+
+    // 命令行参数覆盖配置
+    if let Some(timeout) = args.timeout {
+        config.timeout = std::time::Duration::from_secs(timeout);
+    }
+    if let Some(init_timeout) = args.init_timeout {
+        config.init_timeout = std::time::Duration::from_secs(init_timeout);
+    }
+    if args.verbose {
+        config.verbose = true;
+    }
+    if let Some(transport) = args.transport {
+        config.transport = transport;
+    }
+    if let Some(listen) = args.listen {
+        config.listen = listen;
+    }
+    if let Some(path) = args.auth_token_file {
+        // 命令行指定的令牌文件优先于配置文件和环境变量中的令牌
+        config.auth_token = None;
+        config.auth_token_file = Some(path);
+    }
+    if let Some(profile) = args.policy {
+        config.policy.profile = profile;
+    }
+    if args.read_only {
+        config.read_only_remote = true;
+    }
+    if let Some(ttl) = args.session_idle_ttl {
+        config.session_idle_ttl = (ttl > 0).then(|| std::time::Duration::from_secs(ttl));
+    }
+    if let Some(max_sessions) = args.max_sessions {
+        config.max_sessions = (max_sessions > 0).then_some(max_sessions);
+    }
+
     // 初始化 tracing 日志订阅器
     // 重要：日志必须输出到 stderr，因为 stdout 用于 MCP JSON-RPC 通信
-    let log_level = if args.verbose { "debug" } else { "info" };
+    let log_level = if config.verbose { "debug" } else { "info" };
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr) // 强制输出到 stderr
         .with_env_filter(
@@ -37,14 +120,9 @@ async fn main() -> anyhow::Result<()> {
 
     info!("MCP WinDbg Server starting...");
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
-
-    // 从环境变量读取配置
-    let mut config = ServerConfig::from_env();
-
-    // 命令行参数覆盖配置
-    config.timeout = std::time::Duration::from_secs(args.timeout);
-    config.init_timeout = std::time::Duration::from_secs(args.init_timeout);
-    config.verbose = args.verbose;
+    if let Some(path) = &config_path {
+        info!("Config file: {}", path.display());
+    }
 
     // 创建并启动服务器
     let server = McpServer::new(config);
@@ -63,7 +141,7 @@ async fn main() -> anyhow::Result<()> {
     });
 
     // 运行服务器
-    // serve_server 应该持续运行，如果它返回了说明连接关闭
+    // stdio 传输在连接关闭时返回；http 传输持续运行直到收到关闭信号
     match server.run().await {
         Ok(_) => {
             info!("MCP server shut down normally");
@@ -76,3 +154,23 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_timeouts_rejected() {
+        for flag in ["--timeout", "--init-timeout"] {
+            let err = Args::try_parse_from(["mcp-windbg-rs", flag, "0"]).unwrap_err();
+            assert!(
+                err.to_string().contains("must be greater than 0"),
+                "{}",
+                err
+            );
+        }
+
+        let args = Args::try_parse_from(["mcp-windbg-rs", "--timeout", "60"]).unwrap();
+        assert_eq!(args.timeout, Some(60));
+    }
+}
@@ -0,0 +1,228 @@
+//! 线程上下文（CONTEXT 结构）解析
+//!
+//! 将 x86、x64 和 ARM64 的 CONTEXT 转换为与 `r` 命令解析结果相同的 [`RegisterContext`]。
+
+use super::Bytes;
+use crate::error::MinidumpError;
+use crate::parser::registers::{Architecture, RegisterContext};
+
+/// x86 CONTEXT 的大小
+const CONTEXT_X86_SIZE: usize = 0x2cc;
+
+/// x64 CONTEXT 的大小
+const CONTEXT_X64_SIZE: usize = 0x4d0;
+
+/// ARM64 CONTEXT 的大小
+const CONTEXT_ARM64_SIZE: usize = 0x390;
+
+/// x64 通用寄存器及其偏移
+const X64_REGISTERS: &[(&str, usize)] = &[
+    ("rax", 0x78),
+    ("rcx", 0x80),
+    ("rdx", 0x88),
+    ("rbx", 0x90),
+    ("rsp", 0x98),
+    ("rbp", 0xa0),
+    ("rsi", 0xa8),
+    ("rdi", 0xb0),
+    ("r8", 0xb8),
+    ("r9", 0xc0),
+    ("r10", 0xc8),
+    ("r11", 0xd0),
+    ("r12", 0xd8),
+    ("r13", 0xe0),
+    ("r14", 0xe8),
+    ("r15", 0xf0),
+    ("rip", 0xf8),
+];
+
+/// x64 段寄存器（16 位）及其偏移
+const X64_SEGMENTS: &[(&str, usize)] = &[
+    ("cs", 0x38),
+    ("ds", 0x3a),
+    ("es", 0x3c),
+    ("fs", 0x3e),
+    ("gs", 0x40),
+    ("ss", 0x42),
+];
+
+/// x86 通用寄存器（32 位）及其偏移
+const X86_REGISTERS: &[(&str, usize)] = &[
+    ("edi", 0x9c),
+    ("esi", 0xa0),
+    ("ebx", 0xa4),
+    ("edx", 0xa8),
+    ("ecx", 0xac),
+    ("eax", 0xb0),
+    ("ebp", 0xb4),
+    ("eip", 0xb8),
+    ("esp", 0xc4),
+];
+
+/// x86 段寄存器（32 位字段）及其偏移
+const X86_SEGMENTS: &[(&str, usize)] = &[
+    ("gs", 0x8c),
+    ("fs", 0x90),
+    ("es", 0x94),
+    ("ds", 0x98),
+    ("cs", 0xbc),
+    ("ss", 0xc8),
+];
+
+/// 解析线程上下文
+///
+/// # 参数
+/// * `data` - CONTEXT 结构数据
+/// * `architecture` - 目标架构（SystemInfoStream）；未知时根据结构大小推断
+///
+/// # 返回
+/// 无法确定架构时返回 None
+///
+/// # 错误
+/// 如果数据短于对应架构的 CONTEXT 结构，返回错误
+pub fn parse_context(
+    data: &[u8],
+    architecture: Architecture,
+) -> Result<Option<RegisterContext>, MinidumpError> {
+    let architecture = match architecture {
+        Architecture::Unknown => match data.len() {
+            CONTEXT_X64_SIZE => Architecture::X64,
+            CONTEXT_X86_SIZE => Architecture::X86,
+            CONTEXT_ARM64_SIZE => Architecture::Arm64,
+            _ => return Ok(None),
+        },
+        known => known,
+    };
+
+    let bytes = Bytes::new(data, "thread context");
+    let mut context = RegisterContext {
+        architecture,
+        ..Default::default()
+    };
+
+    match architecture {
+        Architecture::X64 => {
+            for &(name, offset) in X64_REGISTERS {
+                context
+                    .registers
+                    .insert(name.to_string(), bytes.u64(offset)?);
+            }
+            for &(name, offset) in X64_SEGMENTS {
+                context
+                    .segments
+                    .insert(name.to_string(), u64::from(bytes.u16(offset)?));
+            }
+            context.flags.value = Some(u64::from(bytes.u32(0x44)?));
+        }
+        Architecture::X86 => {
+            for &(name, offset) in X86_REGISTERS {
+                context
+                    .registers
+                    .insert(name.to_string(), u64::from(bytes.u32(offset)?));
+            }
+            for &(name, offset) in X86_SEGMENTS {
+                context
+                    .segments
+                    .insert(name.to_string(), u64::from(bytes.u32(offset)?));
+            }
+            context.flags.value = Some(u64::from(bytes.u32(0xc0)?));
+        }
+        Architecture::Arm64 => {
+            for i in 0..29 {
+                context
+                    .registers
+                    .insert(format!("x{}", i), bytes.u64(0x08 + i * 8)?);
+            }
+            for (name, offset) in [("fp", 0xf0), ("lr", 0xf8), ("sp", 0x100), ("pc", 0x108)] {
+                context
+                    .registers
+                    .insert(name.to_string(), bytes.u64(offset)?);
+            }
+            context.flags.value = Some(u64::from(bytes.u32(0x04)?));
+        }
+        Architecture::Unknown => return Ok(None),
+    }
+
+    let (ip, sp, fp) = match architecture {
+        Architecture::X64 => ("rip", "rsp", "rbp"),
+        Architecture::X86 => ("eip", "esp", "ebp"),
+        _ => ("pc", "sp", "fp"),
+    };
+    context.instruction_pointer = context.registers.get(ip).copied();
+    context.stack_pointer = context.registers.get(sp).copied();
+    context.frame_pointer = context.registers.get(fp).copied();
+
+    Ok(Some(context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn test_parse_x64_context() {
+        let mut data = vec![0u8; CONTEXT_X64_SIZE];
+        put(&mut data, 0x38, &0x33u16.to_le_bytes());
+        put(&mut data, 0x44, &0x10246u32.to_le_bytes());
+        put(&mut data, 0x98, &0xd12f_f7f0u64.to_le_bytes());
+        put(&mut data, 0xf8, &0x7ff6_a1b2_1234u64.to_le_bytes());
+
+        let context = parse_context(&data, Architecture::X64).unwrap().unwrap();
+        assert_eq!(context.architecture, Architecture::X64);
+        assert_eq!(context.registers.len(), 17);
+        assert_eq!(context.instruction_pointer, Some(0x7ff6_a1b2_1234));
+        assert_eq!(context.stack_pointer, Some(0xd12f_f7f0));
+        assert_eq!(context.frame_pointer, Some(0));
+        assert_eq!(context.segments["cs"], 0x33);
+        assert_eq!(context.flags.value, Some(0x10246));
+
+        // 架构未知时根据大小推断
+        let context = parse_context(&data, Architecture::Unknown)
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.architecture, Architecture::X64);
+    }
+
+    #[test]
+    fn test_parse_x86_context() {
+        let mut data = vec![0u8; CONTEXT_X86_SIZE];
+        put(&mut data, 0xb8, &0x0040_1000u32.to_le_bytes());
+        put(&mut data, 0xc4, &0x0019_ff40u32.to_le_bytes());
+        put(&mut data, 0xb4, &0x0019_ff70u32.to_le_bytes());
+
+        let context = parse_context(&data, Architecture::Unknown)
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.architecture, Architecture::X86);
+        assert_eq!(context.instruction_pointer, Some(0x0040_1000));
+        assert_eq!(context.stack_pointer, Some(0x0019_ff40));
+        assert_eq!(context.frame_pointer, Some(0x0019_ff70));
+        assert_eq!(context.segments.len(), 6);
+    }
+
+    #[test]
+    fn test_parse_arm64_context() {
+        let mut data = vec![0u8; CONTEXT_ARM64_SIZE];
+        put(&mut data, 0x08, &1u64.to_le_bytes());
+        put(&mut data, 0x100, &0x6f_ff80u64.to_le_bytes());
+        put(&mut data, 0x108, &0x7ff6_0000_1000u64.to_le_bytes());
+
+        let context = parse_context(&data, Architecture::Arm64).unwrap().unwrap();
+        assert_eq!(context.registers["x0"], 1);
+        assert_eq!(context.registers.len(), 33);
+        assert_eq!(context.stack_pointer, Some(0x6f_ff80));
+        assert_eq!(context.instruction_pointer, Some(0x7ff6_0000_1000));
+    }
+
+    #[test]
+    fn test_parse_context_errors() {
+        assert!(parse_context(&[0; 16], Architecture::Unknown)
+            .unwrap()
+            .is_none());
+        assert!(parse_context(&[0; 16], Architecture::X64).is_err());
+    }
+}
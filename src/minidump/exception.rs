@@ -0,0 +1,102 @@
+//! ExceptionStream（MINIDUMP_EXCEPTION_STREAM）解析
+
+use super::{Bytes, Location};
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use serde::Serialize;
+
+/// MINIDUMP_EXCEPTION_STREAM 的大小
+const EXCEPTION_STREAM_SIZE: usize = 168;
+
+/// EXCEPTION_MAXIMUM_PARAMETERS
+const MAX_PARAMETERS: usize = 15;
+
+/// 异常信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExceptionInfo {
+    /// 触发异常的线程 ID
+    pub thread_id: u32,
+    /// 异常代码（例如 0xc0000005）
+    #[serde(with = "hex::plain")]
+    pub code: u32,
+    /// 异常标志
+    #[serde(with = "hex::plain")]
+    pub flags: u32,
+    /// 嵌套异常记录的地址
+    #[serde(with = "hex::plain")]
+    pub record: u64,
+    /// 异常地址
+    #[serde(with = "hex::plain")]
+    pub address: u64,
+    /// 异常参数
+    #[serde(with = "hex::vec")]
+    pub parameters: Vec<u64>,
+    /// 异常时的线程上下文（CONTEXT）位置
+    pub thread_context: Location,
+}
+
+impl ExceptionInfo {
+    /// 解析流数据
+    ///
+    /// # 错误
+    /// 如果数据短于 MINIDUMP_EXCEPTION_STREAM，返回错误
+    pub fn parse(data: &[u8]) -> Result<Self, MinidumpError> {
+        let bytes = Bytes::new(data, "exception stream");
+        if bytes.len() < EXCEPTION_STREAM_SIZE {
+            return Err(MinidumpError::Truncated("exception stream"));
+        }
+
+        let count = (bytes.u32(32)? as usize).min(MAX_PARAMETERS);
+        let parameters = (0..count)
+            .map(|i| bytes.u64(40 + i * 8))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            thread_id: bytes.u32(0)?,
+            code: bytes.u32(8)?,
+            flags: bytes.u32(12)?,
+            record: bytes.u64(16)?,
+            address: bytes.u64(24)?,
+            parameters,
+            thread_context: Location {
+                data_size: bytes.u32(160)?,
+                rva: bytes.u32(164)?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::exception_stream;
+    use super::*;
+
+    #[test]
+    fn test_parse_exception() {
+        let data = exception_stream(0x2b40, 0xc000_0005, 0x7ff6_a1b2_1234, &[1, 0x10], 0x800);
+        let info = ExceptionInfo::parse(&data).unwrap();
+        assert_eq!(info.thread_id, 0x2b40);
+        assert_eq!(info.code, 0xc000_0005);
+        assert_eq!(info.address, 0x7ff6_a1b2_1234);
+        assert_eq!(info.parameters, [1, 0x10]);
+        assert_eq!(info.thread_context.rva, 0x800);
+        assert_eq!(info.thread_context.data_size, 0x4d0);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["code"], "0xc0000005");
+        assert_eq!(json["parameters"][1], "0x10");
+    }
+
+    #[test]
+    fn test_parse_clamps_parameter_count() {
+        let mut data = exception_stream(1, 0x8000_0003, 0x1000, &[], 0);
+        data[32..36].copy_from_slice(&1000u32.to_le_bytes());
+        let info = ExceptionInfo::parse(&data).unwrap();
+        assert_eq!(info.parameters.len(), MAX_PARAMETERS);
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        assert!(ExceptionInfo::parse(&[0; 100]).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! MemoryListStream 和 Memory64ListStream 解析
+
+use super::{list_entries, Bytes};
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use serde::Serialize;
+
+/// MINIDUMP_MEMORY_DESCRIPTOR 的大小
+const MEMORY_DESCRIPTOR_SIZE: usize = 16;
+
+/// MINIDUMP_MEMORY_DESCRIPTOR64 的大小
+const MEMORY64_DESCRIPTOR_SIZE: usize = 16;
+
+/// 转储中保存的一段内存
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryRegion {
+    /// 起始地址
+    #[serde(with = "hex::plain")]
+    pub start: u64,
+    /// 大小（字节）
+    pub size: u64,
+    /// 数据在文件中的偏移
+    #[serde(skip)]
+    pub rva: u64,
+}
+
+impl MemoryRegion {
+    /// 结束地址（不含）
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.size)
+    }
+
+    /// 是否包含指定地址
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.start && address < self.end()
+    }
+
+    /// 截取 `[start, end)` 与本区域的交集
+    ///
+    /// # 返回
+    /// 没有交集，或交集的文件偏移溢出（构造的 Memory64 列表可以使偏移接近 `u64::MAX`，
+    /// 视为超出文件范围）时返回 None
+    pub fn clip(&self, start: u64, end: u64) -> Option<Self> {
+        let start = start.max(self.start);
+        let end = end.min(self.end());
+        if start >= end {
+            return None;
+        }
+        Some(Self {
+            start,
+            size: end - start,
+            rva: self.rva.checked_add(start - self.start)?,
+        })
+    }
+}
+
+/// 解析 MemoryListStream
+///
+/// # 错误
+/// 如果条目数量与流大小不符，返回错误
+pub(crate) fn parse_memory_list(data: &[u8]) -> Result<Vec<MemoryRegion>, MinidumpError> {
+    let bytes = Bytes::new(data, "memory list stream");
+    let (count, first) = list_entries(&bytes, MEMORY_DESCRIPTOR_SIZE)?;
+
+    (0..count)
+        .map(|i| {
+            let offset = first + i * MEMORY_DESCRIPTOR_SIZE;
+            Ok(MemoryRegion {
+                start: bytes.u64(offset)?,
+                size: u64::from(bytes.u32(offset + 8)?),
+                rva: u64::from(bytes.u32(offset + 12)?),
+            })
+        })
+        .collect()
+}
+
+/// 解析 Memory64ListStream（完整转储），各区域数据从 BaseRva 开始连续存放
+///
+/// # 错误
+/// 如果条目数量与流大小不符，返回错误
+pub(crate) fn parse_memory64_list(data: &[u8]) -> Result<Vec<MemoryRegion>, MinidumpError> {
+    let bytes = Bytes::new(data, "memory64 list stream");
+    let count = bytes.u64(0)?;
+    let mut rva = bytes.u64(8)?;
+    if count > ((bytes.len() - 16) / MEMORY64_DESCRIPTOR_SIZE) as u64 {
+        return Err(MinidumpError::Truncated("memory64 list stream"));
+    }
+
+    (0..count as usize)
+        .map(|i| {
+            let offset = 16 + i * MEMORY64_DESCRIPTOR_SIZE;
+            let region = MemoryRegion {
+                start: bytes.u64(offset)?,
+                size: bytes.u64(offset + 8)?,
+                rva,
+            };
+            rva = rva.saturating_add(region.size);
+            Ok(region)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::*;
+    use super::super::{stream_type, Minidump};
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_region_clip() {
+        let region = MemoryRegion {
+            start: 0x1000,
+            size: 0x100,
+            rva: 0x40,
+        };
+        assert!(region.contains(0x10ff));
+        assert!(!region.contains(0x1100));
+        assert_eq!(
+            region.clip(0xf00, 0x1010),
+            Some(MemoryRegion {
+                start: 0x1000,
+                size: 0x10,
+                rva: 0x40
+            })
+        );
+        assert_eq!(region.clip(0x1080, 0x2000).unwrap().rva, 0xc0);
+        assert_eq!(region.clip(0x2000, 0x3000), None);
+
+        // 文件偏移溢出
+        let region = MemoryRegion {
+            start: 0x1000,
+            size: 0x100,
+            rva: u64::MAX - 0x10,
+        };
+        assert_eq!(region.clip(0x1000, 0x1010).unwrap().rva, u64::MAX - 0x10);
+        assert_eq!(region.clip(0x1080, 0x1100), None);
+    }
+
+    #[test]
+    fn test_read_memory_lists() {
+        let mut builder = DumpBuilder::new();
+        let small = builder.append(&[0x11; 0x20]);
+        builder.add_stream(
+            stream_type::MEMORY_LIST,
+            &memory_list_stream(&[(0xd12f_f000, 0x20, small)]),
+        );
+        let base = builder.append(&[0x22; 0x10]);
+        builder.append(&[0x33; 0x08]);
+        builder.add_stream(
+            stream_type::MEMORY64_LIST,
+            &memory64_list_stream(base, &[(0x7ff6_0000_0000, 0x10), (0x7ff6_0001_0000, 0x08)]),
+        );
+        let mut dump = Minidump::from_reader(Cursor::new(builder.build())).unwrap();
+
+        let regions = dump.memory_regions().unwrap();
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[2].rva, u64::from(base) + 0x10);
+
+        let region = dump.find_memory(0x7ff6_0001_0004).unwrap().unwrap();
+        assert_eq!(region.start, 0x7ff6_0001_0000);
+        assert_eq!(dump.read_memory(&region).unwrap(), [0x33; 8]);
+        let region = dump.find_memory(0xd12f_f010).unwrap().unwrap();
+        assert_eq!(dump.read_memory(&region).unwrap(), [0x11; 0x20]);
+        assert!(dump.find_memory(0x1000).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_truncated_lists() {
+        assert!(parse_memory_list(&[3, 0, 0, 0, 0]).is_err());
+        let mut data = 5u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 24]);
+        assert!(parse_memory64_list(&data).is_err());
+        assert!(parse_memory64_list(&[0; 8]).is_err());
+    }
+}
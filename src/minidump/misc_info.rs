@@ -0,0 +1,118 @@
+//! MiscInfoStream（MINIDUMP_MISC_INFO 及其 _2/_3/_4/_5 扩展）解析
+
+use super::{utf16_string, Bytes};
+use crate::error::MinidumpError;
+use serde::Serialize;
+
+/// MINIDUMP_MISC1_PROCESS_ID
+const MISC1_PROCESS_ID: u32 = 0x1;
+/// MINIDUMP_MISC1_PROCESS_TIMES
+const MISC1_PROCESS_TIMES: u32 = 0x2;
+/// MINIDUMP_MISC4_BUILDSTRING
+const MISC4_BUILDSTRING: u32 = 0x100;
+
+/// MINIDUMP_MISC_INFO 的大小
+const MISC_INFO_SIZE: usize = 24;
+/// MINIDUMP_MISC_INFO_4 中 BuildString 的偏移
+const BUILD_STRING_OFFSET: usize = 232;
+/// BuildString 的字符数
+const BUILD_STRING_CHARS: usize = 260;
+
+/// 杂项信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MiscInfo {
+    /// 进程 ID
+    pub process_id: Option<u32>,
+    /// 进程创建时间（Unix 时间戳）
+    pub process_create_time: Option<u32>,
+    /// 进程用户态时间（秒）
+    pub process_user_time: Option<u32>,
+    /// 进程内核态时间（秒）
+    pub process_kernel_time: Option<u32>,
+    /// 操作系统构建字符串（MINIDUMP_MISC_INFO_4 起，例如 `19041.1.amd64fre.vb_release.191206-1406`）
+    pub build_string: Option<String>,
+}
+
+impl MiscInfo {
+    /// 解析流数据
+    ///
+    /// 只读取 Flags1 中标记为有效的字段；较新版本的扩展字段存在时一并读取。
+    ///
+    /// # 错误
+    /// 如果数据短于 MINIDUMP_MISC_INFO，返回错误
+    pub fn parse(data: &[u8]) -> Result<Self, MinidumpError> {
+        let bytes = Bytes::new(data, "misc info stream");
+        if bytes.len() < MISC_INFO_SIZE {
+            return Err(MinidumpError::Truncated("misc info stream"));
+        }
+
+        let flags = bytes.u32(4)?;
+        let mut info = Self::default();
+        if flags & MISC1_PROCESS_ID != 0 {
+            info.process_id = Some(bytes.u32(8)?);
+        }
+        if flags & MISC1_PROCESS_TIMES != 0 {
+            info.process_create_time = Some(bytes.u32(12)?);
+            info.process_user_time = Some(bytes.u32(16)?);
+            info.process_kernel_time = Some(bytes.u32(20)?);
+        }
+        if flags & MISC4_BUILDSTRING != 0 {
+            if let Ok(raw) = bytes.slice(BUILD_STRING_OFFSET, BUILD_STRING_CHARS * 2) {
+                info.build_string = Some(utf16_string(raw)).filter(|s| !s.is_empty());
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::misc_info_stream;
+    use super::*;
+
+    #[test]
+    fn test_parse_misc_info() {
+        let info = MiscInfo::parse(&misc_info_stream(0x1a2c, 1_792_001_719)).unwrap();
+        assert_eq!(info.process_id, Some(0x1a2c));
+        assert_eq!(info.process_create_time, Some(1_792_001_719));
+        assert_eq!(info.process_user_time, Some(12));
+        assert_eq!(info.process_kernel_time, Some(3));
+        assert_eq!(info.build_string, None);
+    }
+
+    #[test]
+    fn test_parse_misc_info_flags() {
+        // 只设置了 PROCESS_ID 标志
+        let mut data = misc_info_stream(42, 1000);
+        data[4..8].copy_from_slice(&MISC1_PROCESS_ID.to_le_bytes());
+        let info = MiscInfo::parse(&data).unwrap();
+        assert_eq!(info.process_id, Some(42));
+        assert_eq!(info.process_create_time, None);
+    }
+
+    #[test]
+    fn test_parse_misc_info_4_build_string() {
+        let mut data = vec![0u8; 832];
+        data[0..4].copy_from_slice(&832u32.to_le_bytes());
+        data[4..8].copy_from_slice(&(MISC1_PROCESS_ID | MISC4_BUILDSTRING).to_le_bytes());
+        data[8..12].copy_from_slice(&7u32.to_le_bytes());
+        let build: Vec<u8> = "19041.1.amd64fre.vb_release.191206-1406"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        data[BUILD_STRING_OFFSET..BUILD_STRING_OFFSET + build.len()].copy_from_slice(&build);
+
+        let info = MiscInfo::parse(&data).unwrap();
+        assert_eq!(info.process_id, Some(7));
+        assert_eq!(
+            info.build_string.as_deref(),
+            Some("19041.1.amd64fre.vb_release.191206-1406")
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        assert!(MiscInfo::parse(&[0; 8]).is_err());
+    }
+}
@@ -0,0 +1,1162 @@
+//! 纯 Rust 的 minidump 读取模块
+//!
+//! 直接从文件解析 MDMP 头部、流目录以及 SystemInfo、MiscInfo、Exception、
+//! 模块列表、线程列表和内存列表等流，无需启动 cdb.exe 即可获取操作系统版本、
+//! CPU 架构、进程 ID、崩溃时间、异常代码、已加载模块（含 PDB 信息）、线程和栈内存。
+
+pub mod context;
+pub mod exception;
+pub mod memory;
+pub mod misc_info;
+pub mod modules;
+pub mod offline;
+pub mod system_info;
+pub mod threads;
+
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use crate::parser::registers::Architecture;
+use crate::utils::format_timestamp;
+use exception::ExceptionInfo;
+use memory::MemoryRegion;
+use misc_info::MiscInfo;
+use modules::{CodeViewInfo, MinidumpModule, UnloadedModule, MAX_CV_RECORD_BYTES};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use system_info::SystemInfo;
+use threads::{MinidumpThread, ThreadName};
+
+/// minidump 文件签名（`MDMP`）
+pub const MINIDUMP_SIGNATURE: u32 = 0x504d_444d;
+
+/// MINIDUMP_TYPE 中表示包含完整进程内存的标志（MiniDumpWithFullMemory）
+pub const MINIDUMP_WITH_FULL_MEMORY: u64 = 0x2;
+
+/// 32 位内核转储签名（DUMP_HEADER32）
+const KERNEL_DUMP32_SIGNATURE: &[u8; 8] = b"PAGEDUMP";
+
+/// 64 位内核转储签名（DUMP_HEADER64）
+const KERNEL_DUMP64_SIGNATURE: &[u8; 8] = b"PAGEDU64";
+
+/// 头部大小（MINIDUMP_HEADER）
+const HEADER_SIZE: usize = 32;
+
+/// 流目录项大小（MINIDUMP_DIRECTORY）
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+
+/// MINIDUMP_STRING 的最大长度（字节），防止损坏的文件导致过大的分配
+const MAX_STRING_BYTES: u32 = 64 * 1024;
+
+/// 流类型（MINIDUMP_STREAM_TYPE）
+pub mod stream_type {
+    /// 未使用的目录项
+    pub const UNUSED: u32 = 0;
+    /// 线程列表
+    pub const THREAD_LIST: u32 = 3;
+    /// 模块列表
+    pub const MODULE_LIST: u32 = 4;
+    /// 内存列表
+    pub const MEMORY_LIST: u32 = 5;
+    /// 异常信息
+    pub const EXCEPTION: u32 = 6;
+    /// 系统信息
+    pub const SYSTEM_INFO: u32 = 7;
+    /// 64 位内存列表（完整转储）
+    pub const MEMORY64_LIST: u32 = 9;
+    /// 句柄数据
+    pub const HANDLE_DATA: u32 = 12;
+    /// 已卸载模块列表
+    pub const UNLOADED_MODULE_LIST: u32 = 14;
+    /// 杂项信息（进程 ID、进程时间等）
+    pub const MISC_INFO: u32 = 15;
+    /// 内存区域信息
+    pub const MEMORY_INFO_LIST: u32 = 16;
+    /// 线程附加信息
+    pub const THREAD_INFO_LIST: u32 = 17;
+    /// 线程名
+    pub const THREAD_NAMES: u32 = 24;
+}
+
+/// minidump 头部（MINIDUMP_HEADER）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MinidumpHeader {
+    /// 格式版本（低 16 位，通常为 0xa793）
+    pub version: u16,
+    /// 实现相关的版本（高 16 位）
+    pub implementation_version: u16,
+    /// 流数量
+    pub number_of_streams: u32,
+    /// 流目录的文件偏移
+    pub stream_directory_rva: u32,
+    /// 校验和（通常为 0）
+    pub checksum: u32,
+    /// 转储生成时间（Unix 时间戳）
+    pub timestamp: u32,
+    /// MINIDUMP_TYPE 标志
+    #[serde(with = "hex::plain")]
+    pub flags: u64,
+}
+
+impl MinidumpHeader {
+    /// 转储生成时间
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(u64::from(self.timestamp))
+    }
+
+    /// 是否包含完整进程内存
+    pub fn has_full_memory(&self) -> bool {
+        self.flags & MINIDUMP_WITH_FULL_MEMORY != 0
+    }
+}
+
+/// 文件内数据的位置（MINIDUMP_LOCATION_DESCRIPTOR）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Location {
+    /// 数据大小（字节）
+    pub data_size: u32,
+    /// 文件偏移
+    pub rva: u32,
+}
+
+/// 流目录项（MINIDUMP_DIRECTORY）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StreamDirectoryEntry {
+    /// 流类型（见 [`stream_type`]）
+    pub stream_type: u32,
+    /// 流数据位置
+    pub location: Location,
+}
+
+/// 无需调试器即可获得的转储概要
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MinidumpSummary {
+    /// 转储生成时间（RFC 3339）
+    pub crash_time: Option<String>,
+    /// 是否包含完整进程内存
+    pub full_memory: bool,
+    /// CPU 架构
+    pub architecture: Option<Architecture>,
+    /// 操作系统版本（例如 `10.0.19045`）
+    pub os_version: Option<String>,
+    /// 进程 ID
+    pub process_id: Option<u32>,
+    /// 进程创建时间（RFC 3339）
+    pub process_create_time: Option<String>,
+    /// 异常代码
+    #[serde(with = "hex::option")]
+    pub exception_code: Option<u64>,
+    /// 异常地址
+    #[serde(with = "hex::option")]
+    pub exception_address: Option<u64>,
+    /// 触发异常的线程 ID
+    pub exception_thread_id: Option<u32>,
+}
+
+/// 转储类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpType {
+    /// 包含完整进程内存的用户模式转储
+    Full,
+    /// 用户模式小型转储
+    Mini,
+    /// 内核转储（完整、内核或小型内存转储）
+    Kernel,
+    /// 无法识别的文件
+    Unknown,
+}
+
+/// 仅读取文件头部即可获得的转储信息
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DumpDetails {
+    /// 转储类型
+    pub dump_type: DumpType,
+    /// 异常代码（用户模式转储的 ExceptionStream）
+    #[serde(with = "hex::option")]
+    pub exception_code: Option<u64>,
+    /// 蓝屏代码（内核转储头部）
+    #[serde(with = "hex::option")]
+    pub bugcheck_code: Option<u64>,
+}
+
+/// 快速识别转储类型并读取异常代码或蓝屏代码
+///
+/// 只读取文件头部、流目录和 ExceptionStream，适合批量列出转储。
+/// 文件无法读取或格式无法识别时返回 [`DumpType::Unknown`]。
+///
+/// # 参数
+/// * `path` - 转储文件路径
+pub fn inspect_dump(path: &Path) -> DumpDetails {
+    let mut details = DumpDetails {
+        dump_type: DumpType::Unknown,
+        exception_code: None,
+        bugcheck_code: None,
+    };
+
+    let mut prefix = Vec::with_capacity(0x40);
+    let Ok(file) = File::open(path) else {
+        return details;
+    };
+    if file.take(0x40).read_to_end(&mut prefix).is_err() {
+        return details;
+    }
+
+    // DUMP_HEADER32/64 中 BugCheckCode 的偏移不同
+    let bugcheck_offset = match prefix.get(..8) {
+        Some(signature) if signature == KERNEL_DUMP32_SIGNATURE => Some(0x28),
+        Some(signature) if signature == KERNEL_DUMP64_SIGNATURE => Some(0x38),
+        _ => None,
+    };
+    if let Some(offset) = bugcheck_offset {
+        details.dump_type = DumpType::Kernel;
+        details.bugcheck_code = Bytes::new(&prefix, "kernel dump header")
+            .u32(offset)
+            .ok()
+            .map(u64::from);
+        return details;
+    }
+
+    if let Ok(mut dump) = Minidump::open(path) {
+        details.dump_type = if dump.header().has_full_memory() {
+            DumpType::Full
+        } else {
+            DumpType::Mini
+        };
+        details.exception_code = dump.exception().ok().flatten().map(|e| u64::from(e.code));
+    }
+
+    details
+}
+
+/// minidump 读取器
+///
+/// 只在打开时读取头部和流目录，其余流按需读取，因此可以用于较大的完整转储。
+#[derive(Debug)]
+pub struct Minidump<R> {
+    /// 底层数据源
+    reader: R,
+    /// 文件长度
+    len: u64,
+    /// 头部
+    header: MinidumpHeader,
+    /// 流目录
+    streams: Vec<StreamDirectoryEntry>,
+}
+
+impl Minidump<BufReader<File>> {
+    /// 打开 minidump 文件
+    ///
+    /// # 参数
+    /// * `path` - 转储文件路径
+    ///
+    /// # 错误
+    /// 如果文件无法读取、签名不是 `MDMP` 或头部/流目录不完整，返回错误
+    pub fn open(path: &Path) -> Result<Self, MinidumpError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> Minidump<R> {
+    /// 从任意可定位的数据源读取 minidump
+    ///
+    /// # 参数
+    /// * `reader` - 数据源（例如文件或内存中的 `Cursor`）
+    ///
+    /// # 错误
+    /// 如果签名不是 `MDMP` 或头部/流目录不完整，返回错误
+    pub fn from_reader(mut reader: R) -> Result<Self, MinidumpError> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        let data = read_exact_at(&mut reader, len, 0, HEADER_SIZE, "header")?;
+        let bytes = Bytes::new(&data, "header");
+
+        let signature = bytes.u32(0)?;
+        if signature != MINIDUMP_SIGNATURE {
+            return Err(MinidumpError::InvalidSignature(signature));
+        }
+        let version = bytes.u32(4)?;
+        let header = MinidumpHeader {
+            version: (version & 0xffff) as u16,
+            implementation_version: (version >> 16) as u16,
+            number_of_streams: bytes.u32(8)?,
+            stream_directory_rva: bytes.u32(12)?,
+            checksum: bytes.u32(16)?,
+            timestamp: bytes.u32(20)?,
+            flags: bytes.u64(24)?,
+        };
+
+        let directory_size = header.number_of_streams as usize * DIRECTORY_ENTRY_SIZE;
+        let data = read_exact_at(
+            &mut reader,
+            len,
+            u64::from(header.stream_directory_rva),
+            directory_size,
+            "stream directory",
+        )?;
+        let bytes = Bytes::new(&data, "stream directory");
+        let streams = (0..header.number_of_streams as usize)
+            .map(|i| {
+                let offset = i * DIRECTORY_ENTRY_SIZE;
+                Ok(StreamDirectoryEntry {
+                    stream_type: bytes.u32(offset)?,
+                    location: Location {
+                        data_size: bytes.u32(offset + 4)?,
+                        rva: bytes.u32(offset + 8)?,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, MinidumpError>>()?;
+
+        Ok(Self {
+            reader,
+            len,
+            header,
+            streams,
+        })
+    }
+
+    /// 头部
+    pub fn header(&self) -> &MinidumpHeader {
+        &self.header
+    }
+
+    /// 流目录
+    pub fn streams(&self) -> &[StreamDirectoryEntry] {
+        &self.streams
+    }
+
+    /// 查找指定类型的第一个流
+    pub fn find_stream(&self, stream_type: u32) -> Option<StreamDirectoryEntry> {
+        self.streams
+            .iter()
+            .find(|s| s.stream_type == stream_type && stream_type != stream_type::UNUSED)
+            .copied()
+    }
+
+    /// 读取指定类型的流数据
+    ///
+    /// # 返回
+    /// 流不存在时返回 None
+    ///
+    /// # 错误
+    /// 如果流数据超出文件末尾，返回错误
+    pub fn read_stream(&mut self, stream_type: u32) -> Result<Option<Vec<u8>>, MinidumpError> {
+        match self.find_stream(stream_type) {
+            Some(entry) => self.read_location(entry.location, "stream").map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 读取指定位置的数据
+    ///
+    /// # 错误
+    /// 如果数据超出文件末尾，返回错误
+    pub fn read_location(
+        &mut self,
+        location: Location,
+        what: &'static str,
+    ) -> Result<Vec<u8>, MinidumpError> {
+        self.read_at(u64::from(location.rva), location.data_size as usize, what)
+    }
+
+    /// 读取指定文件偏移处的数据
+    ///
+    /// # 错误
+    /// 如果数据超出文件末尾，返回错误
+    pub fn read_at(
+        &mut self,
+        offset: u64,
+        size: usize,
+        what: &'static str,
+    ) -> Result<Vec<u8>, MinidumpError> {
+        read_exact_at(&mut self.reader, self.len, offset, size, what)
+    }
+
+    /// 读取 MINIDUMP_STRING（32 位字节长度 + UTF-16LE 字符）
+    ///
+    /// # 参数
+    /// * `rva` - 字符串的文件偏移（ThreadNamesStream 使用 64 位 RVA）
+    ///
+    /// # 错误
+    /// 如果字符串超出文件末尾或长度异常，返回错误
+    pub fn read_string(&mut self, rva: u64) -> Result<String, MinidumpError> {
+        let data = self.read_at(rva, 4, "string")?;
+        let length = Bytes::new(&data, "string").u32(0)?;
+        if length > MAX_STRING_BYTES {
+            return Err(MinidumpError::Truncated("string"));
+        }
+        let data = self.read_at(rva + 4, length as usize, "string")?;
+        Ok(utf16_string(&data))
+    }
+
+    /// 读取 SystemInfoStream
+    ///
+    /// # 返回
+    /// 流不存在时返回 None
+    pub fn system_info(&mut self) -> Result<Option<SystemInfo>, MinidumpError> {
+        let Some(data) = self.read_stream(stream_type::SYSTEM_INFO)? else {
+            return Ok(None);
+        };
+        let mut info = SystemInfo::parse(&data)?;
+        if info.csd_version_rva != 0 {
+            // Service Pack 字符串缺失不影响其余字段
+            info.csd_version = self
+                .read_string(u64::from(info.csd_version_rva))
+                .ok()
+                .filter(|s| !s.is_empty());
+        }
+        Ok(Some(info))
+    }
+
+    /// 读取 MiscInfoStream
+    ///
+    /// # 返回
+    /// 流不存在时返回 None
+    pub fn misc_info(&mut self) -> Result<Option<MiscInfo>, MinidumpError> {
+        self.read_stream(stream_type::MISC_INFO)?
+            .map(|data| MiscInfo::parse(&data))
+            .transpose()
+    }
+
+    /// 读取 ExceptionStream
+    ///
+    /// # 返回
+    /// 流不存在时（例如手动生成的转储）返回 None
+    pub fn exception(&mut self) -> Result<Option<ExceptionInfo>, MinidumpError> {
+        self.read_stream(stream_type::EXCEPTION)?
+            .map(|data| ExceptionInfo::parse(&data))
+            .transpose()
+    }
+
+    /// 读取 ModuleListStream（包含模块路径、版本和 CodeView PDB 信息）
+    ///
+    /// 单个模块的路径或 CodeView 记录损坏时对应字段为空，不影响其他模块。
+    ///
+    /// # 返回
+    /// 流不存在时返回空列表
+    ///
+    /// # 错误
+    /// 如果流数据不完整，返回错误
+    pub fn modules(&mut self) -> Result<Vec<MinidumpModule>, MinidumpError> {
+        let Some(data) = self.read_stream(stream_type::MODULE_LIST)? else {
+            return Ok(Vec::new());
+        };
+        let mut modules = modules::parse_module_list(&data)?;
+        for module in &mut modules {
+            module.path = self
+                .read_string(u64::from(module.name_rva))
+                .unwrap_or_default();
+            let cv = module.cv_record;
+            if cv.rva != 0 && cv.data_size != 0 && cv.data_size <= MAX_CV_RECORD_BYTES {
+                module.codeview = self
+                    .read_location(cv, "codeview record")
+                    .ok()
+                    .and_then(|data| CodeViewInfo::parse(&data));
+            }
+        }
+        Ok(modules)
+    }
+
+    /// 读取 UnloadedModuleListStream
+    ///
+    /// # 返回
+    /// 流不存在时返回空列表
+    ///
+    /// # 错误
+    /// 如果流数据不完整，返回错误
+    pub fn unloaded_modules(&mut self) -> Result<Vec<UnloadedModule>, MinidumpError> {
+        let Some(data) = self.read_stream(stream_type::UNLOADED_MODULE_LIST)? else {
+            return Ok(Vec::new());
+        };
+        let mut modules = modules::parse_unloaded_module_list(&data)?;
+        for module in &mut modules {
+            module.path = self
+                .read_string(u64::from(module.name_rva))
+                .unwrap_or_default();
+        }
+        Ok(modules)
+    }
+
+    /// 读取 ThreadListStream，并合并 ThreadNamesStream 中的线程名
+    ///
+    /// # 返回
+    /// 流不存在时返回空列表
+    ///
+    /// # 错误
+    /// 如果线程列表不完整，返回错误；线程名流损坏时忽略线程名
+    pub fn threads(&mut self) -> Result<Vec<MinidumpThread>, MinidumpError> {
+        let Some(data) = self.read_stream(stream_type::THREAD_LIST)? else {
+            return Ok(Vec::new());
+        };
+        let mut threads = threads::parse_thread_list(&data)?;
+
+        let names = self.thread_names().unwrap_or_default();
+        for thread in &mut threads {
+            thread.name = names
+                .iter()
+                .find(|n| n.thread_id == thread.thread_id)
+                .map(|n| n.name.clone())
+                .filter(|n| !n.is_empty());
+        }
+        Ok(threads)
+    }
+
+    /// 读取 ThreadNamesStream（SetThreadDescription 设置的线程名）
+    ///
+    /// # 返回
+    /// 流不存在时返回空列表
+    ///
+    /// # 错误
+    /// 如果流数据不完整，返回错误
+    pub fn thread_names(&mut self) -> Result<Vec<ThreadName>, MinidumpError> {
+        let Some(data) = self.read_stream(stream_type::THREAD_NAMES)? else {
+            return Ok(Vec::new());
+        };
+        threads::parse_thread_names(&data)?
+            .into_iter()
+            .map(|(thread_id, rva)| {
+                Ok(ThreadName {
+                    thread_id,
+                    name: self.read_string(rva)?,
+                })
+            })
+            .collect()
+    }
+
+    /// 读取 MemoryListStream 和 Memory64ListStream 中的全部内存区域
+    ///
+    /// # 返回
+    /// 两个流都不存在时返回空列表
+    ///
+    /// # 错误
+    /// 如果流数据不完整，返回错误
+    pub fn memory_regions(&mut self) -> Result<Vec<MemoryRegion>, MinidumpError> {
+        let mut regions = match self.read_stream(stream_type::MEMORY_LIST)? {
+            Some(data) => memory::parse_memory_list(&data)?,
+            None => Vec::new(),
+        };
+        if let Some(data) = self.read_stream(stream_type::MEMORY64_LIST)? {
+            regions.extend(memory::parse_memory64_list(&data)?);
+        }
+        Ok(regions)
+    }
+
+    /// 查找包含指定地址的内存区域
+    ///
+    /// # 返回
+    /// 转储中没有保存该地址的内存时返回 None
+    ///
+    /// # 错误
+    /// 如果内存列表不完整，返回错误
+    pub fn find_memory(&mut self, address: u64) -> Result<Option<MemoryRegion>, MinidumpError> {
+        Ok(self
+            .memory_regions()?
+            .into_iter()
+            .find(|r| r.contains(address)))
+    }
+
+    /// 读取内存区域的数据
+    ///
+    /// # 错误
+    /// 如果数据超出文件末尾，返回错误
+    pub fn read_memory(&mut self, region: &MemoryRegion) -> Result<Vec<u8>, MinidumpError> {
+        let size = usize::try_from(region.size).map_err(|_| MinidumpError::Truncated("memory"))?;
+        self.read_at(region.rva, size, "memory")
+    }
+
+    /// 生成转储概要
+    ///
+    /// 尽力而为：单个流缺失或损坏时对应字段为空，不影响其他字段。
+    pub fn summary(&mut self) -> MinidumpSummary {
+        let system_info = self.system_info().ok().flatten();
+        let misc_info = self.misc_info().ok().flatten();
+        let exception = self.exception().ok().flatten();
+
+        MinidumpSummary {
+            crash_time: (self.header.timestamp != 0).then(|| format_timestamp(self.header.time())),
+            full_memory: self.header.has_full_memory(),
+            architecture: system_info.as_ref().map(|s| s.architecture),
+            os_version: system_info.as_ref().map(SystemInfo::os_version),
+            process_id: misc_info.as_ref().and_then(|m| m.process_id),
+            process_create_time: misc_info
+                .as_ref()
+                .and_then(|m| m.process_create_time)
+                .map(|t| format_timestamp(UNIX_EPOCH + Duration::from_secs(u64::from(t)))),
+            exception_code: exception.as_ref().map(|e| u64::from(e.code)),
+            exception_address: exception.as_ref().map(|e| e.address),
+            exception_thread_id: exception.as_ref().map(|e| e.thread_id),
+        }
+    }
+}
+
+/// 读取指定偏移处的定长数据，超出文件末尾时返回 `Truncated`
+fn read_exact_at<R: Read + Seek>(
+    reader: &mut R,
+    len: u64,
+    offset: u64,
+    size: usize,
+    what: &'static str,
+) -> Result<Vec<u8>, MinidumpError> {
+    // 先检查边界，避免损坏的长度字段导致过大的分配
+    if offset.checked_add(size as u64).is_none_or(|end| end > len) {
+        return Err(MinidumpError::Truncated(what));
+    }
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut data = vec![0; size];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// 读取列表流的条目数量，返回 `(数量, 第一个条目的偏移)`
+///
+/// 部分写入器会在 32 位数量字段后填充 4 字节，使条目 8 字节对齐，
+/// 因此流大小恰好多出 4 字节时从偏移 8 开始读取。
+///
+/// # 错误
+/// 如果流大小不足以容纳声明数量的条目，返回错误
+pub(crate) fn list_entries(
+    bytes: &Bytes,
+    entry_size: usize,
+) -> Result<(usize, usize), MinidumpError> {
+    let count = bytes.u32(0)? as usize;
+    let size = count
+        .checked_mul(entry_size)
+        .ok_or(MinidumpError::Truncated(bytes.what))?;
+    match bytes.len().checked_sub(size) {
+        Some(8) => Ok((count, 8)),
+        Some(extra) if extra >= 4 => Ok((count, 4)),
+        _ => Err(MinidumpError::Truncated(bytes.what)),
+    }
+}
+
+/// 解码 UTF-16LE 字符串（遇到 NUL 结束，用于定长字符数组）
+pub(crate) fn utf16_string(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// 带边界检查的小端字节读取
+pub(crate) struct Bytes<'a> {
+    data: &'a [u8],
+    what: &'static str,
+}
+
+impl<'a> Bytes<'a> {
+    /// 创建读取器；`what` 用于越界时的错误信息
+    pub(crate) fn new(data: &'a [u8], what: &'static str) -> Self {
+        Self { data, what }
+    }
+
+    /// 数据长度
+    pub(crate) fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 取出指定范围的切片
+    pub(crate) fn slice(&self, offset: usize, size: usize) -> Result<&'a [u8], MinidumpError> {
+        offset
+            .checked_add(size)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or(MinidumpError::Truncated(self.what))
+    }
+
+    pub(crate) fn u8(&self, offset: usize) -> Result<u8, MinidumpError> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    pub(crate) fn u16(&self, offset: usize) -> Result<u16, MinidumpError> {
+        let b = self.slice(offset, 2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&self, offset: usize) -> Result<u32, MinidumpError> {
+        let b = self.slice(offset, 4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn u64(&self, offset: usize) -> Result<u64, MinidumpError> {
+        Ok(u64::from(self.u32(offset)?) | (u64::from(self.u32(offset + 4)?) << 32))
+    }
+}
+
+/// 构造合成 minidump 的测试辅助工具
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::{stream_type, MINIDUMP_SIGNATURE};
+
+    /// 合成 minidump 构造器
+    ///
+    /// 数据按追加顺序排列在头部之后，流目录放在文件末尾，
+    /// 因此可以先追加字符串等数据，再在流中引用其 RVA。
+    pub struct DumpBuilder {
+        data: Vec<u8>,
+        streams: Vec<(u32, u32, u32)>,
+        timestamp: u32,
+        flags: u64,
+    }
+
+    impl DumpBuilder {
+        pub fn new() -> Self {
+            Self {
+                data: vec![0; 32],
+                streams: Vec::new(),
+                timestamp: 0,
+                flags: 0,
+            }
+        }
+
+        pub fn timestamp(&mut self, timestamp: u32) -> &mut Self {
+            self.timestamp = timestamp;
+            self
+        }
+
+        pub fn flags(&mut self, flags: u64) -> &mut Self {
+            self.flags = flags;
+            self
+        }
+
+        /// 追加原始数据（4 字节对齐），返回其 RVA
+        pub fn append(&mut self, bytes: &[u8]) -> u32 {
+            while self.data.len() & 3 != 0 {
+                self.data.push(0);
+            }
+            let rva = self.data.len() as u32;
+            self.data.extend_from_slice(bytes);
+            rva
+        }
+
+        /// 追加 MINIDUMP_STRING，返回其 RVA
+        pub fn add_string(&mut self, text: &str) -> u32 {
+            let units: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            let mut bytes = (units.len() as u32).to_le_bytes().to_vec();
+            bytes.extend_from_slice(&units);
+            bytes.extend_from_slice(&[0, 0]);
+            self.append(&bytes)
+        }
+
+        /// 追加流数据并登记到流目录
+        pub fn add_stream(&mut self, stream_type: u32, bytes: &[u8]) -> &mut Self {
+            let rva = self.append(bytes);
+            self.streams.push((stream_type, bytes.len() as u32, rva));
+            self
+        }
+
+        pub fn build(&mut self) -> Vec<u8> {
+            let mut directory = Vec::new();
+            for (stream_type, size, rva) in &self.streams {
+                directory.extend_from_slice(&stream_type.to_le_bytes());
+                directory.extend_from_slice(&size.to_le_bytes());
+                directory.extend_from_slice(&rva.to_le_bytes());
+            }
+            let directory_rva = self.append(&directory);
+
+            let mut data = self.data.clone();
+            let header = [
+                MINIDUMP_SIGNATURE,
+                0xa793,
+                self.streams.len() as u32,
+                directory_rva,
+                0,
+                self.timestamp,
+            ];
+            for (i, value) in header.iter().enumerate() {
+                data[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+            }
+            data[24..32].copy_from_slice(&self.flags.to_le_bytes());
+            data
+        }
+    }
+
+    /// SystemInfoStream（MINIDUMP_SYSTEM_INFO）
+    pub fn system_info_stream(
+        architecture: u16,
+        (major, minor, build): (u32, u32, u32),
+        csd_version_rva: u32,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&architecture.to_le_bytes());
+        bytes.extend_from_slice(&6u16.to_le_bytes()); // ProcessorLevel
+        bytes.extend_from_slice(&0x9e0au16.to_le_bytes()); // ProcessorRevision
+        bytes.push(8); // NumberOfProcessors
+        bytes.push(1); // ProductType（工作站）
+        for value in [major, minor, build, 2, csd_version_rva] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&0x100u16.to_le_bytes()); // SuiteMask
+        bytes.extend_from_slice(&[0; 2]);
+        bytes.extend_from_slice(&[0; 24]); // CPU 信息
+        bytes
+    }
+
+    /// MiscInfoStream（MINIDUMP_MISC_INFO，包含进程 ID 和进程时间）
+    pub fn misc_info_stream(process_id: u32, create_time: u32) -> Vec<u8> {
+        [24, 0x3, process_id, create_time, 12, 3]
+            .iter()
+            .flat_map(|v: &u32| v.to_le_bytes())
+            .collect()
+    }
+
+    /// ExceptionStream（MINIDUMP_EXCEPTION_STREAM）
+    pub fn exception_stream(
+        thread_id: u32,
+        code: u32,
+        address: u64,
+        parameters: &[u64],
+        context_rva: u32,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&thread_id.to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&code.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // ExceptionFlags
+        bytes.extend_from_slice(&0u64.to_le_bytes()); // ExceptionRecord
+        bytes.extend_from_slice(&address.to_le_bytes());
+        bytes.extend_from_slice(&(parameters.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        for i in 0..15 {
+            let value = parameters.get(i).copied().unwrap_or_default();
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&0x4d0u32.to_le_bytes()); // ThreadContext.DataSize
+        bytes.extend_from_slice(&context_rva.to_le_bytes());
+        bytes
+    }
+
+    /// CodeView 7.0 记录（RSDS）
+    pub fn rsds_record(guid: &[u8; 16], age: u32, pdb_file: &str) -> Vec<u8> {
+        let mut bytes = b"RSDS".to_vec();
+        bytes.extend_from_slice(guid);
+        bytes.extend_from_slice(&age.to_le_bytes());
+        bytes.extend_from_slice(pdb_file.as_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    /// ModuleListStream 中的一个模块
+    pub struct ModuleSpec {
+        pub base: u64,
+        pub size: u32,
+        pub timestamp: u32,
+        pub name_rva: u32,
+        /// 文件版本 `(FileVersionMS, FileVersionLS)`，None 表示没有版本资源
+        pub version: Option<(u32, u32)>,
+        /// CodeView 记录 `(DataSize, Rva)`
+        pub cv_record: (u32, u32),
+    }
+
+    /// ModuleListStream（MINIDUMP_MODULE_LIST）
+    pub fn module_list_stream(modules: &[ModuleSpec]) -> Vec<u8> {
+        let mut bytes = (modules.len() as u32).to_le_bytes().to_vec();
+        for module in modules {
+            bytes.extend_from_slice(&module.base.to_le_bytes());
+            for value in [module.size, 0x5a3c1, module.timestamp, module.name_rva] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            // VS_FIXEDFILEINFO（52 字节）
+            let mut version = [0u32; 13];
+            if let Some((ms, ls)) = module.version {
+                version[0] = 0xfeef_04bd;
+                version[1] = 0x0001_0000;
+                version[2..6].copy_from_slice(&[ms, ls, ms, ls]);
+            }
+            bytes.extend(version.iter().flat_map(|v| v.to_le_bytes()));
+            bytes.extend_from_slice(&module.cv_record.0.to_le_bytes());
+            bytes.extend_from_slice(&module.cv_record.1.to_le_bytes());
+            bytes.extend_from_slice(&[0; 8]); // MiscRecord
+            bytes.extend_from_slice(&[0; 16]); // Reserved0/1
+        }
+        bytes
+    }
+
+    /// UnloadedModuleListStream，模块为 `(基址, 大小, 模块名 RVA)`
+    pub fn unloaded_module_list_stream(modules: &[(u64, u32, u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for value in [12, 24, modules.len() as u32] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for (base, size, name_rva) in modules {
+            bytes.extend_from_slice(&base.to_le_bytes());
+            for value in [*size, 0, 0x5f00_0000, *name_rva] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// ThreadListStream，线程为 `(线程 ID, TEB, 栈起始地址, (栈大小, 栈 RVA))`
+    pub fn thread_list_stream(threads: &[(u32, u64, u64, (u32, u32))]) -> Vec<u8> {
+        let mut bytes = (threads.len() as u32).to_le_bytes().to_vec();
+        for (thread_id, teb, stack_start, (stack_size, stack_rva)) in threads {
+            for value in [*thread_id, 0, 0x20, 0] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&teb.to_le_bytes());
+            bytes.extend_from_slice(&stack_start.to_le_bytes());
+            bytes.extend_from_slice(&stack_size.to_le_bytes());
+            bytes.extend_from_slice(&stack_rva.to_le_bytes());
+            bytes.extend_from_slice(&[0; 8]); // ThreadContext
+        }
+        bytes
+    }
+
+    /// ThreadNamesStream，条目为 `(线程 ID, 线程名 RVA64)`
+    pub fn thread_names_stream(names: &[(u32, u64)]) -> Vec<u8> {
+        let mut bytes = (names.len() as u32).to_le_bytes().to_vec();
+        for (thread_id, rva) in names {
+            bytes.extend_from_slice(&thread_id.to_le_bytes());
+            bytes.extend_from_slice(&rva.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// MemoryListStream，区域为 `(起始地址, 大小, RVA)`
+    pub fn memory_list_stream(regions: &[(u64, u32, u32)]) -> Vec<u8> {
+        let mut bytes = (regions.len() as u32).to_le_bytes().to_vec();
+        for (start, size, rva) in regions {
+            bytes.extend_from_slice(&start.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(&rva.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Memory64ListStream，区域为 `(起始地址, 大小)`，数据从 `base_rva` 开始连续存放
+    pub fn memory64_list_stream(base_rva: u32, regions: &[(u64, u64)]) -> Vec<u8> {
+        let mut bytes = (regions.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&u64::from(base_rva).to_le_bytes());
+        for (start, size) in regions {
+            bytes.extend_from_slice(&start.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 包含 SystemInfo、MiscInfo 和 Exception 流的 x64 访问违例转储
+    pub fn sample_dump() -> Vec<u8> {
+        let mut builder = DumpBuilder::new();
+        builder.timestamp(1_792_002_031).flags(0x1826);
+        let csd = builder.add_string("Service Pack 1");
+        builder
+            .add_stream(
+                stream_type::SYSTEM_INFO,
+                &system_info_stream(9, (10, 0, 19045), csd),
+            )
+            .add_stream(
+                stream_type::MISC_INFO,
+                &misc_info_stream(0x1a2c, 1_792_001_719),
+            )
+            .add_stream(
+                stream_type::EXCEPTION,
+                &exception_stream(0x2b40, 0xc000_0005, 0x7ff6_a1b2_1234, &[0, 8], 0),
+            );
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::*;
+    use super::*;
+    use std::io::Cursor;
+
+    fn open(data: Vec<u8>) -> Result<Minidump<Cursor<Vec<u8>>>, MinidumpError> {
+        Minidump::from_reader(Cursor::new(data))
+    }
+
+    #[test]
+    fn test_read_header_and_directory() {
+        let dump = open(sample_dump()).unwrap();
+        let header = dump.header();
+        assert_eq!(header.version, 0xa793);
+        assert_eq!(header.number_of_streams, 3);
+        assert_eq!(header.timestamp, 1_792_002_031);
+        assert!(header.has_full_memory());
+
+        let types: Vec<u32> = dump.streams().iter().map(|s| s.stream_type).collect();
+        assert_eq!(
+            types,
+            [
+                stream_type::SYSTEM_INFO,
+                stream_type::MISC_INFO,
+                stream_type::EXCEPTION
+            ]
+        );
+        assert_eq!(
+            dump.find_stream(stream_type::MISC_INFO)
+                .unwrap()
+                .location
+                .data_size,
+            24
+        );
+        assert!(dump.find_stream(stream_type::THREAD_LIST).is_none());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut dump = open(sample_dump()).unwrap();
+        let summary = dump.summary();
+        assert_eq!(summary.crash_time.as_deref(), Some("2026-10-14T18:20:31Z"));
+        assert!(summary.full_memory);
+        assert_eq!(summary.architecture, Some(Architecture::X64));
+        assert_eq!(summary.os_version.as_deref(), Some("10.0.19045"));
+        assert_eq!(summary.process_id, Some(0x1a2c));
+        assert_eq!(
+            summary.process_create_time.as_deref(),
+            Some("2026-10-14T18:15:19Z")
+        );
+        assert_eq!(summary.exception_code, Some(0xc000_0005));
+        assert_eq!(summary.exception_address, Some(0x7ff6_a1b2_1234));
+        assert_eq!(summary.exception_thread_id, Some(0x2b40));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["exception_code"], "0xc0000005");
+        assert_eq!(json["architecture"], "x64");
+    }
+
+    #[test]
+    fn test_summary_without_streams() {
+        let mut dump = open(DumpBuilder::new().build()).unwrap();
+        assert!(dump.system_info().unwrap().is_none());
+        assert!(dump.exception().unwrap().is_none());
+
+        let summary = dump.summary();
+        assert_eq!(summary.crash_time, None);
+        assert!(!summary.full_memory);
+        assert_eq!(summary.exception_code, None);
+    }
+
+    #[test]
+    fn test_read_string() {
+        let mut builder = DumpBuilder::new();
+        let rva = builder.add_string("C:\\Windows\\System32\\ntdll.dll");
+        let mut dump = open(builder.build()).unwrap();
+        assert_eq!(
+            dump.read_string(u64::from(rva)).unwrap(),
+            "C:\\Windows\\System32\\ntdll.dll"
+        );
+        assert!(dump.read_string(0xffff_0000).is_err());
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let mut data = b"PAGEDU64".to_vec();
+        data.resize(64, 0);
+        assert!(matches!(
+            open(data),
+            Err(MinidumpError::InvalidSignature(0x4547_4150))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_header() {
+        assert!(matches!(
+            open(b"MDMP\x93\xa7".to_vec()),
+            Err(MinidumpError::Truncated("header"))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_directory() {
+        let mut data = sample_dump();
+        // 声明超出文件长度的流数量
+        data[8..12].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(matches!(
+            open(data),
+            Err(MinidumpError::Truncated("stream directory"))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        let mut builder = DumpBuilder::new();
+        builder.add_stream(stream_type::EXCEPTION, &[0; 16]);
+        let mut dump = open(builder.build()).unwrap();
+        assert!(matches!(
+            dump.exception(),
+            Err(MinidumpError::Truncated("exception stream"))
+        ));
+        // 概要对损坏的流容错
+        assert_eq!(dump.summary().exception_code, None);
+    }
+
+    #[test]
+    fn test_open_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("app.dmp");
+        std::fs::write(&path, sample_dump()).unwrap();
+
+        let mut dump = Minidump::open(&path).unwrap();
+        let info = dump.system_info().unwrap().unwrap();
+        assert_eq!(info.csd_version.as_deref(), Some("Service Pack 1"));
+        assert!(Minidump::open(&dir.path().join("missing.dmp")).is_err());
+    }
+
+    #[test]
+    fn test_inspect_dump() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let full = dir.path().join("full.dmp");
+        std::fs::write(&full, sample_dump()).unwrap();
+        let details = inspect_dump(&full);
+        assert_eq!(details.dump_type, DumpType::Full);
+        assert_eq!(details.exception_code, Some(0xc000_0005));
+        assert_eq!(details.bugcheck_code, None);
+
+        let mini = dir.path().join("mini.dmp");
+        std::fs::write(&mini, DumpBuilder::new().flags(0x1).build()).unwrap();
+        let details = inspect_dump(&mini);
+        assert_eq!(details.dump_type, DumpType::Mini);
+        assert_eq!(details.exception_code, None);
+
+        let mut header = KERNEL_DUMP64_SIGNATURE.to_vec();
+        header.resize(0x1000, 0);
+        header[0x38..0x3c].copy_from_slice(&0xd1u32.to_le_bytes());
+        let kernel = dir.path().join("MEMORY.DMP");
+        std::fs::write(&kernel, &header).unwrap();
+        let details = inspect_dump(&kernel);
+        assert_eq!(details.dump_type, DumpType::Kernel);
+        assert_eq!(details.bugcheck_code, Some(0xd1));
+
+        let mut header = KERNEL_DUMP32_SIGNATURE.to_vec();
+        header.resize(0x1000, 0);
+        header[0x28..0x2c].copy_from_slice(&0x7eu32.to_le_bytes());
+        std::fs::write(&kernel, &header).unwrap();
+        assert_eq!(inspect_dump(&kernel).bugcheck_code, Some(0x7e));
+
+        let text = dir.path().join("notes.dmp");
+        std::fs::write(&text, "not a dump").unwrap();
+        assert_eq!(inspect_dump(&text).dump_type, DumpType::Unknown);
+        assert_eq!(
+            inspect_dump(&dir.path().join("missing.dmp")).dump_type,
+            DumpType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_list_entries() {
+        let data = [2, 0, 0, 0, 0xaa, 0xbb, 0xcc, 0xdd];
+        assert_eq!(list_entries(&Bytes::new(&data, "list"), 2).unwrap(), (2, 4));
+        let padded = [1, 0, 0, 0, 0, 0, 0, 0, 0xaa, 0xbb, 0xcc, 0xdd];
+        assert_eq!(
+            list_entries(&Bytes::new(&padded, "list"), 4).unwrap(),
+            (1, 8)
+        );
+        assert!(list_entries(&Bytes::new(&data, "list"), 4).is_err());
+    }
+
+    #[test]
+    fn test_bytes_bounds() {
+        let bytes = Bytes::new(&[1, 2, 3, 4, 5], "test");
+        assert_eq!(bytes.len(), 5);
+        assert_eq!(bytes.u8(4).unwrap(), 5);
+        assert_eq!(bytes.u32(0).unwrap(), 0x0403_0201);
+        assert!(bytes.u32(2).is_err());
+        assert!(bytes.slice(usize::MAX, 2).is_err());
+    }
+}
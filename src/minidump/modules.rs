@@ -0,0 +1,424 @@
+//! ModuleListStream 和 UnloadedModuleListStream 解析（包含 CodeView PDB 信息）
+
+use super::{list_entries, Bytes, Location};
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use crate::parser::modules::{ModuleInfo, SymbolStatus};
+use serde::Serialize;
+
+/// MINIDUMP_MODULE 的大小
+const MODULE_SIZE: usize = 108;
+
+/// MINIDUMP_UNLOADED_MODULE 的大小
+const UNLOADED_MODULE_SIZE: usize = 24;
+
+/// VS_FIXEDFILEINFO 签名
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef_04bd;
+
+/// CodeView 7.0 记录签名（`RSDS`）
+const CV_SIGNATURE_RSDS: u32 = 0x5344_5352;
+
+/// CodeView 2.0 记录签名（`NB10`）
+const CV_SIGNATURE_NB10: u32 = 0x3031_424e;
+
+/// CodeView 记录的最大长度，防止损坏的文件导致过大的分配
+pub(crate) const MAX_CV_RECORD_BYTES: u32 = 4096;
+
+/// 已加载模块
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MinidumpModule {
+    /// 模块基址
+    #[serde(with = "hex::plain")]
+    pub base: u64,
+    /// 映像大小
+    pub size: u32,
+    /// PE 校验和
+    #[serde(with = "hex::plain")]
+    pub checksum: u32,
+    /// 链接时间戳
+    #[serde(with = "hex::plain")]
+    pub timestamp: u32,
+    /// 映像完整路径
+    pub path: String,
+    /// 文件版本（VS_FIXEDFILEINFO）
+    pub file_version: Option<String>,
+    /// 产品版本（VS_FIXEDFILEINFO）
+    pub product_version: Option<String>,
+    /// CodeView 调试信息（PDB 文件名、GUID 和 Age）
+    pub codeview: Option<CodeViewInfo>,
+    /// 模块名字符串的 RVA
+    #[serde(skip)]
+    pub(crate) name_rva: u32,
+    /// CodeView 记录的位置
+    #[serde(skip)]
+    pub(crate) cv_record: Location,
+}
+
+impl MinidumpModule {
+    /// 映像文件名（例如 `contoso.exe`）
+    pub fn file_name(&self) -> &str {
+        file_name(&self.path)
+    }
+
+    /// 模块名（与 CDB 一致，为去掉扩展名的文件名，例如 `contoso`）
+    pub fn name(&self) -> &str {
+        let file_name = self.file_name();
+        file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem)
+    }
+
+    /// 映像结束地址（不含）
+    pub fn end(&self) -> u64 {
+        self.base.saturating_add(u64::from(self.size))
+    }
+
+    /// 是否包含指定地址
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.base && address < self.end()
+    }
+
+    /// 转换为与 `lm v` 解析结果相同的结构
+    ///
+    /// 离线读取时没有尝试加载符号，符号状态为 [`SymbolStatus::Deferred`]。
+    pub fn to_module_info(&self) -> ModuleInfo {
+        let file_name = self.file_name();
+
+        ModuleInfo {
+            start: Some(self.base),
+            end: Some(self.end()),
+            name: self.name().to_string(),
+            symbol_status: SymbolStatus::Deferred,
+            symbol_file: self.codeview.as_ref().map(|cv| cv.pdb_file.clone()),
+            image_path: Some(self.path.clone()).filter(|p| !p.is_empty()),
+            image_name: Some(file_name.to_string()).filter(|n| !n.is_empty()),
+            timestamp: Some(u64::from(self.timestamp)),
+            checksum: Some(u64::from(self.checksum)),
+            file_version: self.file_version.clone(),
+            product_version: self.product_version.clone(),
+            company: None,
+        }
+    }
+}
+
+/// CodeView 调试信息
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CodeViewInfo {
+    /// PDB 文件路径（链接时记录的路径）
+    pub pdb_file: String,
+    /// PDB GUID（RSDS 格式，例如 `{3F2504E0-4F89-11D3-9A0C-0305E82C3301}`）
+    pub guid: Option<String>,
+    /// PDB 签名（NB10 格式）
+    pub signature: Option<u32>,
+    /// PDB Age
+    pub age: u32,
+    /// 符号服务器上的索引目录（GUID/签名 + Age，例如 `3F2504E04F8911D39A0C0305E82C33011`）
+    pub symbol_key: String,
+}
+
+impl CodeViewInfo {
+    /// 解析 CodeView 记录（支持 RSDS 和 NB10）
+    ///
+    /// # 返回
+    /// 记录格式无法识别时返回 None
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let bytes = Bytes::new(data, "codeview record");
+        match bytes.u32(0).ok()? {
+            CV_SIGNATURE_RSDS => {
+                let g = bytes.slice(4, 16).ok()?;
+                let data1 = u32::from_le_bytes([g[0], g[1], g[2], g[3]]);
+                let data2 = u16::from_le_bytes([g[4], g[5]]);
+                let data3 = u16::from_le_bytes([g[6], g[7]]);
+                let data4: String = g[8..].iter().map(|b| format!("{:02X}", b)).collect();
+                let age = bytes.u32(20).ok()?;
+                Some(Self {
+                    pdb_file: nul_terminated(bytes.slice(24, data.len() - 24).ok()?),
+                    guid: Some(format!(
+                        "{{{:08X}-{:04X}-{:04X}-{}-{}}}",
+                        data1,
+                        data2,
+                        data3,
+                        &data4[..4],
+                        &data4[4..]
+                    )),
+                    signature: None,
+                    age,
+                    symbol_key: format!(
+                        "{:08X}{:04X}{:04X}{}{:X}",
+                        data1, data2, data3, data4, age
+                    ),
+                })
+            }
+            CV_SIGNATURE_NB10 => {
+                let signature = bytes.u32(8).ok()?;
+                let age = bytes.u32(12).ok()?;
+                Some(Self {
+                    pdb_file: nul_terminated(bytes.slice(16, data.len() - 16).ok()?),
+                    guid: None,
+                    signature: Some(signature),
+                    age,
+                    symbol_key: format!("{:X}{:X}", signature, age),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 已卸载模块
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UnloadedModule {
+    /// 模块基址
+    #[serde(with = "hex::plain")]
+    pub base: u64,
+    /// 映像大小
+    pub size: u32,
+    /// PE 校验和
+    #[serde(with = "hex::plain")]
+    pub checksum: u32,
+    /// 链接时间戳
+    #[serde(with = "hex::plain")]
+    pub timestamp: u32,
+    /// 映像完整路径
+    pub path: String,
+    /// 模块名字符串的 RVA
+    #[serde(skip)]
+    pub(crate) name_rva: u32,
+}
+
+/// 解析 ModuleListStream（不读取模块名和 CodeView 记录）
+///
+/// # 错误
+/// 如果模块数量与流大小不符，返回错误
+pub(crate) fn parse_module_list(data: &[u8]) -> Result<Vec<MinidumpModule>, MinidumpError> {
+    let bytes = Bytes::new(data, "module list stream");
+    let (count, first) = list_entries(&bytes, MODULE_SIZE)?;
+
+    (0..count)
+        .map(|i| {
+            let offset = first + i * MODULE_SIZE;
+            Ok(MinidumpModule {
+                base: bytes.u64(offset)?,
+                size: bytes.u32(offset + 8)?,
+                checksum: bytes.u32(offset + 12)?,
+                timestamp: bytes.u32(offset + 16)?,
+                name_rva: bytes.u32(offset + 20)?,
+                file_version: fixed_file_version(&bytes, offset + 24, 8)?,
+                product_version: fixed_file_version(&bytes, offset + 24, 16)?,
+                cv_record: Location {
+                    data_size: bytes.u32(offset + 76)?,
+                    rva: bytes.u32(offset + 80)?,
+                },
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// 解析 UnloadedModuleListStream（不读取模块名）
+///
+/// # 错误
+/// 如果头部或条目不完整，返回错误
+pub(crate) fn parse_unloaded_module_list(
+    data: &[u8],
+) -> Result<Vec<UnloadedModule>, MinidumpError> {
+    let bytes = Bytes::new(data, "unloaded module list stream");
+    let header_size = bytes.u32(0)? as usize;
+    let entry_size = bytes.u32(4)? as usize;
+    let count = bytes.u32(8)? as usize;
+    if entry_size < UNLOADED_MODULE_SIZE
+        || count
+            .checked_mul(entry_size)
+            .and_then(|size| size.checked_add(header_size))
+            .is_none_or(|end| end > bytes.len())
+    {
+        return Err(MinidumpError::Truncated("unloaded module list stream"));
+    }
+
+    (0..count)
+        .map(|i| {
+            let offset = header_size + i * entry_size;
+            Ok(UnloadedModule {
+                base: bytes.u64(offset)?,
+                size: bytes.u32(offset + 8)?,
+                checksum: bytes.u32(offset + 12)?,
+                timestamp: bytes.u32(offset + 16)?,
+                name_rva: bytes.u32(offset + 20)?,
+                path: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// 从 VS_FIXEDFILEINFO 读取版本号（`offset` 为结构起始位置，`field` 为 MS 字段偏移）
+fn fixed_file_version(
+    bytes: &Bytes,
+    offset: usize,
+    field: usize,
+) -> Result<Option<String>, MinidumpError> {
+    if bytes.u32(offset)? != FIXED_FILE_INFO_SIGNATURE {
+        return Ok(None);
+    }
+    let ms = bytes.u32(offset + field)?;
+    let ls = bytes.u32(offset + field + 4)?;
+    Ok(Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xffff,
+        ls >> 16,
+        ls & 0xffff
+    )))
+}
+
+/// 路径中的文件名部分（同时支持 `\` 和 `/` 分隔符）
+fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+/// 读取以 NUL 结尾的 UTF-8 字符串
+fn nul_terminated(data: &[u8]) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::*;
+    use super::super::{stream_type, Minidump};
+    use super::*;
+    use std::io::Cursor;
+
+    const GUID: [u8; 16] = [
+        0xe0, 0x04, 0x25, 0x3f, 0x89, 0x4f, 0xd3, 0x11, 0x9a, 0x0c, 0x03, 0x05, 0xe8, 0x2c, 0x33,
+        0x01,
+    ];
+
+    #[test]
+    fn test_parse_rsds() {
+        let cv = CodeViewInfo::parse(&rsds_record(&GUID, 1, "C:\\build\\contoso.pdb")).unwrap();
+        assert_eq!(
+            cv.guid.as_deref(),
+            Some("{3F2504E0-4F89-11D3-9A0C-0305E82C3301}")
+        );
+        assert_eq!(cv.age, 1);
+        assert_eq!(cv.pdb_file, "C:\\build\\contoso.pdb");
+        assert_eq!(cv.symbol_key, "3F2504E04F8911D39A0C0305E82C33011");
+    }
+
+    #[test]
+    fn test_parse_nb10() {
+        let mut record = CV_SIGNATURE_NB10.to_le_bytes().to_vec();
+        record.extend_from_slice(&0u32.to_le_bytes());
+        record.extend_from_slice(&0x3a2b_1c0du32.to_le_bytes());
+        record.extend_from_slice(&0x2u32.to_le_bytes());
+        record.extend_from_slice(b"legacy.pdb\0");
+
+        let cv = CodeViewInfo::parse(&record).unwrap();
+        assert_eq!(cv.guid, None);
+        assert_eq!(cv.signature, Some(0x3a2b_1c0d));
+        assert_eq!(cv.pdb_file, "legacy.pdb");
+        assert_eq!(cv.symbol_key, "3A2B1C0D2");
+        assert!(CodeViewInfo::parse(b"XXXX").is_none());
+    }
+
+    #[test]
+    fn test_read_module_list() {
+        let mut builder = DumpBuilder::new();
+        let name = builder.add_string("C:\\Program Files\\Contoso\\contoso.exe");
+        let cv = rsds_record(&GUID, 3, "contoso.pdb");
+        let cv_rva = builder.append(&cv);
+        let ntdll = builder.add_string("C:\\Windows\\System32\\ntdll.dll");
+        builder.add_stream(
+            stream_type::MODULE_LIST,
+            &module_list_stream(&[
+                ModuleSpec {
+                    base: 0x7ff6_a1b2_0000,
+                    size: 0x2f000,
+                    timestamp: 0x6a2e_0f6f,
+                    name_rva: name,
+                    version: Some((0x0001_0004, 0x0002_0000)),
+                    cv_record: (cv.len() as u32, cv_rva),
+                },
+                ModuleSpec {
+                    base: 0x7ffb_1d8c_0000,
+                    size: 0x1f8000,
+                    timestamp: 0x2f8b_6e32,
+                    name_rva: ntdll,
+                    version: None,
+                    cv_record: (0, 0),
+                },
+            ]),
+        );
+        let mut dump = Minidump::from_reader(Cursor::new(builder.build())).unwrap();
+
+        let modules = dump.modules().unwrap();
+        assert_eq!(modules.len(), 2);
+        let contoso = &modules[0];
+        assert_eq!(contoso.path, "C:\\Program Files\\Contoso\\contoso.exe");
+        assert_eq!(contoso.file_name(), "contoso.exe");
+        assert_eq!(contoso.name(), "contoso");
+        assert!(contoso.contains(0x7ff6_a1b2_1234));
+        assert!(!contoso.contains(0x7ff6_a1b4_f000));
+        assert_eq!(contoso.file_version.as_deref(), Some("1.4.2.0"));
+        let codeview = contoso.codeview.as_ref().unwrap();
+        assert_eq!(codeview.pdb_file, "contoso.pdb");
+        assert_eq!(codeview.age, 3);
+        assert_eq!(modules[1].file_version, None);
+        assert_eq!(modules[1].codeview, None);
+
+        let info = contoso.to_module_info();
+        assert_eq!(info.name, "contoso");
+        assert_eq!(info.start, Some(0x7ff6_a1b2_0000));
+        assert_eq!(info.end, Some(0x7ff6_a1b4_f000));
+        assert_eq!(info.symbol_status, SymbolStatus::Deferred);
+        assert_eq!(info.image_name.as_deref(), Some("contoso.exe"));
+        assert!(info.is_third_party());
+        assert!(!modules[1].to_module_info().is_third_party());
+
+        let json = serde_json::to_value(contoso).unwrap();
+        assert_eq!(json["base"], "0x7ff6a1b20000");
+        assert_eq!(
+            json["codeview"]["symbol_key"],
+            "3F2504E04F8911D39A0C0305E82C33013"
+        );
+    }
+
+    #[test]
+    fn test_module_list_with_padding() {
+        // 部分写入器在数量字段后填充 4 字节
+        let mut data = 1u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 4]);
+        let mut module = vec![0u8; MODULE_SIZE];
+        module[0..8].copy_from_slice(&0x1000_0000u64.to_le_bytes());
+        data.extend_from_slice(&module);
+
+        let modules = parse_module_list(&data).unwrap();
+        assert_eq!(modules[0].base, 0x1000_0000);
+        assert!(parse_module_list(&data[..50]).is_err());
+    }
+
+    #[test]
+    fn test_read_unloaded_modules() {
+        let mut builder = DumpBuilder::new();
+        let name = builder.add_string("hook64.dll");
+        builder.add_stream(
+            stream_type::UNLOADED_MODULE_LIST,
+            &unloaded_module_list_stream(&[(0x7ffb_0000_0000, 0x4000, name)]),
+        );
+        let mut dump = Minidump::from_reader(Cursor::new(builder.build())).unwrap();
+
+        let unloaded = dump.unloaded_modules().unwrap();
+        assert_eq!(unloaded.len(), 1);
+        assert_eq!(unloaded[0].path, "hook64.dll");
+        assert_eq!(unloaded[0].size, 0x4000);
+
+        assert!(parse_unloaded_module_list(&[12, 0, 0, 0, 24, 0, 0, 0, 5, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_missing_streams() {
+        let mut dump = Minidump::from_reader(Cursor::new(DumpBuilder::new().build())).unwrap();
+        assert!(dump.modules().unwrap().is_empty());
+        assert!(dump.unloaded_modules().unwrap().is_empty());
+    }
+}
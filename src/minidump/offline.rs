@@ -0,0 +1,466 @@
+//! 无调试器的离线分析
+//!
+//! 找不到 cdb.exe 时，只根据转储文件中的原始数据给出初步结论：异常记录、
+//! 故障线程及其寄存器上下文、模块列表和栈指针附近的原始栈内存。
+//! 离线分析不进行符号解析，地址只标注为 `模块+偏移`。
+
+use super::context::parse_context;
+use super::exception::ExceptionInfo;
+use super::memory::MemoryRegion;
+use super::modules::MinidumpModule;
+use super::threads::MinidumpThread;
+use super::{Minidump, MinidumpSummary};
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use crate::parser::registers::{Architecture, RegisterContext};
+use serde::Serialize;
+use std::io::{Read, Seek};
+
+/// 离线分析的符号解析状态
+pub const NO_SYMBOLICATION: &str = "none";
+
+/// 读取栈指针以下（低地址，已出栈区域）的字节数
+const STACK_BYTES_BELOW_SP: u64 = 0x40;
+
+/// 读取栈指针以上（高地址，调用者帧）的字节数
+const STACK_BYTES_ABOVE_SP: u64 = 0x400;
+
+/// 离线分析结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OfflineReport {
+    /// 符号解析状态（固定为 `none`）
+    pub symbolication: &'static str,
+    /// 转储概要
+    pub summary: MinidumpSummary,
+    /// 异常记录
+    pub exception: Option<OfflineException>,
+    /// 触发异常的线程
+    pub faulting_thread: Option<FaultingThread>,
+    /// 故障线程栈指针附近的原始栈内存
+    pub stack_memory: Option<StackMemory>,
+    /// 已加载模块
+    pub modules: Vec<MinidumpModule>,
+    /// 全部线程
+    pub threads: Vec<MinidumpThread>,
+    /// 读取过程中遇到的问题（流缺失或损坏）
+    pub warnings: Vec<String>,
+}
+
+/// 带解释的异常记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OfflineException {
+    /// 原始异常记录
+    #[serde(flatten)]
+    pub record: ExceptionInfo,
+    /// 异常名称（例如 `ACCESS_VIOLATION`）
+    pub name: Option<&'static str>,
+    /// 异常地址所在模块（`模块+偏移`）
+    pub module: Option<String>,
+    /// 异常参数的解释（例如访问违例的读写类型和目标地址）
+    pub description: Option<String>,
+}
+
+/// 触发异常的线程
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FaultingThread {
+    /// 线程信息
+    #[serde(flatten)]
+    pub thread: MinidumpThread,
+    /// 异常时的寄存器上下文（优先使用 ExceptionStream 中的上下文）
+    pub registers: Option<RegisterContext>,
+    /// 指令指针所在模块（`模块+偏移`）
+    pub instruction_module: Option<String>,
+}
+
+/// 原始栈内存
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackMemory {
+    /// 栈指针
+    #[serde(with = "hex::plain")]
+    pub stack_pointer: u64,
+    /// 第一个字的地址
+    #[serde(with = "hex::plain")]
+    pub start: u64,
+    /// 指针大小（字节）
+    pub pointer_size: usize,
+    /// 按指针大小划分的栈内容
+    pub words: Vec<StackWord>,
+}
+
+/// 栈上的一个指针大小的值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackWord {
+    /// 地址
+    #[serde(with = "hex::plain")]
+    pub address: u64,
+    /// 值
+    #[serde(with = "hex::plain")]
+    pub value: u64,
+    /// 值指向的模块（`模块+偏移`，可能是返回地址）
+    pub module: Option<String>,
+}
+
+/// 常见异常代码的名称
+pub fn exception_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        0x8000_0002 => "DATATYPE_MISALIGNMENT",
+        0x8000_0003 => "BREAKPOINT",
+        0x8000_0004 => "SINGLE_STEP",
+        0xc000_0005 => "ACCESS_VIOLATION",
+        0xc000_0006 => "IN_PAGE_ERROR",
+        0xc000_0008 => "INVALID_HANDLE",
+        0xc000_001d => "ILLEGAL_INSTRUCTION",
+        0xc000_0025 => "NONCONTINUABLE_EXCEPTION",
+        0xc000_008c => "ARRAY_BOUNDS_EXCEEDED",
+        0xc000_0094 => "INTEGER_DIVIDE_BY_ZERO",
+        0xc000_0095 => "INTEGER_OVERFLOW",
+        0xc000_0096 => "PRIVILEGED_INSTRUCTION",
+        0xc000_00fd => "STACK_OVERFLOW",
+        0xc000_0374 => "HEAP_CORRUPTION",
+        0xc000_0409 => "STACK_BUFFER_OVERRUN",
+        0xc000_0417 => "INVALID_CRUNTIME_PARAMETER",
+        0xc000_0602 => "FAIL_FAST_EXCEPTION",
+        0xe06d_7363 => "CPP_EH_EXCEPTION",
+        _ => return None,
+    })
+}
+
+/// 将地址标注为 `模块+偏移`
+///
+/// # 返回
+/// 地址不在任何模块内时返回 None
+pub fn module_offset(modules: &[MinidumpModule], address: u64) -> Option<String> {
+    modules
+        .iter()
+        .find(|m| m.contains(address))
+        .map(|m| format!("{}+{:#x}", m.name(), address - m.base))
+}
+
+/// 解释访问违例和页错误的异常参数
+fn describe_exception(record: &ExceptionInfo) -> Option<String> {
+    if !matches!(record.code, 0xc000_0005 | 0xc000_0006) {
+        return None;
+    }
+    let access = match record.parameters.first()? {
+        0 => "read from",
+        1 => "write to",
+        8 => "execute",
+        _ => return None,
+    };
+    let target = record.parameters.get(1)?;
+    Some(format!("Attempt to {} address {:#x}", access, target))
+}
+
+/// 离线分析转储
+///
+/// 尽力而为：单个流缺失或损坏时对应字段为空，并在 `warnings` 中说明。
+///
+/// # 参数
+/// * `dump` - 已打开的 minidump
+pub fn analyze<R: Read + Seek>(dump: &mut Minidump<R>) -> OfflineReport {
+    let mut report = OfflineReport {
+        symbolication: NO_SYMBOLICATION,
+        summary: dump.summary(),
+        ..Default::default()
+    };
+
+    match dump.modules() {
+        Ok(modules) => report.modules = modules,
+        Err(e) => report
+            .warnings
+            .push(format!("Module list unavailable: {}", e)),
+    }
+    match dump.threads() {
+        Ok(threads) => report.threads = threads,
+        Err(e) => report
+            .warnings
+            .push(format!("Thread list unavailable: {}", e)),
+    }
+
+    let record = match dump.exception() {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            report
+                .warnings
+                .push("Dump has no exception stream".to_string());
+            return report;
+        }
+        Err(e) => {
+            report
+                .warnings
+                .push(format!("Exception record unavailable: {}", e));
+            return report;
+        }
+    };
+
+    let architecture = report.summary.architecture.unwrap_or_default();
+    let thread = report
+        .threads
+        .iter()
+        .find(|t| t.thread_id == record.thread_id)
+        .cloned();
+
+    // 异常上下文描述异常发生时的状态；缺失时使用线程列表中的上下文
+    let context_location = Some(record.thread_context)
+        .filter(|l| l.rva != 0 && l.data_size != 0)
+        .or_else(|| thread.as_ref().map(|t| t.context))
+        .filter(|l| l.rva != 0 && l.data_size != 0);
+    let registers = context_location.and_then(|location| {
+        match dump
+            .read_location(location, "thread context")
+            .and_then(|data| parse_context(&data, architecture))
+        {
+            Ok(registers) => registers,
+            Err(e) => {
+                report
+                    .warnings
+                    .push(format!("Thread context unavailable: {}", e));
+                None
+            }
+        }
+    });
+
+    let stack_pointer = registers.as_ref().and_then(|r| r.stack_pointer);
+    if let Some(stack_pointer) = stack_pointer {
+        let pointer_size = match registers.as_ref().map(|r| r.architecture) {
+            Some(Architecture::X86) => 4,
+            _ => 8,
+        };
+        match read_stack(dump, thread.as_ref(), stack_pointer, pointer_size) {
+            Ok(Some((start, data))) => {
+                report.stack_memory = Some(StackMemory {
+                    stack_pointer,
+                    start,
+                    pointer_size,
+                    words: stack_words(&report.modules, start, &data, pointer_size),
+                });
+            }
+            Ok(None) => report.warnings.push(format!(
+                "Stack memory at {:#x} is not present in the dump",
+                stack_pointer
+            )),
+            Err(e) => report
+                .warnings
+                .push(format!("Stack memory unavailable: {}", e)),
+        }
+    }
+
+    report.faulting_thread = Some(FaultingThread {
+        instruction_module: registers
+            .as_ref()
+            .and_then(|r| r.instruction_pointer)
+            .and_then(|ip| module_offset(&report.modules, ip)),
+        thread: thread.unwrap_or_else(|| MinidumpThread {
+            thread_id: record.thread_id,
+            ..Default::default()
+        }),
+        registers,
+    });
+    report.exception = Some(OfflineException {
+        name: exception_name(record.code),
+        module: module_offset(&report.modules, record.address),
+        description: describe_exception(&record),
+        record,
+    });
+
+    report
+}
+
+/// 读取栈指针附近的内存，返回 `(起始地址, 数据)`
+///
+/// 优先使用线程列表中保存的栈内存，其次在内存列表中查找。
+fn read_stack<R: Read + Seek>(
+    dump: &mut Minidump<R>,
+    thread: Option<&MinidumpThread>,
+    stack_pointer: u64,
+    pointer_size: usize,
+) -> Result<Option<(u64, Vec<u8>)>, MinidumpError> {
+    let region: Option<MemoryRegion> = match thread
+        .and_then(MinidumpThread::stack_region)
+        .filter(|r| r.contains(stack_pointer))
+    {
+        Some(region) => Some(region),
+        None => dump.find_memory(stack_pointer)?,
+    };
+    let Some(region) = region else {
+        return Ok(None);
+    };
+
+    let align = !(pointer_size as u64 - 1);
+    let window = region.clip(
+        stack_pointer.saturating_sub(STACK_BYTES_BELOW_SP) & align,
+        stack_pointer.saturating_add(STACK_BYTES_ABOVE_SP),
+    );
+    match window {
+        Some(window) => Ok(Some((window.start, dump.read_memory(&window)?))),
+        None => Ok(None),
+    }
+}
+
+/// 将栈内存按指针大小划分，并标注指向模块内的值
+fn stack_words(
+    modules: &[MinidumpModule],
+    start: u64,
+    data: &[u8],
+    pointer_size: usize,
+) -> Vec<StackWord> {
+    data.chunks_exact(pointer_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut bytes = [0u8; 8];
+            bytes[..pointer_size].copy_from_slice(chunk);
+            let value = u64::from_le_bytes(bytes);
+            StackWord {
+                address: start + (i * pointer_size) as u64,
+                value,
+                module: module_offset(modules, value),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::stream_type;
+    use super::super::testing::*;
+    use super::*;
+    use std::io::Cursor;
+
+    const STACK_START: u64 = 0xd12f_f000;
+    const STACK_POINTER: u64 = 0xd12f_f7f0;
+
+    /// 线程 0x2b40 在 contoso 内访问违例，栈上有一个指向 contoso 的返回地址
+    fn crash_dump(with_exception_context: bool) -> Vec<u8> {
+        let mut builder = DumpBuilder::new();
+        let name = builder.add_string("C:\\Program Files\\Contoso\\contoso.exe");
+        builder.add_stream(
+            stream_type::MODULE_LIST,
+            &module_list_stream(&[ModuleSpec {
+                base: 0x7ff6_a1b2_0000,
+                size: 0x2f000,
+                timestamp: 0,
+                name_rva: name,
+                version: None,
+                cv_record: (0, 0),
+            }]),
+        );
+        builder.add_stream(
+            stream_type::SYSTEM_INFO,
+            &system_info_stream(9, (10, 0, 19045), 0),
+        );
+
+        let mut stack = vec![0u8; 0x1000];
+        let slot = (STACK_POINTER - STACK_START) as usize + 8;
+        stack[slot..slot + 8].copy_from_slice(&0x7ff6_a1b2_5678u64.to_le_bytes());
+        let stack_rva = builder.append(&stack);
+
+        let mut context = vec![0u8; 0x4d0];
+        context[0x98..0xa0].copy_from_slice(&STACK_POINTER.to_le_bytes());
+        context[0xf8..0x100].copy_from_slice(&0x7ff6_a1b2_1234u64.to_le_bytes());
+        let context_rva = builder.append(&context);
+
+        builder.add_stream(
+            stream_type::THREAD_LIST,
+            &thread_list_stream(&[(0x2b40, 0, STACK_START, (0x1000, stack_rva))]),
+        );
+        builder.add_stream(
+            stream_type::EXCEPTION,
+            &exception_stream(
+                0x2b40,
+                0xc000_0005,
+                0x7ff6_a1b2_1234,
+                &[0, 8],
+                if with_exception_context {
+                    context_rva
+                } else {
+                    0
+                },
+            ),
+        );
+        builder.build()
+    }
+
+    #[test]
+    fn test_analyze_crash() {
+        let mut dump = Minidump::from_reader(Cursor::new(crash_dump(true))).unwrap();
+        let report = analyze(&mut dump);
+        assert_eq!(report.symbolication, "none");
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+        let exception = report.exception.as_ref().unwrap();
+        assert_eq!(exception.name, Some("ACCESS_VIOLATION"));
+        assert_eq!(exception.module.as_deref(), Some("contoso+0x1234"));
+        assert_eq!(
+            exception.description.as_deref(),
+            Some("Attempt to read from address 0x8")
+        );
+
+        let thread = report.faulting_thread.as_ref().unwrap();
+        assert_eq!(thread.thread.thread_id, 0x2b40);
+        let registers = thread.registers.as_ref().unwrap();
+        assert_eq!(registers.stack_pointer, Some(STACK_POINTER));
+        assert_eq!(thread.instruction_module.as_deref(), Some("contoso+0x1234"));
+
+        let stack = report.stack_memory.as_ref().unwrap();
+        assert_eq!(stack.pointer_size, 8);
+        assert_eq!(stack.start, STACK_POINTER - 0x40);
+        assert_eq!(stack.words.len(), (0x40 + 0x400) / 8);
+        let word = stack
+            .words
+            .iter()
+            .find(|w| w.address == STACK_POINTER + 8)
+            .unwrap();
+        assert_eq!(word.value, 0x7ff6_a1b2_5678);
+        assert_eq!(word.module.as_deref(), Some("contoso+0x5678"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["symbolication"], "none");
+        assert_eq!(json["exception"]["code"], "0xc0000005");
+        assert_eq!(json["faulting_thread"]["thread_id"], "0x2b40");
+        assert_eq!(json["stack_memory"]["stack_pointer"], "0xd12ff7f0");
+    }
+
+    #[test]
+    fn test_analyze_without_context() {
+        // 没有异常上下文时使用线程上下文；线程上下文为空时没有栈内存
+        let mut dump = Minidump::from_reader(Cursor::new(crash_dump(false))).unwrap();
+        let report = analyze(&mut dump);
+        let thread = report.faulting_thread.as_ref().unwrap();
+        assert!(thread.registers.is_none());
+        assert!(report.stack_memory.is_none());
+        assert_eq!(
+            report.exception.as_ref().unwrap().name,
+            Some("ACCESS_VIOLATION")
+        );
+    }
+
+    #[test]
+    fn test_analyze_without_exception() {
+        let mut dump = Minidump::from_reader(Cursor::new(DumpBuilder::new().build())).unwrap();
+        let report = analyze(&mut dump);
+        assert!(report.exception.is_none());
+        assert!(report.faulting_thread.is_none());
+        assert_eq!(report.warnings, ["Dump has no exception stream"]);
+    }
+
+    #[test]
+    fn test_exception_helpers() {
+        assert_eq!(exception_name(0xc000_00fd), Some("STACK_OVERFLOW"));
+        assert_eq!(exception_name(0x1234), None);
+
+        let record = ExceptionInfo {
+            code: 0xc000_0005,
+            parameters: vec![8, 0xdead_0000],
+            ..Default::default()
+        };
+        assert_eq!(
+            describe_exception(&record).as_deref(),
+            Some("Attempt to execute address 0xdead0000")
+        );
+        let record = ExceptionInfo {
+            code: 0x8000_0003,
+            ..Default::default()
+        };
+        assert_eq!(describe_exception(&record), None);
+    }
+}
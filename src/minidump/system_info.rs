@@ -0,0 +1,118 @@
+//! SystemInfoStream（MINIDUMP_SYSTEM_INFO）解析
+
+use super::Bytes;
+use crate::error::MinidumpError;
+use crate::parser::registers::Architecture;
+use serde::Serialize;
+
+/// MINIDUMP_SYSTEM_INFO 的大小
+const SYSTEM_INFO_SIZE: usize = 56;
+
+/// 系统信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SystemInfo {
+    /// CPU 架构
+    pub architecture: Architecture,
+    /// 原始处理器架构值（PROCESSOR_ARCHITECTURE_*）
+    pub processor_architecture: u16,
+    /// 处理器级别
+    pub processor_level: u16,
+    /// 处理器修订号
+    pub processor_revision: u16,
+    /// 处理器数量
+    pub number_of_processors: u8,
+    /// 产品类型（1 为工作站，2 为域控制器，3 为服务器）
+    pub product_type: u8,
+    /// 主版本号
+    pub major_version: u32,
+    /// 次版本号
+    pub minor_version: u32,
+    /// 构建号
+    pub build_number: u32,
+    /// 平台 ID（2 为 Windows NT）
+    pub platform_id: u32,
+    /// Service Pack 字符串
+    pub csd_version: Option<String>,
+    /// Service Pack 字符串的 RVA
+    #[serde(skip)]
+    pub(crate) csd_version_rva: u32,
+}
+
+impl SystemInfo {
+    /// 解析流数据（不读取 Service Pack 字符串）
+    ///
+    /// # 错误
+    /// 如果数据短于 MINIDUMP_SYSTEM_INFO，返回错误
+    pub fn parse(data: &[u8]) -> Result<Self, MinidumpError> {
+        let bytes = Bytes::new(data, "system info stream");
+        if bytes.len() < SYSTEM_INFO_SIZE {
+            return Err(MinidumpError::Truncated("system info stream"));
+        }
+
+        let processor_architecture = bytes.u16(0)?;
+        Ok(Self {
+            architecture: architecture_from_raw(processor_architecture),
+            processor_architecture,
+            processor_level: bytes.u16(2)?,
+            processor_revision: bytes.u16(4)?,
+            number_of_processors: bytes.u8(6)?,
+            product_type: bytes.u8(7)?,
+            major_version: bytes.u32(8)?,
+            minor_version: bytes.u32(12)?,
+            build_number: bytes.u32(16)?,
+            platform_id: bytes.u32(20)?,
+            csd_version: None,
+            csd_version_rva: bytes.u32(24)?,
+        })
+    }
+
+    /// 操作系统版本（例如 `10.0.19045`）
+    pub fn os_version(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.major_version, self.minor_version, self.build_number
+        )
+    }
+}
+
+/// 将 PROCESSOR_ARCHITECTURE_* 映射到 [`Architecture`]
+fn architecture_from_raw(value: u16) -> Architecture {
+    match value {
+        0 => Architecture::X86,
+        9 => Architecture::X64,
+        12 => Architecture::Arm64,
+        _ => Architecture::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::system_info_stream;
+    use super::*;
+
+    #[test]
+    fn test_parse_system_info() {
+        let info = SystemInfo::parse(&system_info_stream(9, (10, 0, 22631), 0x40)).unwrap();
+        assert_eq!(info.architecture, Architecture::X64);
+        assert_eq!(info.processor_level, 6);
+        assert_eq!(info.number_of_processors, 8);
+        assert_eq!(info.product_type, 1);
+        assert_eq!(info.platform_id, 2);
+        assert_eq!(info.csd_version_rva, 0x40);
+        assert_eq!(info.os_version(), "10.0.22631");
+    }
+
+    #[test]
+    fn test_architecture_from_raw() {
+        assert_eq!(architecture_from_raw(0), Architecture::X86);
+        assert_eq!(architecture_from_raw(12), Architecture::Arm64);
+        // ARM32 和 IA64 没有对应的寄存器解析，归为未知
+        assert_eq!(architecture_from_raw(5), Architecture::Unknown);
+        assert_eq!(architecture_from_raw(0xffff), Architecture::Unknown);
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        assert!(SystemInfo::parse(&[0; 20]).is_err());
+    }
+}
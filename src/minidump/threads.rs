@@ -0,0 +1,218 @@
+//! ThreadListStream 和 ThreadNamesStream 解析
+
+use super::memory::MemoryRegion;
+use super::{list_entries, Bytes, Location};
+use crate::error::MinidumpError;
+use crate::parser::hex;
+use crate::parser::threads::ThreadInfo;
+use serde::Serialize;
+
+/// MINIDUMP_THREAD 的大小
+const THREAD_SIZE: usize = 48;
+
+/// MINIDUMP_THREAD_NAME 的大小（4 字节对齐打包）
+const THREAD_NAME_SIZE: usize = 12;
+
+/// 线程
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MinidumpThread {
+    /// 线程 ID
+    #[serde(with = "hex::plain")]
+    pub thread_id: u32,
+    /// 挂起计数
+    pub suspend_count: u32,
+    /// 优先级类
+    pub priority_class: u32,
+    /// 线程优先级
+    pub priority: i32,
+    /// 线程环境块地址
+    #[serde(with = "hex::plain")]
+    pub teb: u64,
+    /// 栈内存的起始地址
+    #[serde(with = "hex::plain")]
+    pub stack_start: u64,
+    /// 转储中保存的栈内存大小
+    pub stack_size: u32,
+    /// 线程名（ThreadNamesStream）
+    pub name: Option<String>,
+    /// 栈内存在文件中的位置
+    #[serde(skip)]
+    pub stack_memory: Location,
+    /// 线程上下文（CONTEXT 结构）在文件中的位置
+    #[serde(skip)]
+    pub context: Location,
+}
+
+impl MinidumpThread {
+    /// 转储中保存的栈内存区域
+    ///
+    /// # 返回
+    /// 没有保存栈内存时返回 None
+    pub fn stack_region(&self) -> Option<MemoryRegion> {
+        (self.stack_memory.rva != 0 && self.stack_memory.data_size != 0).then(|| MemoryRegion {
+            start: self.stack_start,
+            size: u64::from(self.stack_memory.data_size),
+            rva: u64::from(self.stack_memory.rva),
+        })
+    }
+
+    /// 转换为与 `~*` 解析结果相同的结构
+    ///
+    /// # 参数
+    /// * `index` - 线程序号（与 CDB 一致，按线程列表顺序编号）
+    /// * `process_id` - 进程 ID（MiscInfoStream，缺失时为 0）
+    pub fn to_thread_info(&self, index: u32, process_id: u32) -> ThreadInfo {
+        ThreadInfo {
+            index,
+            pid: process_id,
+            tid: self.thread_id,
+            suspend_count: self.suspend_count,
+            teb: Some(self.teb).filter(|&teb| teb != 0),
+            name: self.name.clone(),
+            priority: Some(self.priority),
+            ..Default::default()
+        }
+    }
+}
+
+/// 线程名
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ThreadName {
+    /// 线程 ID
+    #[serde(with = "hex::plain")]
+    pub thread_id: u32,
+    /// 线程名
+    pub name: String,
+}
+
+/// 解析 ThreadListStream（不读取线程名）
+///
+/// # 错误
+/// 如果线程数量与流大小不符，返回错误
+pub(crate) fn parse_thread_list(data: &[u8]) -> Result<Vec<MinidumpThread>, MinidumpError> {
+    let bytes = Bytes::new(data, "thread list stream");
+    let (count, first) = list_entries(&bytes, THREAD_SIZE)?;
+
+    (0..count)
+        .map(|i| {
+            let offset = first + i * THREAD_SIZE;
+            let stack_memory = Location {
+                data_size: bytes.u32(offset + 32)?,
+                rva: bytes.u32(offset + 36)?,
+            };
+            Ok(MinidumpThread {
+                thread_id: bytes.u32(offset)?,
+                suspend_count: bytes.u32(offset + 4)?,
+                priority_class: bytes.u32(offset + 8)?,
+                priority: bytes.u32(offset + 12)? as i32,
+                teb: bytes.u64(offset + 16)?,
+                stack_start: bytes.u64(offset + 24)?,
+                stack_size: stack_memory.data_size,
+                name: None,
+                stack_memory,
+                context: Location {
+                    data_size: bytes.u32(offset + 40)?,
+                    rva: bytes.u32(offset + 44)?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// 解析 ThreadNamesStream，返回 `(线程 ID, 线程名 RVA)` 列表
+///
+/// # 错误
+/// 如果条目数量与流大小不符，返回错误
+pub(crate) fn parse_thread_names(data: &[u8]) -> Result<Vec<(u32, u64)>, MinidumpError> {
+    let bytes = Bytes::new(data, "thread names stream");
+    let (count, first) = list_entries(&bytes, THREAD_NAME_SIZE)?;
+
+    (0..count)
+        .map(|i| {
+            let offset = first + i * THREAD_NAME_SIZE;
+            Ok((bytes.u32(offset)?, bytes.u64(offset + 4)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::*;
+    use super::super::{stream_type, Minidump};
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_threads_with_names() {
+        let mut builder = DumpBuilder::new();
+        let stack = builder.append(&[0xcc; 64]);
+        let name = builder.add_string("RenderThread");
+        builder
+            .add_stream(
+                stream_type::THREAD_LIST,
+                &thread_list_stream(&[
+                    (0x2b40, 0x7ff6_1000_0000, 0xd12f_f800, (64, stack)),
+                    (0x1f0c, 0, 0, (0, 0)),
+                ]),
+            )
+            .add_stream(
+                stream_type::THREAD_NAMES,
+                &thread_names_stream(&[(0x2b40, u64::from(name))]),
+            );
+        let mut dump = Minidump::from_reader(Cursor::new(builder.build())).unwrap();
+
+        let threads = dump.threads().unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].thread_id, 0x2b40);
+        assert_eq!(threads[0].name.as_deref(), Some("RenderThread"));
+        assert_eq!(threads[0].stack_start, 0xd12f_f800);
+        assert_eq!(threads[0].stack_size, 64);
+        assert_eq!(
+            dump.read_location(threads[0].stack_memory, "stack")
+                .unwrap(),
+            [0xcc; 64]
+        );
+        assert_eq!(threads[1].name, None);
+        let region = threads[0].stack_region().unwrap();
+        assert_eq!(region.start, 0xd12f_f800);
+        assert_eq!(region.rva, u64::from(stack));
+        assert_eq!(threads[1].stack_region(), None);
+
+        let info = threads[0].to_thread_info(0, 0x1a2c);
+        assert_eq!(info.tid, 0x2b40);
+        assert_eq!(info.pid, 0x1a2c);
+        assert_eq!(info.teb, Some(0x7ff6_1000_0000));
+        assert_eq!(info.name.as_deref(), Some("RenderThread"));
+        assert_eq!(threads[1].to_thread_info(1, 0).teb, None);
+
+        let json = serde_json::to_value(&threads[0]).unwrap();
+        assert_eq!(json["thread_id"], "0x2b40");
+        assert!(json.get("context").is_none());
+    }
+
+    #[test]
+    fn test_thread_names_stream_errors() {
+        // 线程名流损坏时仍返回线程列表
+        let mut builder = DumpBuilder::new();
+        builder
+            .add_stream(
+                stream_type::THREAD_LIST,
+                &thread_list_stream(&[(0x10, 0, 0, (0, 0))]),
+            )
+            .add_stream(
+                stream_type::THREAD_NAMES,
+                &thread_names_stream(&[(0x10, 0xffff_0000)]),
+            );
+        let mut dump = Minidump::from_reader(Cursor::new(builder.build())).unwrap();
+        assert!(dump.thread_names().is_err());
+        assert_eq!(dump.threads().unwrap()[0].name, None);
+    }
+
+    #[test]
+    fn test_parse_thread_list_truncated() {
+        assert!(parse_thread_list(&[2, 0, 0, 0]).is_err());
+        assert!(parse_thread_list(&[]).is_err());
+        assert!(parse_thread_names(&[1, 0, 0, 0, 0x10]).is_err());
+        assert!(parse_thread_list(&[0, 0, 0, 0]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,472 @@
+//! `!analyze -v` 输出解析
+//!
+//! 提取 FAILURE_BUCKET_ID、EXCEPTION_RECORD、STACK_TEXT 等字段，生成类型化的 [`AnalyzeReport`]。
+
+use super::{hex, parse_hex, strip_prompt};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// `!analyze -v` 的结构化分析结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AnalyzeReport {
+    /// 故障桶 ID（FAILURE_BUCKET_ID）
+    pub failure_bucket_id: Option<String>,
+    /// 故障 ID 哈希（FAILURE_ID_HASH）
+    pub failure_id_hash: Option<String>,
+    /// 异常代码
+    #[serde(with = "hex::option")]
+    pub exception_code: Option<u64>,
+    /// 异常记录（EXCEPTION_RECORD）
+    pub exception_record: Option<ExceptionRecord>,
+    /// 故障指令位置（FAULTING_IP）
+    pub faulting_ip: Option<FaultingIp>,
+    /// 故障模块名（MODULE_NAME）
+    pub module_name: Option<String>,
+    /// 故障映像名（IMAGE_NAME）
+    pub image_name: Option<String>,
+    /// 故障符号（SYMBOL_NAME）
+    pub symbol_name: Option<String>,
+    /// 进程名（PROCESS_NAME）
+    pub process_name: Option<String>,
+    /// 蓝屏代码（BUGCHECK_CODE）
+    #[serde(with = "hex::option")]
+    pub bugcheck_code: Option<u64>,
+    /// 蓝屏名称（例如 DRIVER_IRQL_NOT_LESS_OR_EQUAL）
+    pub bugcheck_name: Option<String>,
+    /// 蓝屏参数（BUGCHECK_P1..P4）
+    #[serde(with = "hex::vec")]
+    pub bugcheck_args: Vec<u64>,
+    /// 故障调用栈（STACK_TEXT）
+    pub stack_text: Vec<StackTextFrame>,
+    /// 所有单行字段（键 -> 值）
+    pub fields: BTreeMap<String, String>,
+}
+
+/// 异常记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExceptionRecord {
+    /// 异常地址
+    #[serde(with = "hex::option")]
+    pub address: Option<u64>,
+    /// 异常地址对应的符号
+    pub symbol: Option<String>,
+    /// 异常代码
+    #[serde(with = "hex::option")]
+    pub code: Option<u64>,
+    /// 异常代码描述（例如 Access violation）
+    pub description: Option<String>,
+    /// 异常标志
+    #[serde(with = "hex::option")]
+    pub flags: Option<u64>,
+    /// 异常参数
+    #[serde(with = "hex::vec")]
+    pub parameters: Vec<u64>,
+}
+
+/// 故障指令位置
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FaultingIp {
+    /// 符号（例如 contoso!Widget::Render+0x24）
+    pub symbol: Option<String>,
+    /// 指令地址
+    #[serde(with = "hex::option")]
+    pub address: Option<u64>,
+    /// 反汇编文本（机器码和指令）
+    pub disassembly: Option<String>,
+}
+
+/// STACK_TEXT 中的一帧
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StackTextFrame {
+    /// 栈指针（x64 为 Child-SP，x86 为 ChildEBP）
+    #[serde(with = "hex::option")]
+    pub child_sp: Option<u64>,
+    /// 返回地址
+    #[serde(with = "hex::option")]
+    pub ret_addr: Option<u64>,
+    /// 参数
+    #[serde(with = "hex::vec")]
+    pub args: Vec<u64>,
+    /// 调用位置（符号或地址）
+    pub call_site: String,
+}
+
+/// 输出中的一个字段段落（`KEY: value` 及其后续行）
+#[derive(Debug, Default)]
+struct Section<'a> {
+    value: &'a str,
+    body: Vec<&'a str>,
+}
+
+/// 解析 `!analyze -v` 输出
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回结构化的分析结果；无法识别的字段保持为空
+pub fn parse_analyze<S: AsRef<str>>(lines: &[S]) -> AnalyzeReport {
+    let mut sections: BTreeMap<&str, Section> = BTreeMap::new();
+    let mut current: Option<&str> = None;
+    let mut report = AnalyzeReport::default();
+    let mut header_args = Vec::new();
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim_end();
+
+        if let Some((key, value)) = split_key(line) {
+            sections.insert(
+                key,
+                Section {
+                    value,
+                    body: Vec::new(),
+                },
+            );
+            current = Some(key);
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            current = None;
+            continue;
+        }
+
+        if let Some(section) = current.and_then(|key| sections.get_mut(key)) {
+            section.body.push(line);
+            continue;
+        }
+
+        // 蓝屏分析头部：`NAME (code)` 和 `ArgN: value, description`
+        if report.bugcheck_name.is_none() && sections.is_empty() {
+            if let Some((name, code)) = parse_bugcheck_header(line) {
+                report.bugcheck_name = Some(name.to_string());
+                report.bugcheck_code = Some(code);
+                continue;
+            }
+        }
+        if let Some(arg) = parse_header_arg(line) {
+            header_args.push(arg);
+        }
+    }
+
+    let field = |key: &str| {
+        sections
+            .get(key)
+            .map(|s| s.value)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    report.failure_bucket_id = field("FAILURE_BUCKET_ID");
+    report.failure_id_hash = field("FAILURE_ID_HASH");
+    report.module_name = field("MODULE_NAME");
+    report.image_name = field("IMAGE_NAME");
+    report.symbol_name = field("SYMBOL_NAME");
+    report.process_name = field("PROCESS_NAME");
+
+    report.exception_record = sections
+        .get("EXCEPTION_RECORD")
+        .map(|s| parse_exception_record(&s.body))
+        .filter(|r| r.code.is_some() || r.address.is_some());
+
+    report.exception_code = sections
+        .get("EXCEPTION_CODE_STR")
+        .and_then(|s| parse_hex(s.value))
+        .or_else(|| report.exception_record.as_ref().and_then(|r| r.code))
+        .or_else(|| {
+            sections
+                .get("EXCEPTION_CODE")
+                .and_then(|s| find_hex_token(s.value))
+        });
+
+    report.faulting_ip = sections
+        .get("FAULTING_IP")
+        .and_then(|s| parse_faulting_ip(&s.body))
+        .or_else(|| {
+            sections
+                .get("CONTEXT")
+                .and_then(|s| parse_context_ip(&s.body))
+        })
+        .or_else(|| {
+            report.exception_record.as_ref().map(|r| FaultingIp {
+                symbol: r.symbol.clone(),
+                address: r.address,
+                disassembly: None,
+            })
+        });
+
+    if let Some(code) = sections
+        .get("BUGCHECK_CODE")
+        .and_then(|s| parse_hex(s.value))
+    {
+        report.bugcheck_code = Some(code);
+    }
+    let params: Vec<u64> = ["BUGCHECK_P1", "BUGCHECK_P2", "BUGCHECK_P3", "BUGCHECK_P4"]
+        .iter()
+        .filter_map(|key| sections.get(key).and_then(|s| parse_hex(s.value)))
+        .collect();
+    report.bugcheck_args = if params.is_empty() {
+        header_args
+    } else {
+        params
+    };
+
+    report.stack_text = sections
+        .get("STACK_TEXT")
+        .map(|s| {
+            s.body
+                .iter()
+                .filter_map(|l| parse_stack_text_line(l))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    report.fields = sections
+        .iter()
+        .filter(|(_, s)| !s.value.is_empty())
+        .map(|(k, s)| (k.to_string(), s.value.to_string()))
+        .collect();
+
+    report
+}
+
+/// 拆分 `KEY: value` 形式的字段行
+///
+/// 键必须以大写字母开头，且只包含大写字母、数字和下划线。
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let mut chars = key.chars();
+    let valid = key.len() >= 2
+        && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid.then(|| (key, value.trim()))
+}
+
+/// 解析蓝屏头部行（例如 `DRIVER_IRQL_NOT_LESS_OR_EQUAL (d1)`）
+fn parse_bugcheck_header(line: &str) -> Option<(&str, u64)> {
+    let (name, rest) = line.trim().split_once(" (")?;
+    let code = parse_hex(rest.strip_suffix(')')?)?;
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some((name, code))
+}
+
+/// 解析蓝屏头部参数行（例如 `Arg1: ffffd50a1e34b010, memory referenced`）
+fn parse_header_arg(line: &str) -> Option<u64> {
+    let rest = line.trim().strip_prefix("Arg")?;
+    let (index, value) = rest.split_once(':')?;
+    index.parse::<u8>().ok()?;
+    let value = value.split(',').next()?;
+    parse_hex(value)
+}
+
+/// 解析 EXCEPTION_RECORD 段落
+fn parse_exception_record(body: &[&str]) -> ExceptionRecord {
+    let mut record = ExceptionRecord::default();
+
+    for line in body {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let (head, detail) = split_parenthesized(value);
+
+        match key {
+            "ExceptionAddress" => {
+                record.address = parse_hex(head);
+                record.symbol = detail.map(normalize_offset);
+            }
+            "ExceptionCode" => {
+                record.code = parse_hex(head);
+                record.description = detail.map(str::to_string);
+            }
+            "ExceptionFlags" => record.flags = parse_hex(head),
+            k if k.starts_with("Parameter[") => {
+                if let Some(v) = parse_hex(head) {
+                    record.parameters.push(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    record
+}
+
+/// 解析 FAULTING_IP 段落（符号行 + 反汇编行）
+fn parse_faulting_ip(body: &[&str]) -> Option<FaultingIp> {
+    let symbol = body.first()?.trim();
+    let (address, disassembly) = body
+        .get(1)
+        .map(|l| parse_disassembly_line(l))
+        .unwrap_or((None, None));
+
+    Some(FaultingIp {
+        symbol: Some(symbol.to_string()),
+        address,
+        disassembly,
+    })
+}
+
+/// 从 CONTEXT 段落末尾的 `symbol:` + 反汇编行中提取故障指令位置
+fn parse_context_ip(body: &[&str]) -> Option<FaultingIp> {
+    let index = body.iter().position(|l| {
+        let l = l.trim();
+        l.ends_with(':') && l.contains('!') && !l.contains(' ')
+    })?;
+    let symbol = body[index].trim().trim_end_matches(':');
+    let (address, disassembly) = body
+        .get(index + 1)
+        .map(|l| parse_disassembly_line(l))
+        .unwrap_or((None, None));
+
+    Some(FaultingIp {
+        symbol: Some(symbol.to_string()),
+        address,
+        disassembly,
+    })
+}
+
+/// 解析反汇编行（例如 ``00007ff6`a1b21234 8b4808 mov ecx,dword ptr [rax+8]``）
+fn parse_disassembly_line(line: &str) -> (Option<u64>, Option<String>) {
+    let line = line.trim();
+    match line.split_once(' ') {
+        Some((addr, rest)) => (parse_hex(addr), Some(rest.trim().to_string())),
+        None => (parse_hex(line), None),
+    }
+}
+
+/// 解析 STACK_TEXT 中的一行
+///
+/// 支持 x64 格式（`sp ret : args : call_site`）和 x86 格式（`ebp ret arg1 arg2 arg3 call_site`）。
+fn parse_stack_text_line(line: &str) -> Option<StackTextFrame> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<&str> = line.splitn(3, " : ").collect();
+    if parts.len() == 3 {
+        let mut head = parts[0].split_whitespace().map(parse_hex);
+        return Some(StackTextFrame {
+            child_sp: head.next().flatten(),
+            ret_addr: head.next().flatten(),
+            args: parts[1].split_whitespace().filter_map(parse_hex).collect(),
+            call_site: parts[2].trim().to_string(),
+        });
+    }
+
+    // x86 格式：最多 5 个前导十六进制值（ebp、返回地址和 3 个参数）
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let hex_count = tokens
+        .iter()
+        .take(5)
+        .take_while(|t| t.len() >= 8 && parse_hex(t).is_some())
+        .count();
+    if hex_count < 2 || hex_count == tokens.len() {
+        return None;
+    }
+    let values: Vec<u64> = tokens[..hex_count]
+        .iter()
+        .filter_map(|t| parse_hex(t))
+        .collect();
+
+    Some(StackTextFrame {
+        child_sp: values.first().copied(),
+        ret_addr: values.get(1).copied(),
+        args: values[2..].to_vec(),
+        call_site: tokens[hex_count..].join(" "),
+    })
+}
+
+/// 拆分 `value (detail)` 形式的文本
+fn split_parenthesized(value: &str) -> (&str, Option<&str>) {
+    match value.split_once(" (") {
+        Some((head, rest)) => (head.trim(), Some(rest.trim_end_matches(')'))),
+        None => (value, None),
+    }
+}
+
+/// 规范化符号偏移（`+0x0000000000000024` -> `+0x24`）
+fn normalize_offset(symbol: &str) -> String {
+    match symbol.rsplit_once("+0x") {
+        Some((name, offset)) => match parse_hex(offset) {
+            Some(v) => format!("{}+{:#x}", name, v),
+            None => symbol.to_string(),
+        },
+        None => symbol.to_string(),
+    }
+}
+
+/// 在文本中查找第一个 `0x` 开头的十六进制值
+fn find_hex_token(text: &str) -> Option<u64> {
+    text.split_whitespace()
+        .find(|t| t.starts_with("0x"))
+        .and_then(parse_hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 解析样例并与黄金文件比较
+    fn assert_golden(sample: &str, golden: &str) {
+        let lines: Vec<&str> = sample.lines().collect();
+        let report = parse_analyze(&lines);
+        let actual = serde_json::to_value(&report).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(golden).unwrap();
+        assert_eq!(
+            actual,
+            expected,
+            "actual:\n{}",
+            serde_json::to_string_pretty(&actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_golden_user_av_x64() {
+        assert_golden(
+            include_str!("../../tests/fixtures/analyze/user_av_x64.txt"),
+            include_str!("../../tests/fixtures/analyze/user_av_x64.json"),
+        );
+    }
+
+    #[test]
+    fn test_golden_kernel_d1() {
+        assert_golden(
+            include_str!("../../tests/fixtures/analyze/kernel_d1.txt"),
+            include_str!("../../tests/fixtures/analyze/kernel_d1.json"),
+        );
+    }
+
+    #[test]
+    fn test_golden_legacy_x86() {
+        assert_golden(
+            include_str!("../../tests/fixtures/analyze/legacy_x86.txt"),
+            include_str!("../../tests/fixtures/analyze/legacy_x86.json"),
+        );
+    }
+
+    #[test]
+    fn test_parse_with_prompt_prefix() {
+        let lines = vec![
+            "0:000> FAILURE_BUCKET_ID:  X_c0000005_app.exe!f".to_string(),
+            String::new(),
+            "EXCEPTION_CODE_STR:  c0000005".to_string(),
+        ];
+        let report = parse_analyze(&lines);
+        assert_eq!(
+            report.failure_bucket_id.as_deref(),
+            Some("X_c0000005_app.exe!f")
+        );
+        assert_eq!(report.exception_code, Some(0xc0000005));
+    }
+
+    #[test]
+    fn test_parse_empty_output() {
+        let report = parse_analyze::<&str>(&[]);
+        assert_eq!(report, AnalyzeReport::default());
+    }
+}
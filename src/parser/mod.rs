@@ -0,0 +1,158 @@
+//! CDB 命令输出解析模块
+//!
+//! 将 CDB 的文本输出解析为类型化的结构，供 MCP 工具以 `structured_content` 形式返回。
+
+pub mod analyze;
+pub mod modules;
+pub mod registers;
+pub mod stack;
+pub mod threads;
+
+/// 去除行首的 CDB 提示符
+///
+/// 支持用户模式（`0:000> `、`0:000:x86> `）和内核模式（`kd> `、`0: kd> `）提示符。
+pub fn strip_prompt(line: &str) -> &str {
+    let Some(pos) = line.find("> ") else {
+        return line;
+    };
+    let prefix = &line[..pos];
+
+    let is_user_prompt = {
+        let mut parts = prefix.split(':');
+        let process = parts.next().unwrap_or_default();
+        let thread = parts.next().unwrap_or_default();
+        let is_num = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        is_num(process)
+            && is_num(thread)
+            && parts.all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_alphanumeric()))
+    };
+    let is_kernel_prompt = prefix == "kd"
+        || prefix
+            .strip_suffix(": kd")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+
+    if is_user_prompt || is_kernel_prompt {
+        &line[pos + 2..]
+    } else {
+        line
+    }
+}
+
+/// 解析十六进制数
+///
+/// 支持 CDB 的 64 位地址分隔符（`00007ff6`a1b21234`）和 `0x` 前缀。
+pub fn parse_hex(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    let digits: String = text.chars().filter(|&c| c != '`').collect();
+    if digits.is_empty() || digits.len() > 16 {
+        return None;
+    }
+    u64::from_str_radix(&digits, 16).ok()
+}
+
+/// 拆分出下一个以空白分隔的列
+///
+/// 返回该列和去除前导空白后的剩余文本；没有更多列时返回 None。
+pub(crate) fn next_token(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    match text.split_once(char::is_whitespace) {
+        Some((token, rest)) => Some((token, rest.trim_start())),
+        None => Some((text, "")),
+    }
+}
+
+/// 以十六进制字符串序列化数值的 serde 辅助函数
+///
+/// 64 位地址超出 JSON 数值的安全整数范围，因此统一序列化为 `"0x..."` 字符串。
+pub(crate) mod hex {
+    /// 序列化整数
+    pub mod plain {
+        use serde::Serializer;
+
+        pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Copy + Into<u64>,
+            S: Serializer,
+        {
+            serializer.serialize_str(&format!("{:#x}", (*value).into()))
+        }
+    }
+
+    /// 序列化 Option<u64>
+    pub mod option {
+        use serde::Serializer;
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<u64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(v) => serializer.serialize_str(&format!("{:#x}", v)),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+
+    /// 序列化 BTreeMap<String, u64>
+    pub mod map {
+        use serde::ser::SerializeMap;
+        use serde::Serializer;
+        use std::collections::BTreeMap;
+
+        pub fn serialize<S: Serializer>(
+            values: &BTreeMap<String, u64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(values.len()))?;
+            for (k, v) in values {
+                map.serialize_entry(k, &format!("{:#x}", v))?;
+            }
+            map.end()
+        }
+    }
+
+    /// 序列化 Vec<u64>
+    pub mod vec {
+        use serde::ser::SerializeSeq;
+        use serde::Serializer;
+
+        pub fn serialize<S: Serializer>(values: &[u64], serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(values.len()))?;
+            for v in values {
+                seq.serialize_element(&format!("{:#x}", v))?;
+            }
+            seq.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_prompt() {
+        assert_eq!(strip_prompt("0:000> kb"), "kb");
+        assert_eq!(strip_prompt("1:012:x86> r"), "r");
+        assert_eq!(strip_prompt("kd> lm"), "lm");
+        assert_eq!(strip_prompt("0: kd> ~"), "~");
+        assert_eq!(strip_prompt("Key  : Value"), "Key  : Value");
+        assert_eq!(strip_prompt("a -> b"), "a -> b");
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("00007ff6`a1b21234"), Some(0x7ff6_a1b2_1234));
+        assert_eq!(parse_hex("0xc0000005"), Some(0xc000_0005));
+        assert_eq!(parse_hex("d1"), Some(0xd1));
+        assert_eq!(parse_hex("contoso"), None);
+        assert_eq!(parse_hex(""), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! `lm`、`lm v`、`lmvm` 模块列表输出解析
+
+use super::{hex, next_token, parse_hex, strip_prompt};
+use serde::{Deserialize, Serialize};
+
+/// 模块的符号加载状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolStatus {
+    /// 延迟加载（尚未尝试加载符号）
+    Deferred,
+    /// 已加载 PDB 符号（公有或私有）
+    Pdb,
+    /// 仅有导出符号
+    Export,
+    /// 没有符号
+    #[default]
+    None,
+}
+
+impl SymbolStatus {
+    /// 从 `lm` 输出中括号内的状态文本解析
+    fn from_text(text: &str) -> Self {
+        match text {
+            "deferred" => Self::Deferred,
+            t if t.contains("export") => Self::Export,
+            t if t.contains("no symbols") => Self::None,
+            t if t.contains("symbols") => Self::Pdb,
+            _ => Self::None,
+        }
+    }
+}
+
+/// 已加载模块信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModuleInfo {
+    /// 起始地址
+    #[serde(with = "hex::option")]
+    pub start: Option<u64>,
+    /// 结束地址
+    #[serde(with = "hex::option")]
+    pub end: Option<u64>,
+    /// 模块名
+    pub name: String,
+    /// 符号状态
+    pub symbol_status: SymbolStatus,
+    /// 已加载的符号文件路径
+    pub symbol_file: Option<String>,
+    /// 映像路径
+    pub image_path: Option<String>,
+    /// 映像文件名
+    pub image_name: Option<String>,
+    /// 链接时间戳（可复现构建时为文件哈希）
+    #[serde(with = "hex::option")]
+    pub timestamp: Option<u64>,
+    /// PE 校验和
+    #[serde(with = "hex::option")]
+    pub checksum: Option<u64>,
+    /// 文件版本
+    pub file_version: Option<String>,
+    /// 产品版本
+    pub product_version: Option<String>,
+    /// 公司名（来自版本资源）
+    pub company: Option<String>,
+}
+
+impl ModuleInfo {
+    /// 是否缺少可用符号（仅导出符号或没有符号）
+    ///
+    /// 延迟加载的模块尚未尝试加载符号，不视为缺少符号。
+    pub fn is_missing_symbols(&self) -> bool {
+        matches!(
+            self.symbol_status,
+            SymbolStatus::Export | SymbolStatus::None
+        )
+    }
+
+    /// 是否为第三方模块
+    ///
+    /// 优先根据版本资源中的公司名判断；缺少公司名时，位于 Windows 目录之外的映像视为第三方。
+    /// 两者都未知时（例如只有 `lm` 的简要输出）返回 false。
+    pub fn is_third_party(&self) -> bool {
+        if let Some(company) = &self.company {
+            return !company.contains("Microsoft");
+        }
+        match &self.image_path {
+            Some(path) => !path.to_ascii_lowercase().contains("\\windows\\"),
+            None => false,
+        }
+    }
+}
+
+/// 解析模块列表输出
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回已加载模块列表（不包含 `Unloaded modules:` 之后的已卸载模块）
+pub fn parse_modules<S: AsRef<str>>(lines: &[S]) -> Vec<ModuleInfo> {
+    let mut modules: Vec<ModuleInfo> = Vec::new();
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim_end();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with("Unloaded modules:") {
+            break;
+        }
+
+        if let Some(module) = parse_module_line(trimmed) {
+            modules.push(module);
+            continue;
+        }
+
+        // `lm v` 的详细信息行（缩进的 `Key: value`）
+        if let (Some(module), Some((key, value))) = (modules.last_mut(), trimmed.split_once(':')) {
+            apply_detail(module, key.trim(), value.trim());
+        }
+    }
+
+    modules
+}
+
+/// 解析模块行（例如 ``00007ffb`1c290000 00007ffb`1c34d000   kernel32   (pdb symbols)   C:\...\kernel32.pdb``）
+fn parse_module_line(line: &str) -> Option<ModuleInfo> {
+    let (start, rest) = next_token(line)?;
+    let (end, rest) = next_token(rest)?;
+    let (name, tail) = next_token(rest)?;
+
+    let mut module = ModuleInfo {
+        start: Some(parse_hex(start)?),
+        end: Some(parse_hex(end)?),
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    // 名称之后：可选标志、(状态)、符号文件
+    if let Some(open) = tail.find('(') {
+        if let Some(close) = tail[open..].find(')') {
+            let status = &tail[open + 1..open + close];
+            module.symbol_status = SymbolStatus::from_text(status);
+            let file = tail[open + close + 1..].trim();
+            if !file.is_empty() {
+                module.symbol_file = Some(file.to_string());
+            }
+        }
+    }
+
+    Some(module)
+}
+
+/// 将 `lm v` 的详细信息字段写入模块
+fn apply_detail(module: &mut ModuleInfo, key: &str, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let value_string = || Some(value.to_string());
+
+    match key {
+        "Image path" => module.image_path = value_string(),
+        "Image name" => module.image_name = value_string(),
+        "Timestamp" => module.timestamp = parse_timestamp(value),
+        "CheckSum" => module.checksum = parse_hex(value),
+        "File version" => module.file_version = value_string(),
+        "Product version" => module.product_version = value_string(),
+        "CompanyName" => module.company = value_string(),
+        // 部分模块只有资源表中的版本字符串
+        "FileVersion" if module.file_version.is_none() => module.file_version = value_string(),
+        "ProductVersion" if module.product_version.is_none() => {
+            module.product_version = value_string()
+        }
+        _ => {}
+    }
+}
+
+/// 解析时间戳字段
+///
+/// 支持 `Wed Oct 14 18:20:31 2026 (6A2E0F6F)` 和
+/// `5C8A0E41 (This is a reproducible build file hash, not a timestamp)` 两种格式。
+fn parse_timestamp(value: &str) -> Option<u64> {
+    if let Some(v) = value.split_whitespace().next().and_then(parse_hex) {
+        return Some(v);
+    }
+    let inner = value.strip_suffix(')')?;
+    parse_hex(&inner[inner.rfind('(')? + 1..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sample: &str) -> Vec<ModuleInfo> {
+        let lines: Vec<&str> = sample.lines().collect();
+        parse_modules(&lines)
+    }
+
+    fn find<'a>(modules: &'a [ModuleInfo], name: &str) -> &'a ModuleInfo {
+        modules.iter().find(|m| m.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_lm() {
+        let modules = parse(include_str!("../../tests/fixtures/cdb/lm.txt"));
+        assert_eq!(modules.len(), 4);
+
+        let contoso = &modules[0];
+        assert_eq!(contoso.name, "contoso");
+        assert_eq!(contoso.start, Some(0x7ff6_a1b2_0000));
+        assert_eq!(contoso.end, Some(0x7ff6_a1b4_f000));
+        assert_eq!(contoso.symbol_status, SymbolStatus::Pdb);
+        assert!(contoso
+            .symbol_file
+            .as_deref()
+            .unwrap()
+            .ends_with("contoso.pdb"));
+        assert_eq!(contoso.image_path, None);
+
+        let vcruntime = &modules[1];
+        assert_eq!(vcruntime.symbol_status, SymbolStatus::Deferred);
+        assert_eq!(vcruntime.symbol_file, None);
+        assert!(!vcruntime.is_third_party());
+    }
+
+    #[test]
+    fn test_parse_lm_v() {
+        let modules = parse(include_str!("../../tests/fixtures/cdb/lm_v.txt"));
+        let names: Vec<&str> = modules.iter().map(|m| m.name.as_str()).collect();
+        // 已卸载模块不包含在列表中
+        assert_eq!(
+            names,
+            [
+                "contoso",
+                "thirdparty",
+                "hook64",
+                "vcruntime140",
+                "kernel32",
+                "ntdll"
+            ]
+        );
+
+        let contoso = find(&modules, "contoso");
+        assert_eq!(
+            contoso.image_path.as_deref(),
+            Some("C:\\Program Files\\Contoso\\contoso.exe")
+        );
+        assert_eq!(contoso.image_name.as_deref(), Some("contoso.exe"));
+        assert_eq!(contoso.timestamp, Some(0x6a2e_0f6f));
+        assert_eq!(contoso.checksum, Some(0x3a2f1));
+        assert_eq!(contoso.file_version.as_deref(), Some("1.4.2.0"));
+        assert_eq!(contoso.product_version.as_deref(), Some("1.4.2.0"));
+        assert_eq!(contoso.company.as_deref(), Some("Contoso Ltd."));
+
+        let thirdparty = find(&modules, "thirdparty");
+        assert_eq!(thirdparty.symbol_status, SymbolStatus::Export);
+        assert_eq!(thirdparty.symbol_file.as_deref(), Some("thirdparty.dll"));
+        assert_eq!(thirdparty.file_version.as_deref(), Some("3.1.0.77"));
+        assert!(thirdparty.is_missing_symbols());
+        assert!(thirdparty.is_third_party());
+
+        let hook = find(&modules, "hook64");
+        assert_eq!(hook.symbol_status, SymbolStatus::None);
+        assert_eq!(hook.timestamp, Some(0x5c8a_0e41));
+        assert_eq!(hook.company, None);
+        assert_eq!(hook.file_version, None);
+        assert!(hook.is_missing_symbols());
+        assert!(hook.is_third_party());
+
+        let kernel32 = find(&modules, "kernel32");
+        assert_eq!(kernel32.timestamp, Some(0x2f8b_6e32));
+        assert!(!kernel32.is_missing_symbols());
+        assert!(!kernel32.is_third_party());
+
+        let vcruntime = find(&modules, "vcruntime140");
+        assert!(!vcruntime.is_missing_symbols());
+        assert!(!vcruntime.is_third_party());
+    }
+
+    #[test]
+    fn test_parse_lmvm_with_prompt() {
+        let sample = include_str!("../../tests/fixtures/cdb/lmvm_contoso.txt");
+        let lines: Vec<String> = sample
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                if i == 0 {
+                    format!("0:000> {}", l)
+                } else {
+                    l.to_string()
+                }
+            })
+            .collect();
+        let modules = parse_modules(&lines);
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].company.as_deref(), Some("Contoso Ltd."));
+    }
+
+    #[test]
+    fn test_symbol_status_from_text() {
+        assert_eq!(SymbolStatus::from_text("deferred"), SymbolStatus::Deferred);
+        assert_eq!(
+            SymbolStatus::from_text("private pdb symbols"),
+            SymbolStatus::Pdb
+        );
+        assert_eq!(
+            SymbolStatus::from_text("codeview symbols"),
+            SymbolStatus::Pdb
+        );
+        assert_eq!(
+            SymbolStatus::from_text("export symbols"),
+            SymbolStatus::Export
+        );
+        assert_eq!(SymbolStatus::from_text("no symbols"), SymbolStatus::None);
+    }
+
+    #[test]
+    fn test_parse_empty_output() {
+        assert!(parse_modules::<&str>(&[]).is_empty());
+    }
+}
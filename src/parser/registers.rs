@@ -0,0 +1,328 @@
+//! 寄存器输出（`r`、`.ecxr`、`.cxr`）解析
+//!
+//! 支持 x86、x64 和 ARM64 的寄存器格式，包括标志位助记符、段寄存器和末尾的反汇编行。
+
+use super::{hex, next_token, parse_hex, strip_prompt};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 段寄存器名称
+const SEGMENT_REGISTERS: [&str; 6] = ["cs", "ss", "ds", "es", "fs", "gs"];
+
+/// x86/x64 标志位助记符（`nv up ei pl zr na po nc` 等）
+const FLAG_MNEMONICS: [&str; 16] = [
+    "nv", "ov", "up", "dn", "ei", "di", "pl", "ng", "nz", "zr", "na", "ac", "po", "pe", "nc", "cy",
+];
+
+/// 目标架构
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Architecture {
+    /// 32 位 x86
+    X86,
+    /// x64（AMD64）
+    X64,
+    /// ARM64
+    Arm64,
+    /// 无法识别
+    #[default]
+    Unknown,
+}
+
+/// 标志寄存器
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Flags {
+    /// 标志寄存器值（x86/x64 为 efl，ARM64 为 psr）
+    #[serde(with = "hex::option")]
+    pub value: Option<u64>,
+    /// I/O 特权级（仅 x86/x64）
+    pub iopl: Option<u64>,
+    /// 标志位助记符（例如 `zr`、`cy`；ARM64 为 `-ZC-`、`EL0`）
+    pub mnemonics: Vec<String>,
+}
+
+/// 当前指令的反汇编
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Disassembly {
+    /// 指令地址
+    #[serde(with = "hex::option")]
+    pub address: Option<u64>,
+    /// 机器码
+    pub bytes: String,
+    /// 助记符
+    pub mnemonic: String,
+    /// 操作数（包含 CDB 追加的有效地址注释）
+    pub operands: String,
+}
+
+/// 寄存器上下文
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RegisterContext {
+    /// 目标架构
+    pub architecture: Architecture,
+    /// 通用寄存器（名称 -> 值）
+    #[serde(with = "hex::map")]
+    pub registers: BTreeMap<String, u64>,
+    /// 段寄存器（仅 x86/x64）
+    #[serde(with = "hex::map")]
+    pub segments: BTreeMap<String, u64>,
+    /// 标志寄存器
+    pub flags: Flags,
+    /// 指令指针（rip/eip/pc）
+    #[serde(with = "hex::option")]
+    pub instruction_pointer: Option<u64>,
+    /// 栈指针（rsp/esp/sp）
+    #[serde(with = "hex::option")]
+    pub stack_pointer: Option<u64>,
+    /// 帧指针（rbp/ebp/fp）
+    #[serde(with = "hex::option")]
+    pub frame_pointer: Option<u64>,
+    /// 当前指令所在符号
+    pub symbol: Option<String>,
+    /// 当前指令的反汇编
+    pub disassembly: Option<Disassembly>,
+}
+
+/// 解析寄存器输出
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回寄存器上下文；无法识别的行被忽略
+pub fn parse_registers<S: AsRef<str>>(lines: &[S]) -> RegisterContext {
+    let mut context = RegisterContext::default();
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if context.disassembly.is_none() {
+            if let Some(disassembly) = parse_disassembly(line) {
+                context.disassembly = Some(disassembly);
+                continue;
+            }
+        }
+
+        // 符号行（例如 `contoso!Widget::Render+0x24:`）
+        if let Some(symbol) = line.strip_suffix(':') {
+            if !symbol.contains('=') && !symbol.contains(' ') {
+                context.symbol = Some(symbol.to_string());
+                continue;
+            }
+        }
+
+        parse_register_line(line, &mut context);
+    }
+
+    context.architecture = detect_architecture(&context.registers);
+    let (ip, sp, fp) = match context.architecture {
+        Architecture::X64 => ("rip", "rsp", "rbp"),
+        Architecture::X86 => ("eip", "esp", "ebp"),
+        Architecture::Arm64 => ("pc", "sp", "fp"),
+        Architecture::Unknown => ("", "", ""),
+    };
+    context.instruction_pointer = context.registers.get(ip).copied();
+    context.stack_pointer = context.registers.get(sp).copied();
+    context.frame_pointer = context.registers.get(fp).copied();
+
+    context
+}
+
+/// 解析包含 `name=value` 对和标志位助记符的行
+fn parse_register_line(line: &str, context: &mut RegisterContext) {
+    let mut after_psr = false;
+
+    for token in line.split_whitespace() {
+        if let Some((name, value)) = token.split_once('=') {
+            let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric());
+            let Some(value) = parse_hex(value).filter(|_| valid_name) else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            after_psr = false;
+
+            match name.as_str() {
+                "iopl" => context.flags.iopl = Some(value),
+                "efl" => context.flags.value = Some(value),
+                "psr" | "cpsr" => {
+                    context.flags.value = Some(value);
+                    after_psr = true;
+                }
+                n if SEGMENT_REGISTERS.contains(&n) => {
+                    context.segments.insert(name, value);
+                }
+                _ => {
+                    context.registers.insert(name, value);
+                }
+            }
+        } else if FLAG_MNEMONICS.contains(&token) || (after_psr && is_arm64_flag(token)) {
+            context.flags.mnemonics.push(token.to_string());
+        }
+    }
+}
+
+/// ARM64 psr 之后的标志（`NZCV` 四位，未置位为 `-`）和异常级别（`EL0`）
+fn is_arm64_flag(token: &str) -> bool {
+    let nzcv = token.len() == 4
+        && token
+            .chars()
+            .zip("NZCV".chars())
+            .all(|(c, flag)| c == flag || c == '-');
+    let el = token
+        .strip_prefix("EL")
+        .is_some_and(|n| n.len() == 1 && n.chars().all(|c| c.is_ascii_digit()));
+    nzcv || el
+}
+
+/// 解析反汇编行（例如 ``00007ff6`a1b21234 8b4808 mov ecx,dword ptr [rax+8]``）
+fn parse_disassembly(line: &str) -> Option<Disassembly> {
+    let (address, rest) = next_token(line)?;
+    if address.len() < 8 || address.contains('=') {
+        return None;
+    }
+    let address = parse_hex(address)?;
+    let (bytes, rest) = next_token(rest)?;
+    if !bytes.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let (mnemonic, operands) = next_token(rest)?;
+
+    Some(Disassembly {
+        address: Some(address),
+        bytes: bytes.to_string(),
+        mnemonic: mnemonic.to_string(),
+        operands: operands.trim().to_string(),
+    })
+}
+
+/// 根据寄存器名称识别架构
+fn detect_architecture(registers: &BTreeMap<String, u64>) -> Architecture {
+    if registers.contains_key("rip") {
+        Architecture::X64
+    } else if registers.contains_key("eip") {
+        Architecture::X86
+    } else if registers.contains_key("pc") && registers.contains_key("x0") {
+        Architecture::Arm64
+    } else {
+        Architecture::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sample: &str) -> RegisterContext {
+        let lines: Vec<&str> = sample.lines().collect();
+        parse_registers(&lines)
+    }
+
+    #[test]
+    fn test_parse_x64() {
+        let context = parse(include_str!("../../tests/fixtures/cdb/registers.txt"));
+        assert_eq!(context.architecture, Architecture::X64);
+        assert_eq!(context.registers.len(), 17);
+        assert_eq!(context.registers["rbx"], 0x1d2_c4a3_f0b0);
+        assert_eq!(context.registers["r8"], 0);
+        assert_eq!(context.instruction_pointer, Some(0x7ff6_a1b2_1234));
+        assert_eq!(context.stack_pointer, Some(0xa5_e6cf_f6a0));
+        assert_eq!(context.frame_pointer, Some(0));
+
+        assert_eq!(context.segments.len(), 6);
+        assert_eq!(context.segments["cs"], 0x33);
+        assert_eq!(context.flags.value, Some(0x10246));
+        assert_eq!(context.flags.iopl, Some(0));
+        assert_eq!(
+            context.flags.mnemonics,
+            ["nv", "up", "ei", "pl", "zr", "na", "po", "nc"]
+        );
+
+        assert_eq!(
+            context.symbol.as_deref(),
+            Some("contoso!Widget::Render+0x24")
+        );
+        let disassembly = context.disassembly.unwrap();
+        assert_eq!(disassembly.address, Some(0x7ff6_a1b2_1234));
+        assert_eq!(disassembly.bytes, "8b4808");
+        assert_eq!(disassembly.mnemonic, "mov");
+        assert_eq!(
+            disassembly.operands,
+            "ecx,dword ptr [rax+8] ds:00000000`00000008=????????"
+        );
+    }
+
+    #[test]
+    fn test_parse_x86() {
+        let context = parse(include_str!("../../tests/fixtures/registers/x86.txt"));
+        assert_eq!(context.architecture, Architecture::X86);
+        assert_eq!(context.registers.len(), 9);
+        assert_eq!(context.instruction_pointer, Some(0x6a4c_1ad3));
+        assert_eq!(context.stack_pointer, Some(0x0019_f6c4));
+        assert_eq!(context.flags.value, Some(0xed3));
+        assert_eq!(
+            context.flags.mnemonics,
+            ["ov", "dn", "di", "ng", "nz", "ac", "pe", "cy"]
+        );
+        assert_eq!(context.segments["fs"], 0x53);
+
+        let disassembly = context.disassembly.unwrap();
+        assert_eq!(disassembly.address, Some(0x6a4c_1ad3));
+        assert_eq!(
+            disassembly.operands,
+            "ecx,dword ptr [eax+4] ds:002b:00000004=????????"
+        );
+    }
+
+    #[test]
+    fn test_parse_arm64() {
+        let context = parse(include_str!("../../tests/fixtures/registers/arm64.txt"));
+        assert_eq!(context.architecture, Architecture::Arm64);
+        // x0-x28、fp、lr、sp、pc
+        assert_eq!(context.registers.len(), 33);
+        assert_eq!(context.registers["x19"], 0x1d2_c4a3_f0b0);
+        assert_eq!(context.instruction_pointer, Some(0x7ff6_a1b2_1234));
+        assert_eq!(context.stack_pointer, Some(0xa5_e6cf_f6a0));
+        assert_eq!(context.frame_pointer, Some(0xa5_e6cf_f6c0));
+        assert!(context.segments.is_empty());
+        assert_eq!(context.flags.value, Some(0x6000_0000));
+        assert_eq!(context.flags.mnemonics, ["-ZC-", "EL0"]);
+
+        let disassembly = context.disassembly.unwrap();
+        assert_eq!(disassembly.bytes, "b9400801");
+        assert_eq!(disassembly.mnemonic, "ldr");
+        assert_eq!(disassembly.operands, "w1,[x0,#8]");
+    }
+
+    #[test]
+    fn test_parse_with_prompt_and_no_symbol() {
+        let lines = [
+            "0:000> eax=00000001 ebx=00000002",
+            "eip=00401000 esp=0012ff80 ebp=0012ff88",
+            "00401000 c3              ret",
+        ];
+        let context = parse_registers(&lines);
+        assert_eq!(context.architecture, Architecture::X86);
+        assert_eq!(context.registers["eax"], 1);
+        assert_eq!(context.symbol, None);
+        assert_eq!(context.disassembly.unwrap().mnemonic, "ret");
+    }
+
+    #[test]
+    fn test_serialize_registers_as_hex() {
+        let context = parse(include_str!("../../tests/fixtures/cdb/registers.txt"));
+        let json = serde_json::to_value(&context).unwrap();
+        assert_eq!(json["architecture"], "x64");
+        assert_eq!(json["registers"]["rip"], "0x7ff6a1b21234");
+        assert_eq!(json["segments"]["gs"], "0x2b");
+    }
+
+    #[test]
+    fn test_parse_empty_output() {
+        let context = parse_registers::<&str>(&[]);
+        assert_eq!(context.architecture, Architecture::Unknown);
+        assert!(context.registers.is_empty());
+    }
+}
@@ -0,0 +1,510 @@
+//! k 系列命令（`k`、`kb`、`kp`、`kn`、`kv`）调用栈输出解析
+//!
+//! 列布局由表头行（`Child-SP`/`ChildEBP`、`RetAddr`、`Args to Child`、`Call Site`）决定，
+//! 同时支持 `~*k` 输出中按线程分组的多个调用栈。
+
+use super::{hex, next_token, parse_hex, strip_prompt};
+use serde::Serialize;
+
+/// 调用栈中的一帧
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StackFrame {
+    /// 帧号（仅 `n` 变体输出）
+    pub frame_no: Option<u32>,
+    /// 栈指针（x64 为 Child-SP，x86 为 ChildEBP；内联帧为空）
+    #[serde(with = "hex::option")]
+    pub child_sp: Option<u64>,
+    /// 返回地址（内联帧为空）
+    #[serde(with = "hex::option")]
+    pub ret_addr: Option<u64>,
+    /// 参数（仅 `kb`/`kv` 变体输出）
+    #[serde(with = "hex::vec")]
+    pub args: Vec<u64>,
+    /// 模块名
+    pub module: Option<String>,
+    /// 函数名（不含 `kp` 输出的参数列表）
+    pub function: Option<String>,
+    /// 相对函数（或模块）的偏移
+    #[serde(with = "hex::option")]
+    pub offset: Option<u64>,
+    /// 源文件路径
+    pub source_file: Option<String>,
+    /// 源文件行号
+    pub line: Option<u32>,
+    /// 是否为内联帧
+    pub inline: bool,
+    /// 原始调用位置文本（不含源文件信息和 `kv` 附加信息）
+    pub call_site: String,
+}
+
+/// 栈回溯过程中输出的警告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackWarning {
+    /// 警告出现前已解析的帧数（警告通常适用于其后的帧）
+    pub frame_index: usize,
+    /// 警告文本
+    pub message: String,
+}
+
+/// 单个线程的调用栈
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ThreadStack {
+    /// 线程序号（来自 `~*k` 的线程头；单线程输出时为空）
+    pub thread: Option<u32>,
+    /// 进程 ID.线程 ID（十六进制，例如 `1a2c.2b40`）
+    pub id: Option<String>,
+    /// 调用栈帧
+    pub frames: Vec<StackFrame>,
+    /// 警告
+    pub warnings: Vec<StackWarning>,
+}
+
+impl ThreadStack {
+    fn is_empty(&self) -> bool {
+        self.thread.is_none() && self.frames.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// 帧行的列布局
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    /// 首列为帧号
+    frame_no: bool,
+    /// 包含 Child-SP / ChildEBP 列
+    child_sp: bool,
+    /// 包含 Args to Child 列
+    args: bool,
+}
+
+impl Layout {
+    /// 从表头行识别列布局
+    fn from_header(line: &str) -> Option<Self> {
+        if !line.contains("RetAddr") && !line.contains("Call Site") {
+            return None;
+        }
+        Some(Self {
+            frame_no: line.starts_with('#'),
+            child_sp: line.contains("Child-SP") || line.contains("ChildEBP"),
+            args: line.contains("Args to Child"),
+        })
+    }
+
+    /// 缺少表头时根据帧行推断列布局
+    fn infer(line: &str) -> Self {
+        let first = line.split_whitespace().next().unwrap_or_default();
+        Self {
+            frame_no: first.len() <= 4 && first.chars().all(|c| c.is_ascii_hexdigit()),
+            child_sp: true,
+            args: line.contains(" : "),
+        }
+    }
+}
+
+/// 解析 k 系列命令输出
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回按线程分组的调用栈；单线程输出时只有一个元素，输出为空时返回空列表
+pub fn parse_stack<S: AsRef<str>>(lines: &[S]) -> Vec<ThreadStack> {
+    let mut stacks = Vec::new();
+    let mut current = ThreadStack::default();
+    let mut layout: Option<Layout> = None;
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some((thread, id)) = parse_thread_header(line) {
+            if !current.is_empty() {
+                stacks.push(std::mem::take(&mut current));
+            }
+            current.thread = Some(thread);
+            current.id = Some(id.to_string());
+            layout = None;
+            continue;
+        }
+
+        if let Some(header) = Layout::from_header(line) {
+            layout = Some(header);
+            continue;
+        }
+
+        if line.contains("WARNING:") {
+            current.warnings.push(StackWarning {
+                frame_index: current.frames.len(),
+                message: line.trim_start_matches('*').trim().to_string(),
+            });
+            continue;
+        }
+
+        let frame_layout = layout.unwrap_or_else(|| Layout::infer(line));
+        if let Some(frame) = parse_frame_line(line, frame_layout) {
+            current.frames.push(frame);
+        }
+    }
+
+    if !current.is_empty() {
+        stacks.push(current);
+    }
+
+    stacks
+}
+
+/// 解析 `~*k` 输出中的线程头（例如 `.  0  Id: 1a2c.2b40 Suspend: 0 ...`）
+fn parse_thread_header(line: &str) -> Option<(u32, &str)> {
+    let line = line.trim_start_matches(['.', '#']).trim_start();
+    let mut tokens = line.split_whitespace();
+    let index = tokens.next()?.parse().ok()?;
+    if tokens.next()? != "Id:" {
+        return None;
+    }
+    Some((index, tokens.next()?))
+}
+
+/// 内联帧中的地址占位符（`--------`--------`）
+fn is_placeholder(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c == '-' || c == '`')
+}
+
+/// 解析一行帧输出
+fn parse_frame_line(line: &str, layout: Layout) -> Option<StackFrame> {
+    let mut frame = StackFrame::default();
+    let mut rest = line;
+
+    if layout.frame_no {
+        let (no, tail) = next_token(rest)?;
+        frame.frame_no = Some(u32::from_str_radix(no, 16).ok()?);
+        rest = tail;
+    }
+
+    let mut addresses = Vec::new();
+    let call_site;
+
+    let parts: Vec<&str> = rest.splitn(3, " : ").collect();
+    if let [head, args, site] = parts[..] {
+        // x64 kb/kv 格式：`sp ret : args : call_site`
+        if head.trim_start().starts_with("(Inline") {
+            frame.inline = true;
+        } else {
+            for token in head.split_whitespace() {
+                addresses.push(parse_hex(token)?);
+            }
+        }
+        frame.args = args.split_whitespace().filter_map(parse_hex).collect();
+        call_site = site;
+    } else {
+        if let Some(tail) = rest.strip_prefix("(Inline") {
+            frame.inline = true;
+            rest = tail.split_once(')')?.1.trim_start();
+            // 跳过 RetAddr 列的占位符
+            while let Some((token, tail)) = next_token(rest) {
+                if !is_placeholder(token) {
+                    break;
+                }
+                rest = tail;
+            }
+        } else {
+            for _ in 0..(1 + layout.child_sp as usize) {
+                let (token, tail) = next_token(rest)?;
+                addresses.push(parse_hex(token)?);
+                rest = tail;
+            }
+        }
+
+        // x86 kb/kv 格式：最多 3 个参数
+        if layout.args {
+            for _ in 0..3 {
+                let Some((token, tail)) = next_token(rest) else {
+                    break;
+                };
+                if tail.is_empty() {
+                    break;
+                }
+                if is_placeholder(token) {
+                    rest = tail;
+                    continue;
+                }
+                match parse_hex(token) {
+                    Some(value) if token.len() >= 8 => {
+                        frame.args.push(value);
+                        rest = tail;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        call_site = rest;
+    }
+
+    match addresses[..] {
+        [sp, ret] => {
+            frame.child_sp = Some(sp);
+            frame.ret_addr = Some(ret);
+        }
+        [ret] => frame.ret_addr = Some(ret),
+        _ => {}
+    }
+
+    parse_call_site(call_site, &mut frame);
+    (!frame.call_site.is_empty()).then_some(frame)
+}
+
+/// 解析调用位置（`module!function+0xoffset [file @ line]`）
+fn parse_call_site(text: &str, frame: &mut StackFrame) {
+    let mut text = text.trim();
+
+    // 源文件信息：`[file @ line]`
+    if let Some(inner) = text.strip_suffix(']') {
+        if let Some(pos) = inner.rfind(" [") {
+            if let Some((file, line)) = inner[pos + 2..].rsplit_once(" @ ") {
+                frame.source_file = Some(file.trim().to_string());
+                frame.line = line.trim().parse().ok();
+                text = inner[..pos].trim_end();
+            }
+        }
+    }
+
+    // kv 附加信息：`(FPO: ...)`、`(CONV: ...)`、`(TrapFrame @ ...)`、`(Inline Function @ ...)`
+    for marker in [" (FPO:", " (CONV:", " (TrapFrame", " (Inline"] {
+        if let Some(pos) = text.find(marker) {
+            text = text[..pos].trim_end();
+        }
+    }
+
+    frame.call_site = text.to_string();
+
+    // 未解析符号的地址（例如 `0x0`）
+    if text.starts_with("0x") && parse_hex(text).is_some() {
+        return;
+    }
+
+    let (symbol, offset) = match text.rsplit_once("+0x") {
+        Some((symbol, offset)) if parse_hex(offset).is_some() => (symbol, parse_hex(offset)),
+        _ => (text, None),
+    };
+    frame.offset = offset;
+
+    match symbol.split_once('!') {
+        Some((module, function)) => {
+            frame.module = Some(module.to_string());
+            frame.function = Some(strip_parameters(function).to_string());
+        }
+        None => frame.module = Some(symbol.to_string()),
+    }
+}
+
+/// 去除 `kp` 输出的函数参数列表
+fn strip_parameters(function: &str) -> &str {
+    if !function.ends_with(')') {
+        return function;
+    }
+
+    let mut depth = 0;
+    for (index, c) in function.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    let name = &function[..index];
+                    // `operator()` 本身以括号结尾，不是参数列表
+                    if name.is_empty() || name.ends_with("operator") {
+                        return function;
+                    }
+                    return name;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    function
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sample: &str) -> Vec<ThreadStack> {
+        let lines: Vec<&str> = sample.lines().collect();
+        parse_stack(&lines)
+    }
+
+    #[test]
+    fn test_parse_kbn_x64() {
+        let stacks = parse(include_str!("../../tests/fixtures/cdb/kbn.txt"));
+        assert_eq!(stacks.len(), 1);
+        let stack = &stacks[0];
+        assert_eq!(stack.thread, None);
+        assert_eq!(stack.frames.len(), 6);
+
+        let top = &stack.frames[0];
+        assert_eq!(top.frame_no, Some(0));
+        assert_eq!(top.child_sp, Some(0xa5_e6cf_f6a0));
+        assert_eq!(top.ret_addr, Some(0x7ff6_a1b2_1890));
+        assert_eq!(top.args, vec![0x1d2_c4a3_f0b0, 0, 1, 0]);
+        assert_eq!(top.module.as_deref(), Some("contoso"));
+        assert_eq!(top.function.as_deref(), Some("Widget::Render"));
+        assert_eq!(top.offset, Some(0x24));
+        assert_eq!(
+            top.source_file.as_deref(),
+            Some("C:\\src\\contoso\\widget.cpp")
+        );
+        assert_eq!(top.line, Some(88));
+        assert!(!top.inline);
+
+        let inline = &stack.frames[1];
+        assert!(inline.inline);
+        assert_eq!(inline.child_sp, None);
+        assert_eq!(inline.ret_addr, None);
+        assert!(inline.args.is_empty());
+        assert_eq!(inline.function.as_deref(), Some("Widget::DrawChildren"));
+        assert_eq!(inline.line, Some(120));
+
+        let last = &stack.frames[5];
+        assert_eq!(last.ret_addr, Some(0));
+        assert_eq!(last.call_site, "ntdll!RtlUserThreadStart+0x21");
+        assert_eq!(last.source_file, None);
+    }
+
+    #[test]
+    fn test_parse_kb_without_child_sp() {
+        let stacks = parse(include_str!("../../tests/fixtures/cdb/kb.txt"));
+        let frames = &stacks[0].frames;
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0].child_sp, None);
+        assert_eq!(frames[0].ret_addr, Some(0x7ff6_a1b2_1890));
+        assert_eq!(frames[0].args.len(), 4);
+        assert_eq!(frames[4].function.as_deref(), Some("RtlUserThreadStart"));
+    }
+
+    #[test]
+    fn test_parse_all_threads() {
+        let stacks = parse(include_str!("../../tests/fixtures/cdb/stacks_all.txt"));
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(stacks[0].thread, Some(0));
+        assert_eq!(stacks[0].id.as_deref(), Some("1a2c.2b40"));
+        assert_eq!(stacks[0].frames.len(), 6);
+        assert_eq!(stacks[1].thread, Some(1));
+        assert_eq!(stacks[1].frames.len(), 4);
+
+        let third = &stacks[2];
+        assert_eq!(third.id.as_deref(), Some("1a2c.0c3c"));
+        assert_eq!(third.frames.len(), 3);
+
+        // 0x0 帧没有模块和函数
+        let zero = &third.frames[0];
+        assert_eq!(zero.call_site, "0x0");
+        assert_eq!(zero.module, None);
+        assert_eq!(zero.function, None);
+        assert_eq!(zero.offset, None);
+
+        // 无符号的模块偏移
+        let export = &third.frames[1];
+        assert_eq!(export.module.as_deref(), Some("thirdparty"));
+        assert_eq!(export.function, None);
+        assert_eq!(export.offset, Some(0x1c44));
+
+        assert_eq!(third.warnings.len(), 1);
+        assert_eq!(third.warnings[0].frame_index, 2);
+        assert!(third.warnings[0]
+            .message
+            .starts_with("WARNING: Stack unwind information not available"));
+    }
+
+    #[test]
+    fn test_parse_kv_x86() {
+        let stacks = parse(include_str!("../../tests/fixtures/stack/x86_kv.txt"));
+        let stack = &stacks[0];
+        assert_eq!(stack.frames.len(), 5);
+
+        let top = &stack.frames[0];
+        assert_eq!(top.frame_no, None);
+        assert_eq!(top.child_sp, Some(0x0019_f6c4));
+        assert_eq!(top.ret_addr, Some(0x7720_a1b2));
+        assert_eq!(top.args, vec![0, 1, 0x0019_f6e8]);
+        assert_eq!(top.call_site, "legacy!CMainWnd::OnPaint+0x3c");
+        assert_eq!(top.function.as_deref(), Some("CMainWnd::OnPaint"));
+        assert_eq!(
+            top.source_file.as_deref(),
+            Some("c:\\build\\legacy\\mainwnd.cpp")
+        );
+        assert_eq!(top.line, Some(77));
+
+        assert_eq!(
+            stack.frames[1].call_site,
+            "user32!_InternalCallWinProc+0x2b"
+        );
+        assert_eq!(stack.frames[3].module.as_deref(), Some("plugin"));
+        assert_eq!(stack.frames[4].call_site, "0x0");
+
+        assert_eq!(stack.warnings.len(), 1);
+        assert_eq!(stack.warnings[0].frame_index, 3);
+        assert_eq!(
+            stack.warnings[0].message,
+            "WARNING: Unable to verify checksum for plugin.dll"
+        );
+    }
+
+    #[test]
+    fn test_parse_kpn_x64() {
+        let stacks = parse(include_str!("../../tests/fixtures/stack/x64_kpn.txt"));
+        let frames = &stacks[0].frames;
+        assert_eq!(frames.len(), 4);
+
+        assert_eq!(frames[0].function.as_deref(), Some("Widget::Render"));
+        assert_eq!(frames[0].offset, Some(0x24));
+        assert!(frames[0].args.is_empty());
+
+        assert!(frames[1].inline);
+        assert_eq!(
+            frames[1].function.as_deref(),
+            Some("std::_Func_impl<<lambda_1>,void>::operator()")
+        );
+        assert_eq!(
+            frames[1].source_file.as_deref(),
+            Some("C:\\Program Files\\MSVC\\include\\functional")
+        );
+
+        assert_eq!(frames[2].function.as_deref(), Some("Scheduler::operator()"));
+
+        // 帧号为十六进制
+        assert_eq!(frames[3].frame_no, Some(0x0a));
+        assert_eq!(frames[3].function.as_deref(), Some("main"));
+        assert_eq!(frames[3].line, Some(41));
+    }
+
+    #[test]
+    fn test_parse_k_x64() {
+        let stacks = parse(include_str!("../../tests/fixtures/stack/x64_k.txt"));
+        let frames = &stacks[0].frames;
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].frame_no, None);
+        assert_eq!(frames[0].child_sp, Some(0xa5_e6cf_f6a8));
+        assert_eq!(frames[1].module.as_deref(), Some("KERNELBASE"));
+        assert_eq!(frames[2].module.as_deref(), Some("contoso"));
+        assert_eq!(frames[2].offset, Some(0x1200));
+    }
+
+    #[test]
+    fn test_parse_with_prompt_and_no_header() {
+        let lines =
+            ["0:000> 00 000000a5`e6cff6a8 00007ffb`1b6e2f4e     ntdll!NtDelayExecution+0x14"];
+        let stacks = parse_stack(&lines);
+        assert_eq!(stacks[0].frames.len(), 1);
+        assert_eq!(stacks[0].frames[0].frame_no, Some(0));
+        assert_eq!(
+            stacks[0].frames[0].function.as_deref(),
+            Some("NtDelayExecution")
+        );
+    }
+
+    #[test]
+    fn test_parse_empty_output() {
+        assert!(parse_stack::<&str>(&[]).is_empty());
+    }
+}
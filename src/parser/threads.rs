@@ -0,0 +1,365 @@
+//! 线程列表（`~`、`~*`）和 `!runaway` 输出解析
+
+use super::stack::{parse_stack, StackFrame};
+use super::{hex, next_token, parse_hex, strip_prompt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 线程信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ThreadInfo {
+    /// 调试器线程序号
+    pub index: u32,
+    /// 进程 ID
+    #[serde(with = "hex::plain")]
+    pub pid: u32,
+    /// 线程 ID
+    #[serde(with = "hex::plain")]
+    pub tid: u32,
+    /// 挂起计数
+    pub suspend_count: u32,
+    /// 线程环境块地址
+    #[serde(with = "hex::option")]
+    pub teb: Option<u64>,
+    /// 是否被调试器冻结
+    pub frozen: bool,
+    /// 是否为当前线程（`.` 标记）
+    pub current: bool,
+    /// 是否为触发最后事件的线程（`#` 标记）
+    pub last_event: bool,
+    /// 线程名（SetThreadDescription 设置的描述）
+    pub name: Option<String>,
+    /// 线程入口点（仅 `~*` 输出）
+    pub start: Option<String>,
+    /// 线程优先级（仅 `~*` 输出）
+    pub priority: Option<i32>,
+}
+
+/// `!runaway` 输出的线程时间（毫秒）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ThreadTimes {
+    /// 调试器线程序号
+    pub index: u32,
+    /// 用户态 CPU 时间
+    pub user_ms: Option<u64>,
+    /// 内核态 CPU 时间
+    pub kernel_ms: Option<u64>,
+    /// 线程存活时间
+    pub elapsed_ms: Option<u64>,
+}
+
+/// 每个线程的汇总信息（线程信息 + CPU 时间 + 栈顶帧）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThreadSummary {
+    /// 线程信息
+    #[serde(flatten)]
+    pub thread: ThreadInfo,
+    /// 用户态 CPU 时间（毫秒）
+    pub user_ms: Option<u64>,
+    /// 内核态 CPU 时间（毫秒）
+    pub kernel_ms: Option<u64>,
+    /// 线程存活时间（毫秒）
+    pub elapsed_ms: Option<u64>,
+    /// 栈顶帧
+    pub top_frame: Option<StackFrame>,
+}
+
+/// 解析 `~` / `~*` 输出
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回线程列表
+pub fn parse_threads<S: AsRef<str>>(lines: &[S]) -> Vec<ThreadInfo> {
+    let mut threads: Vec<ThreadInfo> = Vec::new();
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(thread) = parse_thread_line(line) {
+            threads.push(thread);
+            continue;
+        }
+
+        // `~*` 的详细信息行
+        let Some(thread) = threads.last_mut() else {
+            continue;
+        };
+        if let Some(start) = line.strip_prefix("Start:") {
+            let start = start.trim();
+            let symbol = start.split_once(" (").map_or(start, |(symbol, _)| symbol);
+            thread.start = Some(symbol.to_string());
+        } else if let Some(priority) = line.strip_prefix("Priority:") {
+            thread.priority = priority
+                .split_whitespace()
+                .next()
+                .and_then(|p| p.parse().ok());
+        }
+    }
+
+    threads
+}
+
+/// 解析线程行（例如 ``.  0  Id: 1a2c.2b40 Suspend: 0 Teb: 000000a5`e6a1e000 Unfrozen "Main"``）
+fn parse_thread_line(line: &str) -> Option<ThreadInfo> {
+    let mut thread = ThreadInfo::default();
+    let mut rest = line;
+
+    match rest.chars().next()? {
+        '.' => thread.current = true,
+        '#' => thread.last_event = true,
+        _ => {}
+    }
+    rest = rest.trim_start_matches(['.', '#']);
+
+    let (index, tail) = next_token(rest)?;
+    thread.index = index.parse().ok()?;
+    let (label, tail) = next_token(tail)?;
+    if label != "Id:" {
+        return None;
+    }
+    let (id, mut tail) = next_token(tail)?;
+    let (pid, tid) = id.split_once('.')?;
+    thread.pid = u32::from_str_radix(pid, 16).ok()?;
+    thread.tid = u32::from_str_radix(tid, 16).ok()?;
+
+    while let Some((token, next)) = next_token(tail) {
+        match token {
+            "Suspend:" => {
+                let (value, next) = next_token(next)?;
+                thread.suspend_count = value.parse().ok()?;
+                tail = next;
+            }
+            "Teb:" => {
+                let (value, next) = next_token(next)?;
+                thread.teb = parse_hex(value);
+                tail = next;
+            }
+            "Frozen" => {
+                thread.frozen = true;
+                tail = next;
+            }
+            "Unfrozen" => tail = next,
+            _ if token.starts_with('"') => {
+                let name = tail.trim().trim_matches('"');
+                if !name.is_empty() {
+                    thread.name = Some(name.to_string());
+                }
+                break;
+            }
+            _ => tail = next,
+        }
+    }
+
+    Some(thread)
+}
+
+/// 解析 `!runaway` 输出（支持 `!runaway 7` 的用户态、内核态和存活时间三段）
+///
+/// # 参数
+/// * `lines` - 命令输出行
+///
+/// # 返回
+/// 返回按线程序号排序的时间信息
+pub fn parse_runaway<S: AsRef<str>>(lines: &[S]) -> Vec<ThreadTimes> {
+    #[derive(Clone, Copy)]
+    enum Section {
+        User,
+        Kernel,
+        Elapsed,
+    }
+
+    let mut times: HashMap<u32, ThreadTimes> = HashMap::new();
+    let mut section = Section::User;
+
+    for raw in lines {
+        let line = strip_prompt(raw.as_ref()).trim();
+        match line {
+            "User Mode Time" => section = Section::User,
+            "Kernel Mode Time" => section = Section::Kernel,
+            "Elapsed Time" => section = Section::Elapsed,
+            _ => {
+                let Some((thread, time)) = line.split_once(char::is_whitespace) else {
+                    continue;
+                };
+                let Some(index) = thread.split_once(':').and_then(|(i, _)| i.parse().ok()) else {
+                    continue;
+                };
+                let Some(ms) = parse_duration_ms(time.trim()) else {
+                    continue;
+                };
+
+                let entry = times.entry(index).or_insert_with(|| ThreadTimes {
+                    index,
+                    ..Default::default()
+                });
+                match section {
+                    Section::User => entry.user_ms = Some(ms),
+                    Section::Kernel => entry.kernel_ms = Some(ms),
+                    Section::Elapsed => entry.elapsed_ms = Some(ms),
+                }
+            }
+        }
+    }
+
+    let mut times: Vec<ThreadTimes> = times.into_values().collect();
+    times.sort_by_key(|t| t.index);
+    times
+}
+
+/// 解析 `0 days 0:00:01.234` 形式的时间
+fn parse_duration_ms(text: &str) -> Option<u64> {
+    let (days, clock) = text.split_once(" days ")?;
+    let days: u64 = days.trim().parse().ok()?;
+    let mut parts = clock.trim().split(':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds = parts.next()?;
+    let (secs, millis) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    let secs: u64 = secs.parse().ok()?;
+    let millis: u64 = format!("{:0<3}", millis).get(..3)?.parse().ok()?;
+
+    Some((((days * 24 + hours) * 60 + minutes) * 60 + secs) * 1000 + millis)
+}
+
+/// 将线程列表与 `!runaway` 时间和 `~*k1` 栈顶帧按线程序号合并
+///
+/// # 参数
+/// * `threads` - `~*` 解析结果
+/// * `runaway` - `!runaway` 输出行（为空时不合并 CPU 时间）
+/// * `top_frames` - `~*k1` 输出行（为空时不合并栈顶帧）
+pub fn summarize_threads<S: AsRef<str>>(
+    threads: Vec<ThreadInfo>,
+    runaway: &[S],
+    top_frames: &[S],
+) -> Vec<ThreadSummary> {
+    let mut times: HashMap<u32, ThreadTimes> = parse_runaway(runaway)
+        .into_iter()
+        .map(|t| (t.index, t))
+        .collect();
+    let mut frames: HashMap<u32, StackFrame> = parse_stack(top_frames)
+        .into_iter()
+        .filter_map(|s| Some((s.thread?, s.frames.into_iter().next()?)))
+        .collect();
+
+    threads
+        .into_iter()
+        .map(|thread| {
+            let times = times.remove(&thread.index).unwrap_or_default();
+            ThreadSummary {
+                top_frame: frames.remove(&thread.index),
+                user_ms: times.user_ms,
+                kernel_ms: times.kernel_ms,
+                elapsed_ms: times.elapsed_ms,
+                thread,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(sample: &str) -> Vec<&str> {
+        sample.lines().collect()
+    }
+
+    #[test]
+    fn test_parse_threads_brief() {
+        let threads = parse_threads(&lines(include_str!("../../tests/fixtures/cdb/threads.txt")));
+        assert_eq!(threads.len(), 3);
+        assert_eq!(threads[0].index, 0);
+        assert_eq!(threads[0].pid, 0x1a2c);
+        assert_eq!(threads[0].tid, 0x2b40);
+        assert_eq!(threads[0].teb, Some(0xa5_e6a1_e000));
+        assert!(threads[0].current);
+        assert!(!threads[1].current);
+        assert_eq!(threads[2].tid, 0x0c3c);
+        assert_eq!(threads[2].start, None);
+    }
+
+    #[test]
+    fn test_parse_threads_verbose() {
+        let threads = parse_threads(&lines(include_str!(
+            "../../tests/fixtures/cdb/threads_verbose.txt"
+        )));
+        assert_eq!(threads.len(), 3);
+
+        assert_eq!(threads[0].name.as_deref(), Some("Main"));
+        assert_eq!(threads[0].start.as_deref(), Some("contoso!wmainCRTStartup"));
+        assert_eq!(threads[0].priority, Some(0));
+
+        assert!(threads[1].frozen);
+        assert_eq!(threads[1].suspend_count, 1);
+        assert_eq!(threads[1].name, None);
+
+        assert!(threads[2].last_event);
+        assert!(!threads[2].current);
+        assert_eq!(threads[2].name.as_deref(), Some("Render worker"));
+        assert_eq!(threads[2].start.as_deref(), Some("thirdparty+0x1000"));
+        assert_eq!(threads[2].priority, Some(2));
+
+        let json = serde_json::to_value(&threads[2]).unwrap();
+        assert_eq!(json["pid"], "0x1a2c");
+        assert_eq!(json["tid"], "0xc3c");
+    }
+
+    #[test]
+    fn test_parse_runaway() {
+        let times = parse_runaway(&lines(include_str!("../../tests/fixtures/cdb/runaway.txt")));
+        assert_eq!(times.len(), 3);
+        assert_eq!(times[0].user_ms, Some(1234));
+        assert_eq!(times[0].kernel_ms, Some(312));
+        assert_eq!(times[0].elapsed_ms, Some(312_001));
+        assert_eq!(times[2].user_ms, Some(12_765));
+        assert_eq!(times[2].elapsed_ms, Some(93_899_420));
+    }
+
+    #[test]
+    fn test_summarize_threads() {
+        let threads = parse_threads(&lines(include_str!(
+            "../../tests/fixtures/cdb/threads_verbose.txt"
+        )));
+        let runaway = lines(include_str!("../../tests/fixtures/cdb/runaway.txt"));
+        let stacks = lines(include_str!("../../tests/fixtures/cdb/stacks_top.txt"));
+
+        let summary = summarize_threads(threads, &runaway, &stacks);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].user_ms, Some(1234));
+        assert_eq!(
+            summary[0].top_frame.as_ref().unwrap().call_site,
+            "contoso!Widget::Render+0x24"
+        );
+        assert_eq!(
+            summary[1].top_frame.as_ref().unwrap().function.as_deref(),
+            Some("NtWaitForWorkViaWorkerFactory")
+        );
+        assert_eq!(summary[2].top_frame.as_ref().unwrap().call_site, "0x0");
+
+        let json = serde_json::to_value(&summary[0]).unwrap();
+        assert_eq!(json["index"], 0);
+        assert_eq!(json["user_ms"], 1234);
+    }
+
+    #[test]
+    fn test_summarize_threads_without_extras() {
+        let threads =
+            parse_threads(&["   0  Id: 10.20 Suspend: 0 Teb: 00000000`7ffde000 Unfrozen"]);
+        let summary = summarize_threads::<&str>(threads, &[], &[]);
+        assert_eq!(summary[0].thread.tid, 0x20);
+        assert_eq!(summary[0].user_ms, None);
+        assert_eq!(summary[0].top_frame, None);
+    }
+
+    #[test]
+    fn test_parse_duration_ms() {
+        assert_eq!(parse_duration_ms("0 days 0:00:00.000"), Some(0));
+        assert_eq!(parse_duration_ms("0 days 0:01:02.5"), Some(62_500));
+        assert_eq!(parse_duration_ms("garbage"), None);
+    }
+}
@@ -0,0 +1,661 @@
+//! 命令策略模块
+//!
+//! 在 `run_windbg_cmd` 把命令交给 CDB 之前，按允许/拒绝模式检查命令。
+//! 用 `;` 或换行分隔的多条命令逐条检查，`~*e` 等线程前缀后的命令也会被检查。
+//! 只读的远程会话另外拒绝执行控制和修改内存的命令（[`check_read_only`]）。
+
+use crate::error::ToolError;
+use crate::utils::glob_match;
+use serde::Deserialize;
+
+/// 内置策略配置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyProfile {
+    /// 不限制（只应用自定义模式）
+    #[default]
+    Unrestricted,
+    /// 拒绝执行外部程序、写文件、修改内存和控制目标的命令
+    Safe,
+}
+
+impl std::str::FromStr for PolicyProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unrestricted" => Ok(PolicyProfile::Unrestricted),
+            "safe" => Ok(PolicyProfile::Safe),
+            other => Err(format!(
+                "unknown policy profile '{}' (expected 'unrestricted' or 'safe')",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PolicyProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyProfile::Unrestricted => write!(f, "unrestricted"),
+            PolicyProfile::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// `safe` 配置拒绝的命令模式
+///
+/// 包括执行外部程序（`.shell`、`.create`）、写文件（`.dump`、`.logopen`、`.writemem`）、
+/// 修改内存（`e*`、`f`）、加载扩展或脚本、控制目标、能嵌套执行其他命令的流程控制命令，
+/// 以及定义别名或延迟执行命令的命令（`as`、`.pcmd`）。
+pub const SAFE_DENY_PATTERNS: &[&str] = &[
+    // 执行外部程序
+    ".shell",
+    "!!*",
+    ".create",
+    ".createdir",
+    ".attach",
+    ".restart",
+    ".kill",
+    ".detach",
+    ".reboot",
+    ".crash",
+    // 写文件
+    ".dump",
+    ".dumpcab",
+    ".writemem",
+    ".write_cmd_hist",
+    ".logopen",
+    ".logappend",
+    ".settings",
+    // 修改内存
+    "e",
+    "e?",
+    "ez?",
+    "f",
+    "fp",
+    // 加载扩展和脚本
+    ".load",
+    ".loadby",
+    ".unload",
+    ".cordll",
+    ".scriptload",
+    ".scriptrun",
+    "$<*",
+    "$><*",
+    "$$<*",
+    "$$><*",
+    "$$>a<*",
+    // 嵌套执行其他命令的流程控制
+    ".block",
+    ".if",
+    ".elsif",
+    ".else",
+    ".for",
+    ".foreach",
+    ".while",
+    ".do",
+    ".catch",
+    "j",
+    "z",
+    ".cmdtree",
+    // 别名展开和延迟执行（在之后的命令或提示符处执行任意命令）
+    "as",
+    "ad",
+    ".pcmd",
+    ".ocommand",
+];
+
+/// 只读远程会话拒绝的命令模式
+///
+/// 包括执行控制（`g`、`p`、`t` 系列、`wt`）、断点（`bp`、`ba` 等）、结束或分离目标、
+/// 修改内存（`e*`、`f`、`m`、`.readmem`、`.dvalloc`）、修改寄存器（`r <寄存器>=<值>`），
+/// 以及能间接执行上述命令的流程控制、脚本、别名和延迟执行（`.pcmd`、`.ocommand`）命令。
+pub const READ_ONLY_DENY_PATTERNS: &[&str] = &[
+    // 执行控制
+    "g",
+    "g?",
+    "p",
+    "pa",
+    "pc",
+    "pct",
+    "ph",
+    "pt",
+    "t",
+    "ta",
+    "tb",
+    "tc",
+    "tct",
+    "th",
+    "tt",
+    "wt",
+    ".step_filter",
+    ".call",
+    // 断点
+    "bp*",
+    "bu*",
+    "bm*",
+    "ba*",
+    "bc",
+    "bd",
+    "be",
+    "bs",
+    "bsc",
+    // 结束、分离或重启目标
+    "q",
+    "q?",
+    ".kill",
+    ".detach",
+    ".abandon",
+    ".restart",
+    ".reboot",
+    ".crash",
+    ".breakin",
+    // 修改内存和寄存器
+    "e",
+    "e?",
+    "ez?",
+    "f",
+    "fp",
+    "m",
+    ".readmem",
+    ".dvalloc",
+    ".dvfree",
+    "r*=*",
+    // 嵌套执行其他命令的流程控制
+    ".block",
+    ".if",
+    ".elsif",
+    ".else",
+    ".for",
+    ".foreach",
+    ".while",
+    ".do",
+    ".catch",
+    "j",
+    "z",
+    // 脚本和别名
+    "$<*",
+    "$><*",
+    "$$<*",
+    "$$><*",
+    "$$>a<*",
+    ".cmdtree",
+    ".scriptload",
+    ".scriptrun",
+    "as",
+    "ad",
+    ".pcmd",
+    ".ocommand",
+];
+
+/// 只读远程会话拒绝的线程命令（`~3n` 挂起、`~3m` 恢复、`~3f` 冻结、`~3u` 解冻线程）
+///
+/// 去掉线程前缀后这些命令与 `n`（设置基数）、`u`（反汇编）等同名，不能作为普通模式匹配。
+const READ_ONLY_THREAD_COMMANDS: &[&str] = &["n", "m", "f", "u"];
+
+/// 检查只读会话中的命令行
+///
+/// # 参数
+/// * `command_line` - 可能包含多条 `;` 或换行分隔命令的命令行
+///
+/// # 错误
+/// 如果任一命令会改变被调试目标，返回 [`ToolError::PolicyViolation`]
+pub fn check_read_only(command_line: &str) -> Result<(), ToolError> {
+    for command in split_commands(command_line) {
+        let thread_command = command
+            .strip_prefix('~')
+            .map(|_| strip_target_prefix(command))
+            .filter(|rest| {
+                READ_ONLY_THREAD_COMMANDS
+                    .iter()
+                    .any(|name| rest.eq_ignore_ascii_case(name))
+            });
+        if let Some(rest) = thread_command {
+            return Err(ToolError::PolicyViolation {
+                command: command.to_string(),
+                reason: format!(
+                    "changes the debuggee (thread command `~{}`) and the session is read-only",
+                    rest
+                ),
+            });
+        }
+        if let Some(pattern) = find_match(READ_ONLY_DENY_PATTERNS.iter().copied(), command) {
+            return Err(ToolError::PolicyViolation {
+                command: command.to_string(),
+                reason: format!(
+                    "changes the debuggee (matches `{}`) and the session is read-only",
+                    pattern
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 命令策略
+///
+/// 拒绝模式优先于允许模式；允许列表非空时，每条命令都必须匹配其中一个模式。
+/// 模式是不区分大小写的通配符（`*`、`?`），与完整命令或命令名（见 [`command_name`]）匹配，
+/// 例如 `.shell` 匹配 `.shell dir`，`e?` 匹配 `eb 1000 90` 和 `eb@rsp 90`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandPolicy {
+    /// 内置策略配置
+    pub profile: PolicyProfile,
+    /// 允许的命令模式
+    pub allow: Vec<String>,
+    /// 拒绝的命令模式
+    pub deny: Vec<String>,
+}
+
+impl CommandPolicy {
+    /// 使用内置配置创建策略
+    pub fn with_profile(profile: PolicyProfile) -> Self {
+        Self {
+            profile,
+            ..Default::default()
+        }
+    }
+
+    /// 是否不做任何限制
+    pub fn is_unrestricted(&self) -> bool {
+        self.profile == PolicyProfile::Unrestricted && self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// 检查命令行
+    ///
+    /// # 参数
+    /// * `command_line` - 可能包含多条 `;` 或换行分隔命令的命令行
+    ///
+    /// # 错误
+    /// 如果任一命令匹配拒绝模式，或不在非空的允许列表中，返回 [`ToolError::PolicyViolation`]
+    pub fn check(&self, command_line: &str) -> Result<(), ToolError> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+
+        for command in split_commands(command_line) {
+            let profile_deny = match self.profile {
+                PolicyProfile::Safe => SAFE_DENY_PATTERNS,
+                PolicyProfile::Unrestricted => &[],
+            };
+            let deny = profile_deny
+                .iter()
+                .copied()
+                .chain(self.deny.iter().map(String::as_str));
+            if let Some(pattern) = find_match(deny, command) {
+                return Err(ToolError::PolicyViolation {
+                    command: command.to_string(),
+                    reason: format!("matches deny pattern `{}`", pattern),
+                });
+            }
+
+            let allow = self.allow.iter().map(String::as_str);
+            if !self.allow.is_empty() && find_match(allow, command).is_none() {
+                return Err(ToolError::PolicyViolation {
+                    command: command.to_string(),
+                    reason: "is not in the allow list".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 查找第一个与命令匹配的模式
+fn find_match<'a>(mut patterns: impl Iterator<Item = &'a str>, command: &str) -> Option<&'a str> {
+    patterns.find(|pattern| command_matches(pattern, command))
+}
+
+/// 模式是否与命令（完整命令或命令名）匹配
+pub(crate) fn command_matches(pattern: &str, command: &str) -> bool {
+    glob_match(pattern, command) || glob_match(pattern, command_name(command))
+}
+
+/// 取命令名
+///
+/// CDB 允许参数直接接在命令名后面（`eb@rsp 90`、`g=0x1234`、`f@rsp L10 0`），
+/// 因此命令名是开头由字母、`.`、`!`、`~` 组成的部分，在 `@`、`=`、数字、引号等其他字符处结束。
+/// `.`/`!` 开头的命名命令（`.write_cmd_hist`、`!wow64exts.sw`）还可以包含数字和下划线。
+fn command_name(command: &str) -> &str {
+    let named = command.starts_with(['.', '!']);
+    let end = command
+        .find(|c: char| {
+            let verb_char = c.is_ascii_alphabetic() || matches!(c, '.' | '!' | '~');
+            !(verb_char || named && (c.is_ascii_digit() || c == '_'))
+        })
+        .unwrap_or(command.len());
+    &command[..end]
+}
+
+/// 拆分命令行
+///
+/// 按 `;` 和换行拆分（不考虑引号，以免引号内的命令逃过检查），去掉空命令。
+/// CDB 把输入中的每一行当作独立的命令行执行，因此换行与 `;` 同样是命令分隔符。
+/// `~*e <命令>` / `~3e <命令>` 等对线程执行的命令，同时返回前缀后的命令。
+pub(crate) fn split_commands(command_line: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    for command in command_line.split([';', '\n', '\r']).map(str::trim) {
+        if command.is_empty() {
+            continue;
+        }
+        commands.push(command);
+
+        let stripped = strip_target_prefix(command);
+        if stripped.len() != command.len() {
+            let mut chars = stripped.chars();
+            let is_execute = matches!(chars.next(), Some('e' | 'E'))
+                && chars.next().is_some_and(char::is_whitespace);
+            if is_execute {
+                commands.extend(split_commands(stripped[1..].trim()));
+            } else if !stripped.is_empty() {
+                commands.push(stripped);
+            }
+        }
+    }
+    commands
+}
+
+/// 去掉进程/线程前缀（例如 `~*`、`~3`、`~~[2b40]`、`|0`）
+fn strip_target_prefix(command: &str) -> &str {
+    let mut rest = command;
+    while let Some(after) = rest.strip_prefix(['~', '|']) {
+        let after = after.strip_prefix('~').unwrap_or(after);
+        rest = if let Some(bracketed) = after.strip_prefix('[') {
+            bracketed.split_once(']').map_or("", |(_, tail)| tail)
+        } else {
+            after.trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '*' | '.' | '#'))
+        };
+        rest = rest.trim_start();
+    }
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe() -> CommandPolicy {
+        CommandPolicy::with_profile(PolicyProfile::Safe)
+    }
+
+    fn rejected(policy: &CommandPolicy, command: &str) -> String {
+        match policy.check(command) {
+            Err(ToolError::PolicyViolation { command, .. }) => command,
+            other => panic!(
+                "Expected PolicyViolation for {:?}, got {:?}",
+                command, other
+            ),
+        }
+    }
+
+    #[test]
+    fn test_unrestricted_allows_everything() {
+        let policy = CommandPolicy::default();
+        assert!(policy.is_unrestricted());
+        assert!(policy.check(".shell dir; eb 1000 90").is_ok());
+    }
+
+    #[test]
+    fn test_safe_profile() {
+        let policy = safe();
+        for command in [
+            "k",
+            "!analyze -v",
+            "lm vm contoso",
+            "dq @rsp L8",
+            ".echo hello; .echo world",
+            "~*k",
+            ".ecxr; kb",
+        ] {
+            assert!(policy.check(command).is_ok(), "{}", command);
+        }
+
+        for command in [
+            ".shell dir",
+            ".SHELL -ci \"k\" findstr x",
+            "!! dir",
+            ".create notepad.exe",
+            ".write_cmd_hist C:\\out.txt",
+            ".logopen C:\\log.txt",
+            ".dump /ma C:\\copy.dmp",
+            "eb 00401000 90",
+            "eD @rsp 0",
+            "ed",
+            "eza 1000 \"text\"",
+            "f 1000 L10 0",
+            "$$>a< C:\\script.txt",
+            ".foreach (x {k}) { .echo x }",
+            ".load C:\\evil.dll",
+        ] {
+            assert_eq!(rejected(&policy, command), command);
+        }
+    }
+
+    #[test]
+    fn test_multi_command_lines() {
+        let policy = safe();
+        assert_eq!(rejected(&policy, "k; .shell dir"), ".shell dir");
+        assert_eq!(rejected(&policy, "k;;  eb 1000 90 ;"), "eb 1000 90");
+        // 引号内的分号同样拆分
+        assert_eq!(rejected(&policy, ".echo \"a; .shell dir\""), ".shell dir\"");
+        // 换行同样分隔命令
+        assert_eq!(rejected(&policy, "k\n.shell dir"), ".shell dir");
+        assert_eq!(rejected(&policy, "k\r\n~*e .shell dir"), ".shell dir");
+    }
+
+    #[test]
+    fn test_thread_prefixes() {
+        let policy = safe();
+        assert!(policy.check("~*k").is_ok());
+        assert!(policy.check("~0s").is_ok());
+        assert_eq!(rejected(&policy, "~*e .shell dir"), ".shell dir");
+        assert_eq!(rejected(&policy, "~~[2b40]e eb 1000 90"), "eb 1000 90");
+        assert_eq!(rejected(&policy, "|0s; ~3 f 1000 L1 0"), "f 1000 L1 0");
+    }
+
+    #[test]
+    fn test_attached_arguments() {
+        // 参数直接接在命令名后面
+        assert_eq!(command_name("eb@rsp 90"), "eb");
+        assert_eq!(command_name("g=0x1234"), "g");
+        assert_eq!(command_name("f@rsp L10 0"), "f");
+        assert_eq!(command_name("ed1000 0"), "ed");
+        assert_eq!(command_name(".block{g}"), ".block");
+        assert_eq!(
+            command_name(".write_cmd_hist C:\\out.txt"),
+            ".write_cmd_hist"
+        );
+        assert_eq!(command_name("!wow64exts.sw"), "!wow64exts.sw");
+        assert_eq!(command_name("$$><C:\\step.txt"), "");
+
+        let policy = safe();
+        for command in ["eb@rsp 90", "ed@rsp 0", "f@rsp L10 0", "eza\"x\""] {
+            assert_eq!(rejected(&policy, command), command);
+        }
+        assert!(policy.check("dq@rsp L8").is_ok());
+
+        let policy = CommandPolicy {
+            deny: vec!["g".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(rejected(&policy, "g=0x1234"), "g=0x1234");
+        assert!(policy.check("gh").is_ok());
+    }
+
+    #[test]
+    fn test_safe_profile_aliases_and_deferred_commands() {
+        let policy = safe();
+        for command in [
+            "aS foo .shell",
+            "as /x foo 1",
+            "ad foo",
+            ".pcmd -s \".shell dir\"",
+            ".ocommand .shell",
+            ".settings save C:\\out.txt",
+            ".cordll -lp C:\\evil",
+        ] {
+            assert_eq!(rejected(&policy, command), command);
+        }
+    }
+
+    #[test]
+    fn test_custom_allow_and_deny() {
+        let policy = CommandPolicy {
+            profile: PolicyProfile::Unrestricted,
+            allow: vec!["k*".to_string(), "!analyze*".to_string(), "lm".to_string()],
+            deny: vec!["kd".to_string()],
+        };
+        assert!(policy.check("kb 20").is_ok());
+        assert!(policy.check("!analyze -v; lm m contoso").is_ok());
+
+        // 拒绝模式优先于允许模式
+        match policy.check("kd") {
+            Err(ToolError::PolicyViolation { reason, .. }) => {
+                assert!(reason.contains("deny pattern `kd`"))
+            }
+            other => panic!("Expected PolicyViolation, got {:?}", other),
+        }
+        match policy.check("k; dq @rsp") {
+            Err(err @ ToolError::PolicyViolation { .. }) => assert_eq!(
+                err.to_string(),
+                "Command rejected by policy: `dq @rsp` is not in the allow list"
+            ),
+            other => panic!("Expected PolicyViolation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_only_commands() {
+        for command in [
+            "k", "r", "r rax", "dq @rsp", "bl", "~*k", ".frame 2", "!peb", "lm",
+        ] {
+            assert!(check_read_only(command).is_ok(), "{}", command);
+        }
+
+        for command in [
+            "g",
+            "gh",
+            "p",
+            "pc",
+            "t",
+            "wt",
+            "bp contoso!main",
+            "ba r8 00401000",
+            "bc *",
+            "q",
+            ".kill",
+            ".detach",
+            "eb 1000 90",
+            "f 1000 L4 0",
+            "r rax=0",
+            "r @rip = 0x1000",
+        ] {
+            assert!(check_read_only(command).is_err(), "{}", command);
+        }
+
+        match check_read_only("k; ~0s; g") {
+            Err(ToolError::PolicyViolation { command, reason }) => {
+                assert_eq!(command, "g");
+                assert!(reason.contains("read-only"));
+            }
+            other => panic!("Expected PolicyViolation, got {:?}", other),
+        }
+        // 线程前缀后的命令同样检查（`~0f` 冻结线程）
+        assert!(check_read_only("~0f").is_err());
+        assert!(check_read_only("~*e g").is_err());
+    }
+
+    #[test]
+    fn test_read_only_bypasses() {
+        let rejected_read_only = |command: &str| match check_read_only(command) {
+            Err(ToolError::PolicyViolation { command, .. }) => command,
+            other => panic!(
+                "Expected PolicyViolation for {:?}, got {:?}",
+                command, other
+            ),
+        };
+
+        // 换行分隔的命令
+        assert_eq!(rejected_read_only("k\ng"), "g");
+        // 嵌套执行命令的流程控制
+        for command in [
+            ".block { g }",
+            ".if (1) { g }",
+            ".if (0) { k } .else { g }",
+            ".for (r $t0 = 0; @$t0 < 1; r $t0 = @$t0 + 1) { g }",
+            ".foreach (x {k}) { g }",
+            ".while (1) { g }",
+            ".do { g } (0)",
+            "j 1 'g'",
+            "z (1)",
+        ] {
+            assert!(check_read_only(command).is_err(), "{}", command);
+        }
+        // 脚本和别名
+        for command in [
+            "$$><C:\\step.txt",
+            "$$>a< C:\\step.txt",
+            "$<C:\\step.txt",
+            ".cmdtree C:\\tree.txt",
+            ".scriptrun C:\\step.js",
+            "as Go g",
+            "aS /x Go 1",
+            "ad Go",
+        ] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+        // 完整的 p*/t* 单步命令族
+        for command in [
+            "pa 00401000",
+            "pc",
+            "pct",
+            "ph",
+            "pt",
+            "ta 00401000",
+            "tb",
+            "tc",
+            "tct",
+            "th",
+            "tt",
+        ] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+        // 写入或分配目标内存
+        for command in [
+            ".readmem C:\\bytes.bin 00401000 L10",
+            ".dvalloc 1000",
+            ".dvfree 00401000 0",
+        ] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+        // 挂起、恢复、冻结和解冻线程
+        for command in ["~n", "~3n", "~m", "~*m", "~0u", "~[2b40]f"] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+
+        // 参数直接接在命令名后面
+        for command in ["eb@rsp 90", "g=0x1234", "f@rsp L10 0", "bp@rip"] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+        // 在提示符处延迟执行的命令
+        for command in [".pcmd -s g", ".ocommand g"] {
+            assert_eq!(rejected_read_only(command), command);
+        }
+
+        // 同名的非线程命令不受影响
+        for command in ["n", "n 16", "u @rip", "~*e lm", "~*e u @rip", "~0s"] {
+            assert!(check_read_only(command).is_ok(), "{}", command);
+        }
+    }
+
+    #[test]
+    fn test_profile_from_str() {
+        assert_eq!(
+            "SAFE".parse::<PolicyProfile>().unwrap(),
+            PolicyProfile::Safe
+        );
+        assert!("strict".parse::<PolicyProfile>().is_err());
+    }
+}
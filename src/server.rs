@@ -2,28 +2,122 @@
 //!
 //! 实现 MCP 协议服务器，处理工具调用和消息路由。
 
-use crate::error::ServerError;
+use crate::auth::{require_bearer_token, BearerAuth};
+use crate::config::FileConfig;
+use crate::error::{ConfigError, ServerError};
+use crate::policy::CommandPolicy;
 use crate::session::SessionManager;
+use crate::timeouts::CommandTimeouts;
 use crate::tools;
 use crate::types::*;
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
+/// HTTP 传输默认监听地址
+pub const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8765";
+
+/// HTTP 传输的 MCP 端点路径
+pub const HTTP_ENDPOINT: &str = "/mcp";
+
+/// MCP 传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// 通过 stdin/stdout 与单个客户端通信
+    #[default]
+    Stdio,
+    /// 通过 streamable HTTP（SSE）服务多个客户端
+    Http,
+}
+
+impl std::str::FromStr for Transport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "stdio" => Ok(Transport::Stdio),
+            "http" => Ok(Transport::Http),
+            other => Err(format!(
+                "unknown transport '{}' (expected 'stdio' or 'http')",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Transport::Stdio => write!(f, "stdio"),
+            Transport::Http => write!(f, "http"),
+        }
+    }
+}
+
 /// 服务器配置
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ServerConfig {
     /// 自定义 CDB 路径
     pub cdb_path: Option<PathBuf>,
     /// 符号路径
     pub symbols_path: Option<String>,
+    /// 每个会话默认附加的 CDB 命令行参数
+    pub cdb_args: Vec<String>,
     /// 命令执行超时时间
     pub timeout: Duration,
     /// 初始化超时时间
     pub init_timeout: Duration,
     /// 是否启用详细日志
     pub verbose: bool,
+    /// MCP 传输方式
+    pub transport: Transport,
+    /// HTTP 传输的监听地址
+    pub listen: SocketAddr,
+    /// HTTP 传输要求的 Bearer 令牌
+    pub auth_token: Option<String>,
+    /// 保存 Bearer 令牌的文件（`auth_token` 未设置时使用）
+    pub auth_token_file: Option<PathBuf>,
+    /// run_windbg_cmd 的命令策略
+    pub policy: CommandPolicy,
+    /// 是否所有远程会话都是只读的
+    pub read_only_remote: bool,
+    /// 空闲会话的存活时间（None 表示不回收）
+    pub session_idle_ttl: Option<Duration>,
+    /// 最大会话数（None 表示不限制）
+    pub max_sessions: Option<usize>,
+    /// 命令类别超时表（`[command_timeouts]` 段）
+    pub command_timeouts: CommandTimeouts,
+}
+
+// 手动实现 Debug，避免令牌出现在日志中
+impl std::fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("cdb_path", &self.cdb_path)
+            .field("symbols_path", &self.symbols_path)
+            .field("cdb_args", &self.cdb_args)
+            .field("timeout", &self.timeout)
+            .field("init_timeout", &self.init_timeout)
+            .field("verbose", &self.verbose)
+            .field("transport", &self.transport)
+            .field("listen", &self.listen)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("auth_token_file", &self.auth_token_file)
+            .field("policy", &self.policy)
+            .field("read_only_remote", &self.read_only_remote)
+            .field("session_idle_ttl", &self.session_idle_ttl)
+            .field("max_sessions", &self.max_sessions)
+            .field("command_timeouts", &self.command_timeouts)
+            .finish()
+    }
 }
 
 impl Default for ServerConfig {
@@ -31,58 +125,226 @@ impl Default for ServerConfig {
         Self {
             cdb_path: None,
             symbols_path: None,
+            cdb_args: Vec::new(),
             timeout: Duration::from_secs(30),
             init_timeout: Duration::from_secs(120),
             verbose: false,
+            transport: Transport::Stdio,
+            listen: DEFAULT_LISTEN_ADDR
+                .parse()
+                .expect("valid default listen address"),
+            auth_token: None,
+            auth_token_file: None,
+            policy: CommandPolicy::default(),
+            read_only_remote: false,
+            session_idle_ttl: None,
+            max_sessions: None,
+            command_timeouts: CommandTimeouts::default(),
         }
     }
 }
 
 impl ServerConfig {
     /// 从环境变量读取配置
-    pub fn from_env() -> Self {
+    ///
+    /// # 错误
+    /// 如果环境变量的值无效（例如超时时间为 0），返回错误
+    pub fn from_env() -> Result<Self, ConfigError> {
         let mut config = Self::default();
+        config.apply_env()?;
+        Ok(config)
+    }
 
+    /// 加载配置文件并叠加环境变量
+    ///
+    /// # 参数
+    /// * `config_path` - 可选的配置文件路径（为 None 时只读取环境变量）
+    ///
+    /// # 错误
+    /// 如果配置文件不存在、无法读取、包含未知键或值无效，返回错误
+    pub fn load(config_path: Option<&Path>) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+
+        if let Some(path) = config_path {
+            FileConfig::load(path)?.apply(&mut config);
+        }
+
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// 使用环境变量覆盖当前配置
+    ///
+    /// # 错误
+    /// 如果任一环境变量的值无效（例如超时时间为 0 或命令策略拼写错误），
+    /// 返回 [`ConfigError::InvalidValue`]
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.apply_vars(|key| std::env::var(key).ok())
+    }
+
+    /// 使用变量查找函数提供的值覆盖当前配置（[`apply_env`](Self::apply_env) 的实现）
+    ///
+    /// # 参数
+    /// * `var` - 按变量名返回变量值的函数
+    fn apply_vars(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
         // 读取 CDB 路径
-        if let Ok(path) = std::env::var("CDB_PATH") {
-            config.cdb_path = Some(PathBuf::from(path));
+        if let Some(path) = var("CDB_PATH") {
+            self.cdb_path = Some(PathBuf::from(path));
         }
 
         // 读取符号路径
-        if let Ok(path) = std::env::var("_NT_SYMBOL_PATH") {
-            config.symbols_path = Some(path);
+        if let Some(path) = var("_NT_SYMBOL_PATH") {
+            self.symbols_path = Some(path);
         }
 
         // 读取命令超时时间
-        if let Ok(timeout_str) = std::env::var("MCP_WINDBG_TIMEOUT") {
-            if let Ok(timeout_secs) = timeout_str.parse::<u64>() {
-                config.timeout = Duration::from_secs(timeout_secs);
-            }
+        if let Some(timeout_str) = var("MCP_WINDBG_TIMEOUT") {
+            self.timeout = parse_timeout_secs("MCP_WINDBG_TIMEOUT", &timeout_str)?;
         }
 
         // 读取初始化超时时间
-        if let Ok(timeout_str) = std::env::var("MCP_WINDBG_INIT_TIMEOUT") {
-            if let Ok(timeout_secs) = timeout_str.parse::<u64>() {
-                config.init_timeout = Duration::from_secs(timeout_secs);
-            }
+        if let Some(timeout_str) = var("MCP_WINDBG_INIT_TIMEOUT") {
+            self.init_timeout = parse_timeout_secs("MCP_WINDBG_INIT_TIMEOUT", &timeout_str)?;
         }
 
         // 读取详细日志设置
-        if let Ok(verbose_str) = std::env::var("MCP_WINDBG_VERBOSE") {
-            config.verbose =
-                verbose_str.eq_ignore_ascii_case("true") || verbose_str.eq_ignore_ascii_case("1");
+        if let Some(verbose_str) = var("MCP_WINDBG_VERBOSE") {
+            self.verbose = parse_bool("MCP_WINDBG_VERBOSE", &verbose_str)?;
         }
 
-        config
+        // 读取传输方式和监听地址
+        if let Some(transport) = var("MCP_WINDBG_TRANSPORT") {
+            self.transport = parse_var("MCP_WINDBG_TRANSPORT", &transport)?;
+        }
+        if let Some(listen) = var("MCP_WINDBG_LISTEN") {
+            self.listen = parse_var("MCP_WINDBG_LISTEN", &listen)?;
+        }
+
+        // 读取 HTTP 认证令牌：环境变量指定的令牌文件优先于配置文件中的令牌，
+        // 同一层中直接指定的令牌仍然优先于令牌文件
+        if let Some(path) = var("MCP_WINDBG_AUTH_TOKEN_FILE") {
+            self.auth_token = None;
+            self.auth_token_file = Some(PathBuf::from(path));
+        }
+        if let Some(token) = var("MCP_WINDBG_AUTH_TOKEN") {
+            self.auth_token = Some(token);
+        }
+
+        // 读取命令策略配置
+        if let Some(profile) = var("MCP_WINDBG_POLICY") {
+            self.policy.profile = parse_var("MCP_WINDBG_POLICY", &profile)?;
+        }
+        if let Some(read_only_str) = var("MCP_WINDBG_READ_ONLY") {
+            self.read_only_remote = parse_bool("MCP_WINDBG_READ_ONLY", &read_only_str)?;
+        }
+
+        // 读取会话回收配置
+        if let Some(ttl_str) = var("MCP_WINDBG_SESSION_IDLE_TTL") {
+            let ttl_secs: u64 = parse_var("MCP_WINDBG_SESSION_IDLE_TTL", &ttl_str)?;
+            self.session_idle_ttl = (ttl_secs > 0).then(|| Duration::from_secs(ttl_secs));
+        }
+        if let Some(max_str) = var("MCP_WINDBG_MAX_SESSIONS") {
+            let max_sessions: usize = parse_var("MCP_WINDBG_MAX_SESSIONS", &max_str)?;
+            self.max_sessions = (max_sessions > 0).then_some(max_sessions);
+        }
+
+        Ok(())
+    }
+
+    /// 解析 HTTP 传输要求的 Bearer 令牌
+    ///
+    /// `auth_token` 优先；否则读取 `auth_token_file` 的内容（去除首尾空白）。
+    ///
+    /// # 返回
+    /// 两者都未设置时返回 None（不启用认证）
+    ///
+    /// # 错误
+    /// 如果令牌文件无法读取，或令牌为空，返回错误
+    pub fn resolve_auth_token(&self) -> Result<Option<String>, ConfigError> {
+        let token = match (&self.auth_token, &self.auth_token_file) {
+            (Some(token), _) => token.trim().to_string(),
+            (None, Some(path)) => std::fs::read_to_string(path)
+                .map_err(|source| ConfigError::ReadFailed {
+                    path: path.clone(),
+                    source,
+                })?
+                .trim()
+                .to_string(),
+            (None, None) => return Ok(None),
+        };
+
+        if token.is_empty() {
+            return Err(ConfigError::InvalidValue {
+                key: "server.auth_token".to_string(),
+                message: "must not be empty".to_string(),
+            });
+        }
+        Ok(Some(token))
+    }
+}
+
+/// 解析环境变量中的超时时间（秒）
+///
+/// 0 会使每条命令立即超时，因此与无法解析的值一样报错。
+///
+/// # 参数
+/// * `key` - 环境变量名（用于错误信息）
+/// * `value` - 环境变量的值
+fn parse_timeout_secs(key: &str, value: &str) -> Result<Duration, ConfigError> {
+    match parse_var::<u64>(key, value)? {
+        0 => Err(ConfigError::InvalidValue {
+            key: key.to_string(),
+            message: "must be greater than 0".to_string(),
+        }),
+        secs => Ok(Duration::from_secs(secs)),
+    }
+}
+
+/// 解析环境变量的值
+///
+/// 拼写错误的值（例如 `MCP_WINDBG_POLICY=saf`）报错，而不是静默保留默认值。
+///
+/// # 参数
+/// * `key` - 环境变量名（用于错误信息）
+/// * `value` - 环境变量的值
+fn parse_var<T>(key: &str, value: &str) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e: T::Err| ConfigError::InvalidValue {
+            key: key.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// 解析环境变量中的布尔值（`true`/`1` 或 `false`/`0`，不区分大小写）
+///
+/// # 参数
+/// * `key` - 环境变量名（用于错误信息）
+/// * `value` - 环境变量的值
+fn parse_bool(key: &str, value: &str) -> Result<bool, ConfigError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(ConfigError::InvalidValue {
+            key: key.to_string(),
+            message: format!("expected 'true' or 'false', got '{}'", value),
+        }),
     }
 }
 
 /// MCP 服务器
+///
+/// 克隆后的实例共享同一个会话管理器，HTTP 传输为每个客户端连接创建一个克隆。
+#[derive(Clone)]
 pub struct McpServer {
     /// 会话管理器
     session_manager: Arc<SessionManager>,
     /// 服务器配置
-    #[allow(dead_code)]
     config: ServerConfig,
 }
 
@@ -98,11 +360,16 @@ impl McpServer {
         info!("Creating MCP server");
         info!("Configuration: {:?}", config);
 
-        let session_manager = Arc::new(SessionManager::new(
-            config.timeout,
-            config.init_timeout,
-            config.verbose,
-        ));
+        let session_manager = Arc::new(
+            SessionManager::new(config.timeout, config.init_timeout, config.verbose)
+                .with_cdb_path(config.cdb_path.clone())
+                .with_symbols_path(config.symbols_path.clone())
+                .with_extra_args(config.cdb_args.clone())
+                .with_read_only_remote(config.read_only_remote)
+                .with_idle_ttl(config.session_idle_ttl)
+                .with_max_sessions(config.max_sessions)
+                .with_command_timeouts(config.command_timeouts.clone()),
+        );
 
         Self {
             session_manager,
@@ -142,6 +409,23 @@ impl McpServer {
             .collect()
     }
 
+    /// run_windbg_cmd 的工具描述（说明命令策略和只读模式）
+    fn run_windbg_cmd_description(&self) -> String {
+        let mut description = "Execute WinDbg commands in an existing session".to_string();
+        if !self.config.policy.is_unrestricted() {
+            description.push_str(&format!(
+                ". Commands (each part of a ';'-separated line) are checked against the server's command policy (profile: {}); rejected commands fail with a policy error",
+                self.config.policy.profile
+            ));
+        }
+        if self.config.read_only_remote {
+            description.push_str(". All remote sessions are read-only: execution control, breakpoints and memory or register writes are rejected");
+        } else {
+            description.push_str(". Remote sessions opened with read_only reject execution control, breakpoints and memory or register writes");
+        }
+        description
+    }
+
     /// 列出所有可用工具
     ///
     /// # 返回
@@ -150,7 +434,10 @@ impl McpServer {
         vec![
             ToolDefinition {
                 name: "open_windbg_dump".to_string(),
-                description: "Open and analyze Windows crash dump files".to_string(),
+                description: "Open and analyze Windows crash dump files. Without cdb.exe, falls back to \
+                    offline minidump analysis (exception record, faulting thread registers, raw stack \
+                    memory and module list; no symbolication)"
+                    .to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -179,7 +466,11 @@ impl McpServer {
             },
             ToolDefinition {
                 name: "open_windbg_remote".to_string(),
-                description: "Connect to a remote debugging session".to_string(),
+                description: if self.config.read_only_remote {
+                    "Connect to a remote debugging session. This server opens every remote session read-only: execution control (g, p, t), breakpoints, .kill/.detach and memory or register writes are rejected; inspection commands are allowed".to_string()
+                } else {
+                    "Connect to a remote debugging session. Pass read_only to reject execution control (g, p, t), breakpoints, .kill/.detach and memory or register writes for this session".to_string()
+                },
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -201,6 +492,11 @@ impl McpServer {
                             "type": "boolean",
                             "description": "Whether to include thread list",
                             "default": false
+                        },
+                        "read_only": {
+                            "type": "boolean",
+                            "description": "Open the session read-only: commands that change the debuggee are rejected until the session is closed",
+                            "default": false
                         }
                     },
                     "required": ["connection_string"]
@@ -208,7 +504,7 @@ impl McpServer {
             },
             ToolDefinition {
                 name: "run_windbg_cmd".to_string(),
-                description: "Execute WinDbg commands in an existing session".to_string(),
+                description: self.run_windbg_cmd_description(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -223,11 +519,152 @@ impl McpServer {
                         "command": {
                             "type": "string",
                             "description": "WinDbg command to execute"
+                        },
+                        "include_stderr": {
+                            "type": "boolean",
+                            "description": "Append lines cdb wrote to stderr while the command ran (e.g. symbol server errors)",
+                            "default": false
+                        },
+                        "timeout_secs": {
+                            "type": "integer",
+                            "description": "Timeout for this command in seconds. Defaults to the command-class timeout (longer for !analyze, .reload /f and !heap) or the server timeout",
+                            "minimum": 1,
+                            "maximum": 3600
                         }
                     },
                     "required": ["command"]
                 }),
             },
+            ToolDefinition {
+                name: "get_stack".to_string(),
+                description: "Get parsed call stack frames for a thread (or all threads) as JSON"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "dump_path": {
+                            "type": "string",
+                            "description": "Dump file path (mutually exclusive with connection_string)"
+                        },
+                        "connection_string": {
+                            "type": "string",
+                            "description": "Remote connection string (mutually exclusive with dump_path)"
+                        },
+                        "thread": {
+                            "type": "integer",
+                            "description": "Thread index (defaults to the current thread)",
+                            "minimum": 0
+                        },
+                        "all_threads": {
+                            "type": "boolean",
+                            "description": "Whether to return stacks for all threads",
+                            "default": false
+                        },
+                        "max_frames": {
+                            "type": "integer",
+                            "description": "Maximum number of frames per thread",
+                            "minimum": 1
+                        }
+                    }
+                }),
+            },
+            ToolDefinition {
+                name: "list_modules".to_string(),
+                description: "List loaded modules with symbol status, version and company as JSON; \
+                    filter for modules without symbols or third-party modules. \
+                    Without cdb.exe, reads the minidump module list with PDB name/GUID/age"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "dump_path": {
+                            "type": "string",
+                            "description": "Dump file path (mutually exclusive with connection_string)"
+                        },
+                        "connection_string": {
+                            "type": "string",
+                            "description": "Remote connection string (mutually exclusive with dump_path)"
+                        },
+                        "module": {
+                            "type": "string",
+                            "description": "Module name pattern (wildcards allowed, uses lmvm)"
+                        },
+                        "missing_symbols": {
+                            "type": "boolean",
+                            "description": "Only return modules with export symbols or no symbols (deferred modules are not included)",
+                            "default": false
+                        },
+                        "third_party": {
+                            "type": "boolean",
+                            "description": "Only return non-Microsoft modules",
+                            "default": false
+                        }
+                    }
+                }),
+            },
+            ToolDefinition {
+                name: "list_threads".to_string(),
+                description: "List threads as JSON rows (id, suspend count, TEB, frozen/current), \
+                    joined with !runaway CPU times and the top stack frame. \
+                    Without cdb.exe, reads the minidump thread list and thread names"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "dump_path": {
+                            "type": "string",
+                            "description": "Dump file path (mutually exclusive with connection_string)"
+                        },
+                        "connection_string": {
+                            "type": "string",
+                            "description": "Remote connection string (mutually exclusive with dump_path)"
+                        },
+                        "include_cpu_times": {
+                            "type": "boolean",
+                            "description": "Whether to include user/kernel/elapsed times from !runaway",
+                            "default": true
+                        },
+                        "include_top_frame": {
+                            "type": "boolean",
+                            "description": "Whether to include the top stack frame of each thread",
+                            "default": true
+                        }
+                    }
+                }),
+            },
+            ToolDefinition {
+                name: "get_registers".to_string(),
+                description: "Get the parsed register context (x86/x64/ARM64) as JSON, \
+                    for a thread or the exception (.ecxr) / context record (.cxr) context"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "dump_path": {
+                            "type": "string",
+                            "description": "Dump file path (mutually exclusive with connection_string)"
+                        },
+                        "connection_string": {
+                            "type": "string",
+                            "description": "Remote connection string (mutually exclusive with dump_path)"
+                        },
+                        "thread": {
+                            "type": "integer",
+                            "description": "Thread index (defaults to the current thread)",
+                            "minimum": 0
+                        },
+                        "exception_context": {
+                            "type": "boolean",
+                            "description": "Switch to the stored exception context (.ecxr) before reading registers",
+                            "default": false
+                        },
+                        "context_record": {
+                            "type": "string",
+                            "description": "Context record address to switch to (.cxr <address>)"
+                        }
+                    }
+                }),
+            },
             ToolDefinition {
                 name: "close_windbg_dump".to_string(),
                 description: "Close a dump file session".to_string(),
@@ -256,9 +693,22 @@ impl McpServer {
                     "required": ["connection_string"]
                 }),
             },
+            ToolDefinition {
+                name: "list_windbg_sessions".to_string(),
+                description: "List open debugger sessions with type, target, creation and \
+                    last-used time, commands executed, wall/CPU time spent in cdb and whether a \
+                    command is currently running"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            },
             ToolDefinition {
                 name: "list_windbg_dumps".to_string(),
-                description: "List dump files in a directory".to_string(),
+                description: "List dump files in a directory with modification time, dump type, \
+                    exception code and process name; supports time, name and size filters"
+                    .to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -270,6 +720,71 @@ impl McpServer {
                             "type": "boolean",
                             "description": "Whether to recursively search subdirectories",
                             "default": false
+                        },
+                        "since": {
+                            "type": "string",
+                            "description": "Only dumps modified at or after this time (RFC 3339 UTC time or YYYY-MM-DD)"
+                        },
+                        "until": {
+                            "type": "string",
+                            "description": "Only dumps modified at or before this time (a date includes the whole day)"
+                        },
+                        "name_glob": {
+                            "type": "string",
+                            "description": "Case-insensitive file name pattern with * and ? (e.g. contoso*.dmp)"
+                        },
+                        "min_size": {
+                            "type": "integer",
+                            "description": "Minimum file size in bytes",
+                            "minimum": 0
+                        },
+                        "max_size": {
+                            "type": "integer",
+                            "description": "Maximum file size in bytes",
+                            "minimum": 0
+                        },
+                        "sort_by": {
+                            "type": "string",
+                            "enum": ["size", "mtime", "name"],
+                            "description": "Sort order: size (largest first), mtime (newest first) or name",
+                            "default": "size"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of dumps to return",
+                            "minimum": 1
+                        }
+                    }
+                }),
+            },
+            ToolDefinition {
+                name: "triage_dumps".to_string(),
+                description: "Open every dump in a directory and group them into crash buckets \
+                    by exception code, faulting module and top normalized frames"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "directory_path": {
+                            "type": "string",
+                            "description": "Directory path to triage (optional, defaults to system dump directory)"
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "Whether to recursively search subdirectories",
+                            "default": false
+                        },
+                        "frames": {
+                            "type": "integer",
+                            "description": "Number of top frames used in the crash signature",
+                            "default": 5,
+                            "minimum": 1,
+                            "maximum": 32
+                        },
+                        "max_dumps": {
+                            "type": "integer",
+                            "description": "Maximum number of dumps to analyze (newest first)",
+                            "minimum": 1
                         }
                     }
                 }),
@@ -282,37 +797,77 @@ impl McpServer {
     /// # 参数
     /// * `tool_name` - 工具名称
     /// * `arguments` - 工具参数（JSON 格式）
+    /// * `cancel` - 请求的取消令牌（触发时中断正在执行的调试器命令）
     ///
     /// # 返回
     /// 返回工具响应
     ///
     /// # 错误
-    /// 如果工具不存在或执行失败，返回错误
+    /// 如果工具不存在、请求被取消或执行失败，返回错误
     pub async fn handle_tool_call(
         &self,
         tool_name: &str,
         arguments: serde_json::Value,
+        cancel: CancellationToken,
     ) -> Result<ToolResponse, ServerError> {
         debug!("Handling tool call: {}", tool_name);
 
         match tool_name {
             "open_windbg_dump" => {
                 let params: OpenWindbgDumpParams = serde_json::from_value(arguments)?;
-                Ok(
-                    tools::handle_open_windbg_dump(Arc::clone(&self.session_manager), params)
-                        .await?,
+                Ok(tools::handle_open_windbg_dump(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    cancel,
                 )
+                .await?)
             }
             "open_windbg_remote" => {
                 let params: OpenWindbgRemoteParams = serde_json::from_value(arguments)?;
-                Ok(
-                    tools::handle_open_windbg_remote(Arc::clone(&self.session_manager), params)
-                        .await?,
+                Ok(tools::handle_open_windbg_remote(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    cancel,
                 )
+                .await?)
             }
             "run_windbg_cmd" => {
                 let params: RunWindbgCmdParams = serde_json::from_value(arguments)?;
-                Ok(tools::handle_run_windbg_cmd(Arc::clone(&self.session_manager), params).await?)
+                Ok(tools::handle_run_windbg_cmd(
+                    Arc::clone(&self.session_manager),
+                    &self.config.policy,
+                    params,
+                    cancel,
+                )
+                .await?)
+            }
+            "get_stack" => {
+                let params: GetStackParams = serde_json::from_value(arguments)?;
+                Ok(
+                    tools::handle_get_stack(Arc::clone(&self.session_manager), params, cancel)
+                        .await?,
+                )
+            }
+            "list_modules" => {
+                let params: ListModulesParams = serde_json::from_value(arguments)?;
+                Ok(
+                    tools::handle_list_modules(Arc::clone(&self.session_manager), params, cancel)
+                        .await?,
+                )
+            }
+            "list_threads" => {
+                let params: ListThreadsParams = serde_json::from_value(arguments)?;
+                Ok(
+                    tools::handle_list_threads(Arc::clone(&self.session_manager), params, cancel)
+                        .await?,
+                )
+            }
+            "get_registers" => {
+                let params: GetRegistersParams = serde_json::from_value(arguments)?;
+                Ok(
+                    tools::handle_get_registers(Arc::clone(&self.session_manager), params, cancel)
+                        .await?,
+                )
             }
             "close_windbg_dump" => {
                 let params: CloseWindbgDumpParams = serde_json::from_value(arguments)?;
@@ -328,10 +883,20 @@ impl McpServer {
                         .await?,
                 )
             }
+            "list_windbg_sessions" => {
+                Ok(tools::handle_list_windbg_sessions(Arc::clone(&self.session_manager)).await?)
+            }
             "list_windbg_dumps" => {
                 let params: ListWindbgDumpsParams = serde_json::from_value(arguments)?;
                 Ok(tools::handle_list_windbg_dumps(params).await?)
             }
+            "triage_dumps" => {
+                let params: TriageDumpsParams = serde_json::from_value(arguments)?;
+                Ok(
+                    tools::handle_triage_dumps(Arc::clone(&self.session_manager), params, cancel)
+                        .await?,
+                )
+            }
             _ => Err(ServerError::ProtocolError(format!(
                 "Unknown tool: {}",
                 tool_name
@@ -341,18 +906,95 @@ impl McpServer {
 
     /// 运行服务器（stdio 传输）
     ///
-    /// 启动服务器并监听 stdin 上的 MCP 请求。
+    /// 按配置的传输方式启动服务器
     ///
     /// # 返回
     /// 如果服务器正常关闭，返回 Ok；否则返回错误
     ///
     /// # 错误
-    /// 如果发生 I/O 错误或协议错误，返回错误
+    /// 如果发生 I/O 错误（例如监听地址被占用）或协议错误，返回错误
     pub async fn run(self) -> Result<(), ServerError> {
+        match self.config.transport {
+            Transport::Stdio => self.serve_stdio().await,
+            Transport::Http => {
+                let listener = tokio::net::TcpListener::bind(self.config.listen).await?;
+                self.serve_http(listener).await
+            }
+        }
+    }
+
+    /// 在已绑定的监听器上通过 streamable HTTP 提供 MCP 服务
+    ///
+    /// 端点为 [`HTTP_ENDPOINT`]。每个 MCP 客户端会话对应一个服务器克隆，
+    /// 调试会话由共享的会话管理器持有，客户端断开后仍然保留。
+    /// 配置了令牌时，所有请求都必须通过 Bearer 认证；监听非回环地址时必须配置令牌。
+    ///
+    /// # 参数
+    /// * `listener` - 已绑定的 TCP 监听器
+    ///
+    /// # 错误
+    /// 如果令牌配置无效、在非回环地址上监听却没有配置令牌，或接受连接时发生 I/O 错误，返回错误
+    pub async fn serve_http(self, listener: tokio::net::TcpListener) -> Result<(), ServerError> {
+        use rmcp::transport::streamable_http_server::{
+            session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+        };
+
+        let auth = self.config.resolve_auth_token()?.map(BearerAuth::new);
+        let local_addr = listener.local_addr()?;
+        match &auth {
+            Some(_) => info!("HTTP bearer token authentication enabled"),
+            None if local_addr.ip().is_loopback() => {
+                info!("HTTP authentication disabled (loopback listener)")
+            }
+            // 任何能连接的人都可以执行调试器命令（包括 `.shell`），拒绝启动
+            None => {
+                return Err(ConfigError::InvalidValue {
+                    key: "listen".to_string(),
+                    message: format!(
+                        "non-loopback address {} requires a bearer token (auth_token or auth_token_file)",
+                        local_addr
+                    ),
+                }
+                .into())
+            }
+        }
+
+        info!(
+            "Starting MCP server (http transport) on http://{}{}",
+            local_addr, HTTP_ENDPOINT
+        );
+        info!("Available tools: {}", self.list_tools().len());
+        self.session_manager.spawn_idle_reaper();
+
+        let service = StreamableHttpService::new(
+            move || Ok(self.clone()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let mut router = axum::Router::new().nest_service(HTTP_ENDPOINT, service);
+        if let Some(auth) = auth {
+            router = router.layer(axum::middleware::from_fn_with_state(
+                auth,
+                require_bearer_token,
+            ));
+        }
+
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+        info!("MCP server stopped");
+        Ok(())
+    }
+
+    /// 启动服务器并监听 stdin 上的 MCP 请求
+    async fn serve_stdio(self) -> Result<(), ServerError> {
         use rmcp::*;
 
         info!("Starting MCP server (stdio transport)");
         info!("Available tools: {}", self.list_tools().len());
+        self.session_manager.spawn_idle_reaper();
 
         // 使用 serve_server 启动服务器
         let transport = transport::stdio();
@@ -421,7 +1063,7 @@ impl rmcp::ServerHandler for McpServer {
     async fn call_tool(
         &self,
         params: rmcp::model::CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
     ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
         use rmcp::model::Content;
 
@@ -432,7 +1074,7 @@ impl rmcp::ServerHandler for McpServer {
             serde_json::json!({})
         };
 
-        // 调用工具处理器
+        // 调用工具处理器；客户端发送 notifications/cancelled 时中断正在执行的调试器命令
         let response = match tool_name.as_ref() {
             "open_windbg_dump" => {
                 let params: OpenWindbgDumpParams =
@@ -442,9 +1084,13 @@ impl rmcp::ServerHandler for McpServer {
                             None,
                         )
                     })?;
-                tools::handle_open_windbg_dump(Arc::clone(&self.session_manager), params)
-                    .await
-                    .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+                tools::handle_open_windbg_dump(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
             }
             "open_windbg_remote" => {
                 let params: OpenWindbgRemoteParams =
@@ -454,9 +1100,13 @@ impl rmcp::ServerHandler for McpServer {
                             None,
                         )
                     })?;
-                tools::handle_open_windbg_remote(Arc::clone(&self.session_manager), params)
-                    .await
-                    .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+                tools::handle_open_windbg_remote(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
             }
             "run_windbg_cmd" => {
                 let params: RunWindbgCmdParams =
@@ -466,9 +1116,75 @@ impl rmcp::ServerHandler for McpServer {
                             None,
                         )
                     })?;
-                tools::handle_run_windbg_cmd(Arc::clone(&self.session_manager), params)
-                    .await
-                    .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+                tools::handle_run_windbg_cmd(
+                    Arc::clone(&self.session_manager),
+                    &self.config.policy,
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
+            "get_stack" => {
+                let params: GetStackParams = serde_json::from_value(arguments).map_err(|e| {
+                    rmcp::ErrorData::invalid_params(
+                        format!("Failed to parse parameters: {}", e),
+                        None,
+                    )
+                })?;
+                tools::handle_get_stack(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
+            "list_modules" => {
+                let params: ListModulesParams = serde_json::from_value(arguments).map_err(|e| {
+                    rmcp::ErrorData::invalid_params(
+                        format!("Failed to parse parameters: {}", e),
+                        None,
+                    )
+                })?;
+                tools::handle_list_modules(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
+            "list_threads" => {
+                let params: ListThreadsParams = serde_json::from_value(arguments).map_err(|e| {
+                    rmcp::ErrorData::invalid_params(
+                        format!("Failed to parse parameters: {}", e),
+                        None,
+                    )
+                })?;
+                tools::handle_list_threads(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
+            "get_registers" => {
+                let params: GetRegistersParams =
+                    serde_json::from_value(arguments).map_err(|e| {
+                        rmcp::ErrorData::invalid_params(
+                            format!("Failed to parse parameters: {}", e),
+                            None,
+                        )
+                    })?;
+                tools::handle_get_registers(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
             }
             "close_windbg_dump" => {
                 let params: CloseWindbgDumpParams =
@@ -494,6 +1210,11 @@ impl rmcp::ServerHandler for McpServer {
                     .await
                     .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
             }
+            "list_windbg_sessions" => {
+                tools::handle_list_windbg_sessions(Arc::clone(&self.session_manager))
+                    .await
+                    .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
             "list_windbg_dumps" => {
                 let params: ListWindbgDumpsParams =
                     serde_json::from_value(arguments).map_err(|e| {
@@ -506,6 +1227,21 @@ impl rmcp::ServerHandler for McpServer {
                     .await
                     .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
             }
+            "triage_dumps" => {
+                let params: TriageDumpsParams = serde_json::from_value(arguments).map_err(|e| {
+                    rmcp::ErrorData::invalid_params(
+                        format!("Failed to parse parameters: {}", e),
+                        None,
+                    )
+                })?;
+                tools::handle_triage_dumps(
+                    Arc::clone(&self.session_manager),
+                    params,
+                    context.ct.clone(),
+                )
+                .await
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?
+            }
             _ => {
                 return Err(rmcp::ErrorData::invalid_request(
                     format!("Unknown tool: {}", tool_name),
@@ -515,6 +1251,7 @@ impl rmcp::ServerHandler for McpServer {
         };
 
         // 转换响应格式
+        let structured_content = response.structured_content;
         let content: Vec<Content> = response
             .content
             .into_iter()
@@ -527,7 +1264,7 @@ impl rmcp::ServerHandler for McpServer {
             content,
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content,
         })
     }
 }
@@ -543,6 +1280,168 @@ mod tests {
         assert!(!config.verbose);
         assert!(config.cdb_path.is_none());
         assert!(config.symbols_path.is_none());
+        assert_eq!(config.transport, Transport::Stdio);
+        assert_eq!(config.listen.to_string(), DEFAULT_LISTEN_ADDR);
+    }
+
+    #[test]
+    fn test_transport_from_str() {
+        assert_eq!("stdio".parse::<Transport>().unwrap(), Transport::Stdio);
+        assert_eq!("HTTP".parse::<Transport>().unwrap(), Transport::Http);
+        assert_eq!(Transport::Http.to_string(), "http");
+        let err = "sse".parse::<Transport>().unwrap_err();
+        assert!(err.contains("unknown transport 'sse'"));
+    }
+
+    #[test]
+    fn test_auth_token_precedence() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        let file_layer = || ServerConfig {
+            auth_token: Some("from-config".to_string()),
+            ..Default::default()
+        };
+
+        // 环境变量中的令牌文件覆盖配置文件中的令牌
+        let mut config = file_layer();
+        config
+            .apply_vars(vars(&[("MCP_WINDBG_AUTH_TOKEN_FILE", "token.txt")]))
+            .unwrap();
+        assert_eq!(config.auth_token, None);
+        assert_eq!(config.auth_token_file, Some(PathBuf::from("token.txt")));
+
+        // 同一层中直接指定的令牌优先于令牌文件
+        let mut config = file_layer();
+        config
+            .apply_vars(vars(&[
+                ("MCP_WINDBG_AUTH_TOKEN_FILE", "token.txt"),
+                ("MCP_WINDBG_AUTH_TOKEN", "from-env"),
+            ]))
+            .unwrap();
+        assert_eq!(config.auth_token.as_deref(), Some("from-env"));
+
+        // 未设置环境变量时保留配置文件中的令牌
+        let mut config = file_layer();
+        config.apply_vars(vars(&[])).unwrap();
+        assert_eq!(config.auth_token.as_deref(), Some("from-config"));
+    }
+
+    #[test]
+    fn test_parse_timeout_secs() {
+        assert_eq!(
+            parse_timeout_secs("MCP_WINDBG_TIMEOUT", "60").unwrap(),
+            Duration::from_secs(60)
+        );
+        assert!(parse_timeout_secs("MCP_WINDBG_TIMEOUT", "soon").is_err());
+        let err = parse_timeout_secs("MCP_WINDBG_INIT_TIMEOUT", "0").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for `MCP_WINDBG_INIT_TIMEOUT`: must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_invalid_env_values_rejected() {
+        let apply = |key: &'static str, value: &'static str| {
+            ServerConfig::default().apply_vars(|k| (k == key).then(|| value.to_string()))
+        };
+
+        for (key, value) in [
+            ("MCP_WINDBG_POLICY", "saf"),
+            ("MCP_WINDBG_READ_ONLY", "yes please"),
+            ("MCP_WINDBG_TRANSPORT", "htp"),
+            ("MCP_WINDBG_LISTEN", "localhost"),
+            ("MCP_WINDBG_TIMEOUT", "soon"),
+            ("MCP_WINDBG_VERBOSE", "on"),
+            ("MCP_WINDBG_MAX_SESSIONS", "-1"),
+        ] {
+            match apply(key, value) {
+                Err(err @ ConfigError::InvalidValue { .. }) => {
+                    assert!(err.to_string().contains(key), "{}", err)
+                }
+                other => panic!(
+                    "Expected InvalidValue for {}={}, got {:?}",
+                    key, value, other
+                ),
+            }
+        }
+
+        let mut config = ServerConfig::default();
+        config
+            .apply_vars(|key| match key {
+                "MCP_WINDBG_POLICY" => Some("SAFE".to_string()),
+                "MCP_WINDBG_READ_ONLY" => Some("0".to_string()),
+                "MCP_WINDBG_TRANSPORT" => Some("http".to_string()),
+                "MCP_WINDBG_LISTEN" => Some("127.0.0.1:9000".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config.policy.profile, crate::policy::PolicyProfile::Safe);
+        assert!(!config.read_only_remote);
+        assert_eq!(config.transport, Transport::Http);
+        assert_eq!(config.listen, "127.0.0.1:9000".parse().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_auth_token() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "from-file\n").unwrap();
+
+        let mut config = ServerConfig::default();
+        assert_eq!(config.resolve_auth_token().unwrap(), None);
+
+        config.auth_token_file = Some(path);
+        assert_eq!(
+            config.resolve_auth_token().unwrap().as_deref(),
+            Some("from-file")
+        );
+
+        // 直接配置的令牌优先，且不会出现在 Debug 输出中
+        config.auth_token = Some("inline".to_string());
+        assert_eq!(
+            config.resolve_auth_token().unwrap().as_deref(),
+            Some("inline")
+        );
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("inline"));
+        assert!(debug.contains("<redacted>"));
+
+        config.auth_token = Some("  ".to_string());
+        assert!(config.resolve_auth_token().is_err());
+
+        config.auth_token = None;
+        config.auth_token_file = Some(dir.path().join("missing"));
+        assert!(matches!(
+            config.resolve_auth_token(),
+            Err(ConfigError::ReadFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_server_config_load_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[server]\ninit_timeout_seconds = 240\n").unwrap();
+
+        let config = ServerConfig::load(Some(&path)).unwrap();
+        assert_eq!(config.init_timeout, Duration::from_secs(240));
+    }
+
+    #[test]
+    fn test_server_config_load_invalid_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[server]\nverbosity = true\n").unwrap();
+
+        let err = ServerConfig::load(Some(&path)).unwrap_err();
+        assert!(err.to_string().contains("verbosity"));
     }
 
     #[test]
@@ -551,4 +1450,20 @@ mod tests {
         let _server = McpServer::new(config);
         // 服务器创建成功
     }
+
+    #[test]
+    fn test_server_new_applies_cdb_settings() {
+        let config = ServerConfig {
+            cdb_path: Some(PathBuf::from("custom_cdb.exe")),
+            symbols_path: Some("C:\\Symbols".to_string()),
+            cdb_args: vec!["-lines".to_string()],
+            ..Default::default()
+        };
+        let server = McpServer::new(config);
+
+        let options = server.session_manager().spawn_options();
+        assert_eq!(options.cdb_path, Some(PathBuf::from("custom_cdb.exe")));
+        assert_eq!(options.symbols_path.as_deref(), Some("C:\\Symbols"));
+        assert_eq!(options.extra_args, vec!["-lines".to_string()]);
+    }
 }
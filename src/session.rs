@@ -1,33 +1,294 @@
 //! 会话管理模块
 //!
-//! 提供 CDB 会话的生命周期管理、连接池和会话复用功能。
+//! 提供调试器会话的生命周期管理、连接池和会话复用功能。
 
-use crate::cdb::CdbSession;
-use crate::error::SessionError;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::backend::{
+    BackendLauncher, CdbLauncher, DebugTarget, DebuggerBackend, SharedBackend, SpawnOptions,
+};
+use crate::error::{CdbError, SessionError};
+use crate::timeouts::CommandTimeouts;
+use async_trait::async_trait;
+use serde::{Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{Mutex, RwLock};
-use tracing::{debug, info};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// 调试器进程退出后重新启动会话的最大尝试次数
+pub const MAX_RESTART_ATTEMPTS: u32 = 3;
+
+/// 重新启动失败后的退避间隔（按尝试次数递增）
+const RESTART_BACKOFF: Duration = Duration::from_millis(200);
+
+/// [`RESTART_WINDOW`] 内允许的最大重新启动次数
+///
+/// 调试器进程反复退出（例如每条命令都会使 cdb.exe 崩溃的转储）时，超过该次数后会话被关闭，
+/// 不再自动重新启动，直到调用者显式关闭该会话。
+pub const MAX_RESTARTS_PER_WINDOW: u32 = 5;
+
+/// 统计重新启动次数的滑动窗口
+pub const RESTART_WINDOW: Duration = Duration::from_secs(600);
+
+/// 会话类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    /// 崩溃转储会话
+    Dump,
+    /// 远程调试会话
+    Remote,
+}
+
+impl SessionKind {
+    /// 类型名称（`dump` 或 `remote`）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionKind::Dump => "dump",
+            SessionKind::Remote => "remote",
+        }
+    }
+}
+
+/// 会话状态快照（由 [`SessionManager::list_sessions`] 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    /// 会话 ID
+    pub id: String,
+    /// 会话类型
+    pub kind: SessionKind,
+    /// 转储文件路径或远程连接字符串
+    pub target: String,
+    /// 调试器进程 ID
+    pub process_id: Option<u32>,
+    /// 创建时间
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub created_at: SystemTime,
+    /// 最后使用时间（最后一次获取会话或执行命令）
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub last_used_at: SystemTime,
+    /// 已执行的命令数
+    pub commands_executed: u64,
+    /// 等待调试器执行命令的累计时间
+    #[serde(rename = "wall_seconds", serialize_with = "serialize_seconds")]
+    pub wall_time: Duration,
+    /// 调试器进程累计消耗的 CPU 时间（截至最后一条命令）
+    #[serde(rename = "cpu_seconds", serialize_with = "serialize_optional_seconds")]
+    pub cpu_time: Option<Duration>,
+    /// 当前是否有命令正在执行（会话被锁定）
+    pub locked: bool,
+    /// 是否为只读会话
+    pub read_only: bool,
+    /// 调试器进程退出后会话被重新启动的次数
+    pub restarts: u32,
+}
+
+fn serialize_timestamp<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&crate::utils::format_timestamp(*time))
+}
+
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn serialize_optional_seconds<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_seconds(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 会话的使用统计
+#[derive(Debug, Clone, Copy)]
+struct Activity {
+    last_used_at: SystemTime,
+    commands_executed: u64,
+    wall_time: Duration,
+    cpu_time: Option<Duration>,
+    /// 调试器进程已退出（会话需要重新启动）
+    dead: bool,
+}
+
+/// 会话元数据
+#[derive(Debug)]
+struct SessionStats {
+    created_at: SystemTime,
+    process_id: Option<u32>,
+    restarts: u32,
+    /// [`RESTART_WINDOW`] 内各次重新启动的时间
+    recent_restarts: Vec<Instant>,
+    activity: std::sync::Mutex<Activity>,
+}
+
+impl SessionStats {
+    fn new(
+        process_id: Option<u32>,
+        cpu_time: Option<Duration>,
+        restarts: u32,
+        recent_restarts: Vec<Instant>,
+    ) -> Self {
+        let now = SystemTime::now();
+        Self {
+            created_at: now,
+            process_id,
+            restarts,
+            recent_restarts,
+            activity: std::sync::Mutex::new(Activity {
+                last_used_at: now,
+                commands_executed: 0,
+                wall_time: Duration::ZERO,
+                cpu_time,
+                dead: false,
+            }),
+        }
+    }
+
+    fn activity(&self) -> Activity {
+        *self.activity.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Activity)) {
+        f(&mut self.activity.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+}
+
+/// 记录命令数、耗时和进程健康状态的后端包装
+#[derive(Debug)]
+struct MeteredBackend {
+    inner: Box<dyn DebuggerBackend>,
+    stats: Arc<SessionStats>,
+    /// 重新启动后尚未交给调用者的提示
+    restart_notice: Option<String>,
+}
+
+impl MeteredBackend {
+    /// 记录一次命令执行的统计信息，并检查调试器进程是否已退出
+    fn record(
+        &mut self,
+        started: Instant,
+        result: Result<Vec<String>, CdbError>,
+    ) -> Result<Vec<String>, CdbError> {
+        let elapsed = started.elapsed();
+        let cpu_time = self.inner.cpu_time();
+        let dead = matches!(result, Err(CdbError::ProcessTerminated))
+            || (result.is_err() && !self.inner.is_alive());
+        if dead {
+            warn!(
+                "Debugger process for session {} exited; the session will be restarted on next use",
+                self.inner.session_id()
+            );
+        }
+
+        self.stats.update(|activity| {
+            activity.last_used_at = SystemTime::now();
+            activity.commands_executed += 1;
+            activity.wall_time += elapsed;
+            activity.cpu_time = cpu_time.or(activity.cpu_time);
+            activity.dead |= dead;
+        });
+        result
+    }
+}
+
+#[async_trait]
+impl DebuggerBackend for MeteredBackend {
+    fn session_id(&self) -> &str {
+        self.inner.session_id()
+    }
+
+    async fn send_command(&mut self, command: &str) -> Result<Vec<String>, CdbError> {
+        let started = Instant::now();
+        let result = self.inner.send_command(command).await;
+        self.record(started, result)
+    }
+
+    async fn send_command_cancellable(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>, CdbError> {
+        let started = Instant::now();
+        let result = self
+            .inner
+            .send_command_cancellable(command, timeout, cancel)
+            .await;
+        self.record(started, result)
+    }
+
+    fn process_id(&self) -> Option<u32> {
+        self.inner.process_id()
+    }
+
+    fn cpu_time(&self) -> Option<Duration> {
+        self.inner.cpu_time()
+    }
+
+    fn is_alive(&mut self) -> bool {
+        !self.stats.activity().dead && self.inner.is_alive()
+    }
+
+    fn stderr_mark(&self) -> u64 {
+        self.inner.stderr_mark()
+    }
+
+    fn stderr_since(&self, mark: u64) -> Vec<String> {
+        self.inner.stderr_since(mark)
+    }
+
+    async fn sync_stderr(&mut self) {
+        self.inner.sync_stderr().await
+    }
+
+    fn take_restart_notice(&mut self) -> Option<String> {
+        self.restart_notice.take()
+    }
+
+    async fn shutdown(self: Box<Self>) -> Result<(), CdbError> {
+        self.inner.shutdown().await
+    }
+}
+
+/// 会话表中的一项
+struct SessionEntry {
+    backend: SharedBackend,
+    target: DebugTarget,
+    stats: Arc<SessionStats>,
+}
 
 /// 会话管理器
 ///
-/// 管理多个 CDB 会话，支持会话复用和并发访问。
+/// 管理多个调试器会话，支持会话复用和并发访问。
 pub struct SessionManager {
-    /// 会话存储（会话 ID -> 会话实例）
-    sessions: Arc<RwLock<HashMap<String, Arc<Mutex<CdbSession>>>>>,
-    /// 默认命令超时时间
-    default_timeout: Duration,
-    /// 默认初始化超时时间
-    default_init_timeout: Duration,
-    /// 是否启用详细日志
-    verbose: bool,
+    /// 会话存储（会话 ID -> 会话实例和元数据）
+    sessions: Arc<RwLock<HashMap<String, SessionEntry>>>,
+    /// 调试器后端启动器
+    launcher: Arc<dyn BackendLauncher>,
+    /// 应用于每个新会话的启动选项（CDB 路径、符号路径、额外参数和超时设置）
+    spawn_options: SpawnOptions,
+    /// 是否所有远程会话都是只读的
+    read_only_remote: bool,
+    /// 以只读模式打开的会话 ID
+    read_only_sessions: Arc<RwLock<HashSet<String>>>,
+    /// 重新启动次数超过上限、不再自动重新启动的会话 ID（关闭会话后清除）
+    abandoned_sessions: Arc<RwLock<HashSet<String>>>,
+    /// 空闲会话的存活时间（超过后由后台任务关闭）
+    idle_ttl: Option<Duration>,
+    /// 最大会话数（超过时关闭最久未使用的会话）
+    max_sessions: Option<usize>,
 }
 
 impl SessionManager {
     /// 创建新的会话管理器
     ///
+    /// 默认使用 [`CdbLauncher`] 启动 cdb.exe 后端。
+    ///
     /// # 参数
     /// * `default_timeout` - 默认命令执行超时时间
     /// * `default_init_timeout` - 默认初始化超时时间
@@ -36,15 +297,134 @@ impl SessionManager {
     /// # 返回
     /// 返回新创建的会话管理器
     pub fn new(default_timeout: Duration, default_init_timeout: Duration, verbose: bool) -> Self {
-        info!("Creating session manager, timeout: {:?}, init_timeout: {:?}", default_timeout, default_init_timeout);
+        info!(
+            "Creating session manager, timeout: {:?}, init_timeout: {:?}",
+            default_timeout, default_init_timeout
+        );
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            default_timeout,
-            default_init_timeout,
-            verbose,
+            launcher: Arc::new(CdbLauncher),
+            spawn_options: SpawnOptions {
+                cdb_path: None,
+                symbols_path: None,
+                extra_args: Vec::new(),
+                timeout: default_timeout,
+                init_timeout: default_init_timeout,
+                verbose,
+                command_timeouts: CommandTimeouts::default(),
+            },
+            read_only_remote: false,
+            read_only_sessions: Arc::new(RwLock::new(HashSet::new())),
+            abandoned_sessions: Arc::new(RwLock::new(HashSet::new())),
+            idle_ttl: None,
+            max_sessions: None,
         }
     }
 
+    /// 设置 CDB 可执行文件路径
+    ///
+    /// # 参数
+    /// * `cdb_path` - 自定义 CDB 路径；为 None 时在默认位置查找
+    pub fn with_cdb_path(mut self, cdb_path: Option<PathBuf>) -> Self {
+        self.spawn_options.cdb_path = cdb_path;
+        self
+    }
+
+    /// 设置符号路径
+    ///
+    /// # 参数
+    /// * `symbols_path` - 传递给 CDB 的 `_NT_SYMBOL_PATH`
+    pub fn with_symbols_path(mut self, symbols_path: Option<String>) -> Self {
+        self.spawn_options.symbols_path = symbols_path;
+        self
+    }
+
+    /// 设置每个会话默认附加的 CDB 命令行参数
+    ///
+    /// # 参数
+    /// * `extra_args` - 额外参数（放在 `-z`/`-remote` 之前）
+    pub fn with_extra_args(mut self, extra_args: Vec<String>) -> Self {
+        self.spawn_options.extra_args = extra_args;
+        self
+    }
+
+    /// 设置命令类别超时表
+    ///
+    /// # 参数
+    /// * `command_timeouts` - 按命令模式选择的超时（覆盖内置表）
+    pub fn with_command_timeouts(mut self, command_timeouts: CommandTimeouts) -> Self {
+        self.spawn_options.command_timeouts = command_timeouts;
+        self
+    }
+
+    /// 设置空闲会话的存活时间
+    ///
+    /// # 参数
+    /// * `idle_ttl` - 空闲超过该时间的会话会被 [`spawn_idle_reaper`](Self::spawn_idle_reaper) 关闭；为 None 时不回收
+    pub fn with_idle_ttl(mut self, idle_ttl: Option<Duration>) -> Self {
+        self.idle_ttl = idle_ttl;
+        self
+    }
+
+    /// 设置最大会话数
+    ///
+    /// # 参数
+    /// * `max_sessions` - 创建新会话后超过该数量时，关闭最久未使用的空闲会话；为 None 时不限制
+    pub fn with_max_sessions(mut self, max_sessions: Option<usize>) -> Self {
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    /// 设置是否所有远程会话都是只读的
+    ///
+    /// # 参数
+    /// * `read_only` - 为 true 时，远程会话拒绝执行控制和修改内存的命令
+    pub fn with_read_only_remote(mut self, read_only: bool) -> Self {
+        self.read_only_remote = read_only;
+        self
+    }
+
+    /// 是否所有远程会话都是只读的
+    pub fn read_only_remote(&self) -> bool {
+        self.read_only_remote
+    }
+
+    /// 将远程会话标记为只读
+    ///
+    /// 只读标记只能收紧：会话关闭之前不能取消。
+    ///
+    /// # 参数
+    /// * `session_id` - 会话 ID（远程连接字符串）
+    pub async fn mark_read_only(&self, session_id: &str) {
+        info!("Session marked read-only: {}", session_id);
+        self.read_only_sessions
+            .write()
+            .await
+            .insert(session_id.to_string());
+    }
+
+    /// 远程会话是否为只读
+    ///
+    /// # 参数
+    /// * `session_id` - 会话 ID（远程连接字符串）
+    pub async fn is_read_only(&self, session_id: &str) -> bool {
+        self.read_only_remote || self.read_only_sessions.read().await.contains(session_id)
+    }
+
+    /// 获取应用于新会话的启动选项
+    pub fn spawn_options(&self) -> &SpawnOptions {
+        &self.spawn_options
+    }
+
+    /// 替换调试器后端启动器
+    ///
+    /// # 参数
+    /// * `launcher` - 用于创建新会话的启动器
+    pub fn with_launcher(mut self, launcher: Arc<dyn BackendLauncher>) -> Self {
+        self.launcher = launcher;
+        self
+    }
+
     /// 获取活跃会话数量
     ///
     /// # 返回
@@ -54,14 +434,54 @@ impl SessionManager {
         sessions.len()
     }
 
+    /// 检查指定会话是否存在
+    ///
+    /// # 参数
+    /// * `session_id` - 会话 ID
+    pub async fn has_session(&self, session_id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(session_id)
+    }
+
+    /// 列出所有会话的状态
+    ///
+    /// # 返回
+    /// 按创建时间排序的会话快照
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        let mut infos = Vec::with_capacity(sessions.len());
+        for (id, entry) in sessions.iter() {
+            let (kind, target) = match &entry.target {
+                DebugTarget::Dump(path) => (SessionKind::Dump, path.display().to_string()),
+                DebugTarget::Remote(connection) => (SessionKind::Remote, connection.clone()),
+            };
+            let read_only = kind == SessionKind::Remote && self.is_read_only(id).await;
+            let activity = entry.stats.activity();
+            infos.push(SessionInfo {
+                id: id.clone(),
+                kind,
+                target,
+                process_id: entry.stats.process_id,
+                created_at: entry.stats.created_at,
+                last_used_at: activity.last_used_at,
+                commands_executed: activity.commands_executed,
+                wall_time: activity.wall_time,
+                cpu_time: activity.cpu_time,
+                locked: entry.backend.try_lock().is_err(),
+                read_only,
+                restarts: entry.stats.restarts,
+            });
+        }
+        infos.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        infos
+    }
+
     /// 获取或创建崩溃转储会话
     ///
     /// 如果会话已存在，返回现有会话；否则创建新会话。
     ///
     /// # 参数
     /// * `dump_path` - 转储文件路径
-    /// * `cdb_path` - 可选的自定义 CDB 路径
-    /// * `symbols_path` - 可选的符号路径
     ///
     /// # 返回
     /// 返回会话的 Arc<Mutex> 引用
@@ -71,113 +491,358 @@ impl SessionManager {
     pub async fn get_or_create_dump_session(
         &self,
         dump_path: &Path,
-        cdb_path: Option<&Path>,
-        symbols_path: Option<&str>,
-    ) -> Result<Arc<Mutex<CdbSession>>, SessionError> {
+    ) -> Result<SharedBackend, SessionError> {
         // 检查转储文件是否存在
         if !dump_path.exists() {
             return Err(SessionError::DumpFileNotFound(dump_path.to_path_buf()));
         }
 
-        // 生成会话 ID（使用绝对路径）
-        let session_id = dump_path
-            .canonicalize()
-            .unwrap_or_else(|_| dump_path.to_path_buf())
-            .to_string_lossy()
-            .to_string();
+        let target = DebugTarget::Dump(dump_path.to_path_buf());
+        self.get_or_create_session(&target).await
+    }
 
-        debug!("Requesting dump session: {}", session_id);
+    /// 获取或创建远程调试会话
+    ///
+    /// 如果会话已存在，返回现有会话；否则创建新会话。
+    ///
+    /// # 参数
+    /// * `connection_string` - 远程连接字符串
+    ///
+    /// # 返回
+    /// 返回会话的 Arc<Mutex> 引用
+    ///
+    /// # 错误
+    /// 如果会话创建失败，返回错误
+    pub async fn get_or_create_remote_session(
+        &self,
+        connection_string: &str,
+    ) -> Result<SharedBackend, SessionError> {
+        let target = DebugTarget::Remote(connection_string.to_string());
+        self.get_or_create_session(&target).await
+    }
 
-        // 检查会话是否已存在
-        {
+    /// 获取或创建指定目标的会话（内部辅助函数）
+    async fn get_or_create_session(
+        &self,
+        target: &DebugTarget,
+    ) -> Result<SharedBackend, SessionError> {
+        let session_id = target.session_id();
+
+        debug!("Requesting session: {}", session_id);
+
+        if self.abandoned_sessions.read().await.contains(&session_id) {
+            return Err(Self::restart_limit_error(&session_id));
+        }
+
+        // 检查会话是否已存在且调试器进程仍在运行
+        let dead = {
             let sessions = self.sessions.read().await;
-            if let Some(session) = sessions.get(&session_id) {
-                info!("Reusing existing dump session: {}", session_id);
-                return Ok(Arc::clone(session));
+            match sessions.get(&session_id) {
+                Some(entry) if Self::is_healthy(entry) => {
+                    info!("Reusing existing session: {}", session_id);
+                    entry
+                        .stats
+                        .update(|activity| activity.last_used_at = SystemTime::now());
+                    return Ok(Arc::clone(&entry.backend));
+                }
+                Some(entry) => Some(Arc::clone(&entry.stats)),
+                None => None,
             }
-        }
+        };
 
-        // 创建新会话
-        info!("Creating new dump session: {}", session_id);
-        let session = CdbSession::new_dump(
-            dump_path,
-            cdb_path,
-            symbols_path,
-            self.default_timeout,
-            self.default_init_timeout,
-            self.verbose,
-        )
-        .await?;
+        let (backend, restarts, recent_restarts) = match dead {
+            Some(stats) => {
+                self.discard_dead_session(&session_id, &stats).await;
 
-        let session_arc = Arc::new(Mutex::new(session));
+                let now = Instant::now();
+                let mut recent_restarts: Vec<Instant> = stats
+                    .recent_restarts
+                    .iter()
+                    .copied()
+                    .filter(|time| now.duration_since(*time) < RESTART_WINDOW)
+                    .collect();
+                if recent_restarts.len() >= MAX_RESTARTS_PER_WINDOW as usize {
+                    warn!(
+                        "Debugger for session {} exited {} times within {:?}, not restarting it again",
+                        session_id,
+                        recent_restarts.len(),
+                        RESTART_WINDOW
+                    );
+                    self.abandoned_sessions
+                        .write()
+                        .await
+                        .insert(session_id.clone());
+                    return Err(Self::restart_limit_error(&session_id));
+                }
+                recent_restarts.push(now);
+
+                let backend = self.restart_session(target, &session_id).await?;
+                (backend, stats.restarts + 1, recent_restarts)
+            }
+            None => {
+                info!("Creating new session: {}", session_id);
+                let backend = self.launcher.spawn(target, &self.spawn_options).await?;
+                (backend, 0, Vec::new())
+            }
+        };
+
+        let stats = Arc::new(SessionStats::new(
+            backend.process_id(),
+            backend.cpu_time(),
+            restarts,
+            recent_restarts,
+        ));
+        let restart_notice = (restarts > 0).then(|| {
+            format!(
+                "> Note: the debugger for session `{}` exited unexpectedly and was restarted (restart #{}). \
+                 Earlier debugger state such as the `.frame`/`~s` context, `.ecxr` and loaded extensions was lost.",
+                session_id, restarts
+            )
+        });
+        let backend: Box<dyn DebuggerBackend> = Box::new(MeteredBackend {
+            inner: backend,
+            stats: Arc::clone(&stats),
+            restart_notice,
+        });
+        let session_arc = Arc::new(Mutex::new(backend));
 
         // 存储会话
         {
             let mut sessions = self.sessions.write().await;
-            sessions.insert(session_id.clone(), Arc::clone(&session_arc));
+            sessions.insert(
+                session_id.clone(),
+                SessionEntry {
+                    backend: Arc::clone(&session_arc),
+                    target: target.clone(),
+                    stats,
+                },
+            );
         }
 
-        info!("Dump session created and stored: {}", session_id);
+        info!("Session created and stored: {}", session_id);
+
+        self.enforce_max_sessions(&session_id).await;
 
         Ok(session_arc)
     }
 
-    /// 获取或创建远程调试会话
+    /// 会话的调试器进程是否仍在运行
     ///
-    /// 如果会话已存在，返回现有会话；否则创建新会话。
+    /// 正在执行命令的会话视为健康，由执行命令的一方发现进程退出。
+    fn is_healthy(entry: &SessionEntry) -> bool {
+        if entry.stats.activity().dead {
+            return false;
+        }
+        match entry.backend.try_lock() {
+            Ok(mut backend) => backend.is_alive(),
+            Err(_) => true,
+        }
+    }
+
+    /// 从会话表中移除调试器进程已退出的会话
     ///
     /// # 参数
-    /// * `connection_string` - 远程连接字符串
-    /// * `cdb_path` - 可选的自定义 CDB 路径
-    /// * `symbols_path` - 可选的符号路径
-    ///
-    /// # 返回
-    /// 返回会话的 Arc<Mutex> 引用
+    /// * `session_id` - 会话 ID
+    /// * `stats` - 检查时看到的会话元数据（会话已被其他调用者替换时不移除）
+    async fn discard_dead_session(&self, session_id: &str, stats: &Arc<SessionStats>) {
+        let entry = {
+            let mut sessions = self.sessions.write().await;
+            match sessions.get(session_id) {
+                Some(entry) if Arc::ptr_eq(&entry.stats, stats) => sessions.remove(session_id),
+                _ => None,
+            }
+        };
+        let Some(entry) = entry else {
+            return;
+        };
+
+        warn!(
+            "Debugger process for session {} is no longer running, discarding session",
+            session_id
+        );
+        entry.stats.update(|activity| activity.dead = true);
+        if let Ok(session_mutex) = Arc::try_unwrap(entry.backend) {
+            if let Err(e) = session_mutex.into_inner().shutdown().await {
+                debug!("Shutdown of dead session {} failed: {}", session_id, e);
+            }
+        }
+    }
+
+    /// 重新启动次数超过上限的错误
+    fn restart_limit_error(session_id: &str) -> SessionError {
+        SessionError::RestartLimitReached {
+            session_id: session_id.to_string(),
+            restarts: MAX_RESTARTS_PER_WINDOW,
+            window: RESTART_WINDOW,
+        }
+    }
+
+    /// 重新启动调试器进程已退出的会话，最多尝试 [`MAX_RESTART_ATTEMPTS`] 次
     ///
     /// # 错误
-    /// 如果会话创建失败，返回错误
-    pub async fn get_or_create_remote_session(
+    /// 如果所有尝试都失败，返回 [`SessionError::RestartFailed`]
+    async fn restart_session(
         &self,
-        connection_string: &str,
-        cdb_path: Option<&Path>,
-        symbols_path: Option<&str>,
-    ) -> Result<Arc<Mutex<CdbSession>>, SessionError> {
-        let session_id = connection_string.to_string();
+        target: &DebugTarget,
+        session_id: &str,
+    ) -> Result<Box<dyn DebuggerBackend>, SessionError> {
+        let mut attempt = 1;
+        loop {
+            info!(
+                "Restarting session {} (attempt {}/{})",
+                session_id, attempt, MAX_RESTART_ATTEMPTS
+            );
+            match self.launcher.spawn(target, &self.spawn_options).await {
+                Ok(backend) => return Ok(backend),
+                Err(e) if attempt >= MAX_RESTART_ATTEMPTS => {
+                    warn!("Giving up restarting session {}: {}", session_id, e);
+                    return Err(SessionError::RestartFailed {
+                        session_id: session_id.to_string(),
+                        attempts: attempt,
+                        source: e,
+                    });
+                }
+                Err(e) => {
+                    warn!(
+                        "Restart attempt {} for session {} failed: {}",
+                        attempt, session_id, e
+                    );
+                    tokio::time::sleep(RESTART_BACKOFF * attempt).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
 
-        debug!("Requesting remote session: {}", session_id);
+    /// 启动后台任务，定期关闭空闲时间超过 TTL 的会话
+    ///
+    /// 任务只持有会话管理器的弱引用，管理器释放后自动退出。
+    ///
+    /// # 返回
+    /// 未配置 TTL 时返回 None
+    pub fn spawn_idle_reaper(self: &Arc<Self>) -> Option<JoinHandle<()>> {
+        let idle_ttl = self.idle_ttl?;
+        let period = (idle_ttl / 4).clamp(Duration::from_millis(100), Duration::from_secs(60));
+        let manager = Arc::downgrade(self);
+        info!(
+            "Starting idle session reaper (ttl: {:?}, period: {:?})",
+            idle_ttl, period
+        );
 
-        // 检查会话是否已存在
-        {
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.evict_idle_sessions().await;
+            }
+        }))
+    }
+
+    /// 关闭空闲时间超过 TTL 的会话
+    ///
+    /// 正在执行命令或被其他调用者持有的会话不会被关闭。
+    /// 被关闭的会话在下次使用时自动重新打开（只读标记保留）。
+    ///
+    /// # 返回
+    /// 被关闭的会话 ID
+    pub async fn evict_idle_sessions(&self) -> Vec<String> {
+        let Some(idle_ttl) = self.idle_ttl else {
+            return Vec::new();
+        };
+
+        let now = SystemTime::now();
+        let idle: Vec<String> = {
             let sessions = self.sessions.read().await;
-            if let Some(session) = sessions.get(&session_id) {
-                info!("Reusing existing remote session: {}", session_id);
-                return Ok(Arc::clone(session));
+            sessions
+                .iter()
+                .filter(|(_, entry)| {
+                    let last_used_at = entry.stats.activity().last_used_at;
+                    now.duration_since(last_used_at).unwrap_or_default() >= idle_ttl
+                })
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+
+        let mut evicted = Vec::new();
+        for session_id in idle {
+            let reason = format!("idle for more than {:?}", idle_ttl);
+            if self.evict_session(&session_id, &reason).await {
+                evicted.push(session_id);
             }
         }
+        evicted
+    }
 
-        // 创建新会话
-        info!("Creating new remote session: {}", session_id);
-        let session = CdbSession::new_remote(
-            connection_string,
-            cdb_path,
-            symbols_path,
-            self.default_timeout,
-            self.default_init_timeout,
-            self.verbose,
-        )
-        .await?;
+    /// 会话数超过上限时，按最久未使用顺序关闭会话
+    ///
+    /// # 参数
+    /// * `keep` - 不参与回收的会话 ID（刚创建的会话）
+    async fn enforce_max_sessions(&self, keep: &str) {
+        let Some(max_sessions) = self.max_sessions else {
+            return;
+        };
 
-        let session_arc = Arc::new(Mutex::new(session));
+        loop {
+            let victim = {
+                let sessions = self.sessions.read().await;
+                if sessions.len() <= max_sessions {
+                    return;
+                }
+                sessions
+                    .iter()
+                    .filter(|(id, entry)| id.as_str() != keep && Self::is_evictable(entry))
+                    .min_by_key(|(_, entry)| entry.stats.activity().last_used_at)
+                    .map(|(id, _)| id.clone())
+            };
 
-        // 存储会话
-        {
-            let mut sessions = self.sessions.write().await;
-            sessions.insert(session_id.clone(), Arc::clone(&session_arc));
+            let Some(victim) = victim else {
+                warn!(
+                    "Session limit ({}) exceeded but every other session is in use",
+                    max_sessions
+                );
+                return;
+            };
+            let reason = format!(
+                "max_sessions ({}) exceeded, least recently used",
+                max_sessions
+            );
+            if !self.evict_session(&victim, &reason).await {
+                return;
+            }
         }
+    }
+
+    /// 会话是否可以回收（没有命令在执行，也没有其他调用者持有）
+    fn is_evictable(entry: &SessionEntry) -> bool {
+        Arc::strong_count(&entry.backend) == 1 && entry.backend.try_lock().is_ok()
+    }
 
-        info!("Remote session created and stored: {}", session_id);
+    /// 回收会话（与 [`close_session`](Self::close_session) 不同，保留只读标记）
+    ///
+    /// # 返回
+    /// 会话被关闭时返回 true；会话不存在或正在使用时返回 false
+    async fn evict_session(&self, session_id: &str, reason: &str) -> bool {
+        let entry = {
+            let mut sessions = self.sessions.write().await;
+            match sessions.get(session_id) {
+                Some(entry) if Self::is_evictable(entry) => sessions.remove(session_id),
+                _ => None,
+            }
+        };
+        let Some(entry) = entry else {
+            return false;
+        };
 
-        Ok(session_arc)
+        info!("Evicting session {}: {}", session_id, reason);
+        if let Ok(session_mutex) = Arc::try_unwrap(entry.backend) {
+            if let Err(e) = session_mutex.into_inner().shutdown().await {
+                warn!("Failed to shut down evicted session {}: {}", session_id, e);
+            }
+        }
+        true
     }
 
     /// 关闭指定会话
@@ -188,17 +853,27 @@ impl SessionManager {
     /// # 返回
     /// 如果成功关闭，返回 Ok；如果会话不存在，返回错误
     ///
+    /// 关闭因重新启动次数超过上限而被放弃的会话时，清除该标记，之后可以重新打开。
+    ///
     /// # 错误
     /// 如果会话不存在或关闭失败，返回错误
     pub async fn close_session(&self, session_id: &str) -> Result<(), SessionError> {
         info!("Closing session: {}", session_id);
 
         // 从存储中移除会话
-        let session_arc = {
-            let mut sessions = self.sessions.write().await;
-            sessions
-                .remove(session_id)
-                .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))?
+        let entry = self.sessions.write().await.remove(session_id);
+        let Some(SessionEntry {
+            backend: session_arc,
+            target,
+            stats,
+        }) = entry
+        else {
+            if self.abandoned_sessions.write().await.remove(session_id) {
+                self.read_only_sessions.write().await.remove(session_id);
+                info!("Abandoned session closed: {}", session_id);
+                return Ok(());
+            }
+            return Err(SessionError::SessionNotFound(session_id.to_string()));
         };
 
         // 尝试获取会话的独占访问权
@@ -206,14 +881,22 @@ impl SessionManager {
         match Arc::try_unwrap(session_arc) {
             Ok(session_mutex) => {
                 // 成功获取独占访问权，关闭会话
-                let session = session_mutex.into_inner();
-                session.shutdown().await?;
+                let backend = session_mutex.into_inner();
+                self.read_only_sessions.write().await.remove(session_id);
+                backend.shutdown().await?;
                 info!("Session closed: {}", session_id);
             }
             Err(arc) => {
                 // 还有其他引用，放回去并记录警告
                 let mut sessions = self.sessions.write().await;
-                sessions.insert(session_id.to_string(), arc);
+                sessions.insert(
+                    session_id.to_string(),
+                    SessionEntry {
+                        backend: arc,
+                        target,
+                        stats,
+                    },
+                );
                 return Err(SessionError::InvalidSessionId(format!(
                     "Session still in use: {}",
                     session_id
@@ -260,6 +943,424 @@ impl SessionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::DebuggerBackend;
+    use crate::error::CdbError;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 按命令回显的脚本化后端
+    #[derive(Debug)]
+    struct ScriptedBackend {
+        session_id: String,
+    }
+
+    #[async_trait]
+    impl DebuggerBackend for ScriptedBackend {
+        fn session_id(&self) -> &str {
+            &self.session_id
+        }
+
+        async fn send_command(&mut self, command: &str) -> Result<Vec<String>, CdbError> {
+            // "crash" 模拟调试器进程在命令执行期间退出
+            if command == "crash" {
+                return Err(CdbError::ProcessTerminated);
+            }
+            Ok(vec![format!("{}: {}", self.session_id, command)])
+        }
+
+        async fn shutdown(self: Box<Self>) -> Result<(), CdbError> {
+            Ok(())
+        }
+    }
+
+    /// 记录启动次数的脚本化启动器
+    #[derive(Default)]
+    struct ScriptedLauncher {
+        spawned: AtomicUsize,
+        /// 接下来需要模拟失败的启动次数
+        failures: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BackendLauncher for ScriptedLauncher {
+        async fn spawn(
+            &self,
+            target: &DebugTarget,
+            _options: &SpawnOptions,
+        ) -> Result<Box<dyn DebuggerBackend>, CdbError> {
+            self.spawned.fetch_add(1, Ordering::SeqCst);
+            let fail = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if fail {
+                return Err(CdbError::ProcessStartFailed("scripted failure".to_string()));
+            }
+            Ok(Box::new(ScriptedBackend {
+                session_id: target.session_id(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_sessions() {
+        let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false)
+            .with_launcher(Arc::new(ScriptedLauncher::default()));
+        manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert!(!manager.is_read_only("tcp:Port=5005").await);
+
+        manager.mark_read_only("tcp:Port=5005").await;
+        assert!(manager.is_read_only("tcp:Port=5005").await);
+        assert!(!manager.is_read_only("tcp:Port=5006").await);
+
+        // 关闭会话后清除只读标记
+        manager.close_session("tcp:Port=5005").await.unwrap();
+        assert!(!manager.is_read_only("tcp:Port=5005").await);
+
+        let manager = manager.with_read_only_remote(true);
+        assert!(manager.read_only_remote());
+        assert!(manager.is_read_only("tcp:Port=5006").await);
+    }
+
+    #[tokio::test]
+    async fn test_custom_launcher_session_reuse() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false)
+            .with_launcher(launcher.clone());
+
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        let output = session.lock().await.send_command("kb").await.unwrap();
+        assert_eq!(output, vec!["tcp:Port=5005: kb".to_string()]);
+        drop(session);
+
+        // 第二次请求复用已有会话
+        let _ = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.active_session_count().await, 1);
+        assert!(manager.has_session("tcp:Port=5005").await);
+
+        manager.close_session("tcp:Port=5005").await.unwrap();
+        assert_eq!(manager.active_session_count().await, 0);
+        assert!(!manager.has_session("tcp:Port=5005").await);
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions() {
+        let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false)
+            .with_launcher(Arc::new(ScriptedLauncher::default()));
+        assert!(manager.list_sessions().await.is_empty());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let dump = dir.path().join("app.dmp");
+        std::fs::write(&dump, "").unwrap();
+        let session = manager.get_or_create_dump_session(&dump).await.unwrap();
+        for command in ["k", "lm"] {
+            session.lock().await.send_command(command).await.unwrap();
+        }
+        manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        manager.mark_read_only("tcp:Port=5005").await;
+
+        let guard = session.lock().await;
+        let sessions = manager.list_sessions().await;
+        assert_eq!(sessions.len(), 2);
+        let dump_info = &sessions[0];
+        assert_eq!(dump_info.kind, SessionKind::Dump);
+        assert_eq!(dump_info.target, dump.display().to_string());
+        assert_eq!(dump_info.commands_executed, 2);
+        assert!(dump_info.last_used_at >= dump_info.created_at);
+        assert!(dump_info.locked);
+        assert!(!dump_info.read_only);
+        // 脚本化后端没有独立进程
+        assert_eq!(dump_info.process_id, None);
+        assert_eq!(dump_info.cpu_time, None);
+
+        let remote = &sessions[1];
+        assert_eq!(remote.kind, SessionKind::Remote);
+        assert_eq!(remote.id, "tcp:Port=5005");
+        assert_eq!(remote.commands_executed, 0);
+        assert!(!remote.locked);
+        assert!(remote.read_only);
+
+        drop(guard);
+        assert!(!manager.list_sessions().await[0].locked);
+
+        let json = serde_json::to_value(remote).unwrap();
+        assert_eq!(json["kind"], "remote");
+        assert_eq!(json["wall_seconds"], 0.0);
+        assert!(json["cpu_seconds"].is_null());
+        assert!(json["created_at"].as_str().unwrap().ends_with('Z'));
+    }
+
+    fn scripted_manager(launcher: Arc<ScriptedLauncher>) -> SessionManager {
+        SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false)
+            .with_launcher(launcher)
+    }
+
+    #[tokio::test]
+    async fn test_evict_idle_sessions() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager =
+            scripted_manager(launcher.clone()).with_idle_ttl(Some(Duration::from_millis(50)));
+
+        let busy = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        manager.mark_read_only("tcp:Port=5005").await;
+        manager
+            .get_or_create_remote_session("tcp:Port=5006")
+            .await
+            .unwrap();
+        assert!(manager.evict_idle_sessions().await.is_empty());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        // 仍被持有的会话不会被回收
+        let _guard = busy.lock().await;
+        assert_eq!(manager.evict_idle_sessions().await, vec!["tcp:Port=5006"]);
+        assert_eq!(manager.active_session_count().await, 1);
+        drop(_guard);
+        drop(busy);
+
+        assert_eq!(manager.evict_idle_sessions().await, vec!["tcp:Port=5005"]);
+        assert_eq!(manager.active_session_count().await, 0);
+
+        // 下次使用时重新打开，只读标记保留
+        manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), 3);
+        assert!(manager.is_read_only("tcp:Port=5005").await);
+    }
+
+    #[tokio::test]
+    async fn test_idle_reaper_task() {
+        let manager = Arc::new(
+            scripted_manager(Arc::new(ScriptedLauncher::default()))
+                .with_idle_ttl(Some(Duration::from_millis(100))),
+        );
+        let reaper = manager.spawn_idle_reaper().unwrap();
+        manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(manager.active_session_count().await, 0);
+
+        // 管理器释放后任务退出
+        drop(manager);
+        tokio::time::timeout(Duration::from_secs(1), reaper)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let manager = Arc::new(scripted_manager(Arc::new(ScriptedLauncher::default())));
+        assert!(manager.spawn_idle_reaper().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_sessions_evicts_lru() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager = scripted_manager(launcher.clone()).with_max_sessions(Some(2));
+
+        for port in [5001, 5002] {
+            manager
+                .get_or_create_remote_session(&format!("tcp:Port={}", port))
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // 使用 5001 后，5002 成为最久未使用的会话
+        let first = manager
+            .get_or_create_remote_session("tcp:Port=5001")
+            .await
+            .unwrap();
+        first.lock().await.send_command("k").await.unwrap();
+        drop(first);
+
+        manager
+            .get_or_create_remote_session("tcp:Port=5003")
+            .await
+            .unwrap();
+        assert_eq!(manager.active_session_count().await, 2);
+        assert!(manager.has_session("tcp:Port=5001").await);
+        assert!(!manager.has_session("tcp:Port=5002").await);
+        assert!(manager.has_session("tcp:Port=5003").await);
+
+        // 其他会话都在使用时暂时超过上限
+        let held: Vec<_> = [5001, 5003]
+            .iter()
+            .map(|port| format!("tcp:Port={}", port))
+            .collect();
+        let mut sessions = Vec::new();
+        for id in &held {
+            sessions.push(manager.get_or_create_remote_session(id).await.unwrap());
+        }
+        manager
+            .get_or_create_remote_session("tcp:Port=5004")
+            .await
+            .unwrap();
+        assert_eq!(manager.active_session_count().await, 3);
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_dead_session_is_restarted() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager = scripted_manager(launcher.clone());
+
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        manager.mark_read_only("tcp:Port=5005").await;
+        {
+            let mut guard = session.lock().await;
+            assert!(guard.take_restart_notice().is_none());
+            assert!(matches!(
+                guard.send_command("crash").await,
+                Err(CdbError::ProcessTerminated)
+            ));
+            assert!(!guard.is_alive());
+        }
+        drop(session);
+
+        // 下次使用时重新启动，提示只返回一次
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), 2);
+        assert_eq!(manager.active_session_count().await, 1);
+        assert!(manager.is_read_only("tcp:Port=5005").await);
+        let mut guard = session.lock().await;
+        let notice = guard.take_restart_notice().unwrap();
+        assert!(notice.contains("restarted"), "{}", notice);
+        assert!(notice.contains("state"), "{}", notice);
+        assert!(guard.take_restart_notice().is_none());
+        assert_eq!(
+            guard.send_command("k").await.unwrap(),
+            vec!["tcp:Port=5005: k"]
+        );
+        drop(guard);
+
+        let sessions = manager.list_sessions().await;
+        assert_eq!(sessions[0].restarts, 1);
+        assert_eq!(sessions[0].commands_executed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_restart_retries_are_bounded() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager = scripted_manager(launcher.clone());
+
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        let _ = session.lock().await.send_command("crash").await;
+        drop(session);
+
+        // 失败次数少于上限时重试成功
+        launcher.failures.store(2, Ordering::SeqCst);
+        manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), 4);
+
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        let _ = session.lock().await.send_command("crash").await;
+        drop(session);
+
+        // 全部失败时放弃并移除会话
+        launcher
+            .failures
+            .store(MAX_RESTART_ATTEMPTS as usize, Ordering::SeqCst);
+        let err = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SessionError::RestartFailed {
+                attempts: MAX_RESTART_ATTEMPTS,
+                ..
+            }
+        ));
+        assert_eq!(
+            launcher.spawned.load(Ordering::SeqCst),
+            4 + MAX_RESTART_ATTEMPTS as usize
+        );
+        assert_eq!(manager.active_session_count().await, 0);
+
+        // 会话已移除，之后按新会话创建
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert!(session.lock().await.take_restart_notice().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_crash_looping_session_is_abandoned() {
+        let launcher = Arc::new(ScriptedLauncher::default());
+        let manager = scripted_manager(launcher.clone());
+
+        // 窗口内的重新启动次数达到上限后不再重新启动
+        for _ in 0..=MAX_RESTARTS_PER_WINDOW {
+            let session = manager
+                .get_or_create_remote_session("tcp:Port=5005")
+                .await
+                .unwrap();
+            let _ = session.lock().await.send_command("crash").await;
+        }
+        let spawned = launcher.spawned.load(Ordering::SeqCst);
+        assert_eq!(spawned, 1 + MAX_RESTARTS_PER_WINDOW as usize);
+
+        for _ in 0..2 {
+            let err = manager
+                .get_or_create_remote_session("tcp:Port=5005")
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, SessionError::RestartLimitReached { .. }),
+                "{:?}",
+                err
+            );
+            assert!(err.to_string().contains("close the session"), "{}", err);
+        }
+        assert_eq!(launcher.spawned.load(Ordering::SeqCst), spawned);
+        assert_eq!(manager.active_session_count().await, 0);
+
+        // 显式关闭后可以重新打开
+        manager.close_session("tcp:Port=5005").await.unwrap();
+        let session = manager
+            .get_or_create_remote_session("tcp:Port=5005")
+            .await
+            .unwrap();
+        assert!(session.lock().await.take_restart_notice().is_none());
+        assert!(matches!(
+            manager.close_session("tcp:Port=5006").await,
+            Err(SessionError::SessionNotFound(_))
+        ));
+    }
 
     #[tokio::test]
     async fn test_session_manager_new() {
@@ -278,7 +1379,7 @@ mod tests {
     async fn test_get_or_create_dump_session_file_not_found() {
         let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false);
         let result = manager
-            .get_or_create_dump_session(Path::new("nonexistent.dmp"), None, None)
+            .get_or_create_dump_session(Path::new("nonexistent.dmp"))
             .await;
 
         assert!(result.is_err());
@@ -288,6 +1389,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spawn_options_builders() {
+        let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), true)
+            .with_cdb_path(Some(PathBuf::from("custom\\cdb.exe")))
+            .with_symbols_path(Some("SRV*C:\\Symbols".to_string()))
+            .with_extra_args(vec!["-lines".to_string()]);
+
+        let options = manager.spawn_options();
+        assert_eq!(options.cdb_path, Some(PathBuf::from("custom\\cdb.exe")));
+        assert_eq!(options.symbols_path.as_deref(), Some("SRV*C:\\Symbols"));
+        assert_eq!(options.extra_args, vec!["-lines".to_string()]);
+        assert_eq!(options.timeout, Duration::from_secs(30));
+        assert!(options.verbose);
+    }
+
     #[tokio::test]
     async fn test_close_session_not_found() {
         let manager = SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false);
@@ -0,0 +1,143 @@
+//! 命令超时模块
+//!
+//! 按命令类别选择超时时间：`!analyze`、`.reload /f`、`!heap` 等耗时命令使用更长的内置超时，
+//! 配置文件的 `[command_timeouts]` 段可以覆盖或补充内置表。
+//! 一行中用 `;` 分隔的多条命令取其中最长的超时。
+
+use crate::policy::{command_matches, split_commands};
+use std::time::Duration;
+
+/// 内置的命令类别超时（秒）
+///
+/// 模式与 [`CommandPolicy`](crate::policy::CommandPolicy) 相同：不区分大小写的通配符，
+/// 与完整命令或命令名匹配。内置超时是下限，默认超时更长时使用默认超时。
+pub const BUILTIN_COMMAND_TIMEOUTS: &[(&str, u64)] = &[
+    // 分析崩溃（可能下载大量符号）
+    ("!analyze*", 300),
+    // 强制重新加载符号
+    (".reload*/f*", 600),
+    ("ld", 600),
+    // 遍历堆
+    ("!heap*", 600),
+];
+
+/// 命令超时表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandTimeouts {
+    /// 配置的命令模式和超时（优先于内置表，可以比默认超时更短）
+    pub overrides: Vec<(String, Duration)>,
+}
+
+impl CommandTimeouts {
+    /// 选择命令行的超时时间
+    ///
+    /// 每条命令依次查找配置的模式和内置表（匹配多个模式时取最长的超时），
+    /// 都不匹配时使用默认超时。
+    ///
+    /// # 参数
+    /// * `command_line` - 可能包含多条 `;` 分隔命令的命令行
+    /// * `default` - 默认超时
+    pub fn timeout_for(&self, command_line: &str, default: Duration) -> Duration {
+        split_commands(command_line)
+            .into_iter()
+            .map(|command| self.timeout_for_command(command, default))
+            .max()
+            .unwrap_or(default)
+    }
+
+    /// 选择单条命令的超时时间
+    fn timeout_for_command(&self, command: &str, default: Duration) -> Duration {
+        let configured = self
+            .overrides
+            .iter()
+            .filter(|(pattern, _)| command_matches(pattern, command))
+            .map(|(_, timeout)| *timeout)
+            .max();
+        if let Some(timeout) = configured {
+            return timeout;
+        }
+
+        BUILTIN_COMMAND_TIMEOUTS
+            .iter()
+            .filter(|(pattern, _)| command_matches(pattern, command))
+            .map(|(_, secs)| Duration::from_secs(*secs).max(default))
+            .max()
+            .unwrap_or(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_builtin_timeouts() {
+        let timeouts = CommandTimeouts::default();
+        assert_eq!(timeouts.timeout_for("k", DEFAULT), DEFAULT);
+        assert_eq!(timeouts.timeout_for("lm", DEFAULT), DEFAULT);
+        assert_eq!(
+            timeouts.timeout_for("!analyze -v", DEFAULT),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            timeouts.timeout_for(".reload /f ntdll.dll", DEFAULT),
+            Duration::from_secs(600)
+        );
+        assert_eq!(timeouts.timeout_for(".reload", DEFAULT), DEFAULT);
+        assert_eq!(
+            timeouts.timeout_for("!HEAP -s -v", DEFAULT),
+            Duration::from_secs(600)
+        );
+
+        // 多条命令取最长的超时，线程前缀后的命令同样匹配
+        assert_eq!(
+            timeouts.timeout_for(".ecxr; !analyze -v; k", DEFAULT),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            timeouts.timeout_for("~*e !heap -s", DEFAULT),
+            Duration::from_secs(600)
+        );
+
+        // 默认超时更长时不缩短
+        let long = Duration::from_secs(900);
+        assert_eq!(timeouts.timeout_for("!analyze -v", long), long);
+    }
+
+    #[test]
+    fn test_configured_timeouts_override_builtin() {
+        let timeouts = CommandTimeouts {
+            overrides: vec![
+                ("!analyze*".to_string(), Duration::from_secs(60)),
+                ("!address*".to_string(), Duration::from_secs(120)),
+            ],
+        };
+        assert_eq!(
+            timeouts.timeout_for("!analyze -v", DEFAULT),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            timeouts.timeout_for("!address -summary", DEFAULT),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            timeouts.timeout_for("!heap -s", DEFAULT),
+            Duration::from_secs(600)
+        );
+        assert_eq!(timeouts.timeout_for("", DEFAULT), DEFAULT);
+    }
+
+    #[test]
+    fn test_attached_arguments() {
+        let timeouts = CommandTimeouts {
+            overrides: vec![("dq".to_string(), Duration::from_secs(90))],
+        };
+        // 与命令策略相同，命令名在 `@` 等参数字符处结束
+        assert_eq!(
+            timeouts.timeout_for("dq@rsp L8", DEFAULT),
+            Duration::from_secs(90)
+        );
+    }
+}
@@ -2,29 +2,43 @@
 //!
 //! 实现所有 MCP 工具的处理逻辑。
 
-use crate::error::ToolError;
+use crate::backend::{DebugTarget, DebuggerBackend, SharedBackend};
+use crate::error::{CdbError, ToolError};
+use crate::minidump::{offline, Minidump};
+use crate::parser::analyze::parse_analyze;
+use crate::parser::modules::parse_modules;
+use crate::parser::registers::{parse_registers, Architecture};
+use crate::parser::stack::parse_stack;
+use crate::parser::threads::{parse_threads, summarize_threads};
+use crate::policy::{self, CommandPolicy};
 use crate::session::SessionManager;
+use crate::triage::{bucket_dumps, CrashSignature, TriagedDump};
 use crate::types::*;
-use std::path::Path;
+use crate::utils::DumpFileInfo;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::{debug, info};
+use tokio::sync::MutexGuard;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
 /// 处理 open_windbg_dump 工具调用
 ///
-/// 打开并分析崩溃转储文件。
+/// 打开并分析崩溃转储文件。客户端取消请求时中断正在执行的分析命令。
 ///
 /// # 参数
 /// * `manager` - 会话管理器
 /// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
 ///
 /// # 返回
 /// 返回包含分析结果的工具响应
 ///
 /// # 错误
-/// 如果文件不存在或分析失败，返回错误
+/// 如果文件不存在、请求被取消或分析失败，返回错误
 pub async fn handle_open_windbg_dump(
     manager: Arc<SessionManager>,
     params: OpenWindbgDumpParams,
+    cancel: CancellationToken,
 ) -> Result<ToolResponse, ToolError> {
     info!("Opening dump file: {}", params.dump_path);
 
@@ -37,12 +51,18 @@ pub async fn handle_open_windbg_dump(
         )));
     }
 
-    // 获取或创建会话
-    let session = manager
-        .get_or_create_dump_session(dump_path, None, None)
-        .await?;
+    // 获取或创建会话；没有 CDB 时退化为离线分析
+    let session = match manager
+        .get_or_create_dump_session(dump_path)
+        .await
+        .map_err(ToolError::from)
+    {
+        Err(e) if e.is_cdb_unavailable() => return open_dump_offline(dump_path, &params),
+        result => result?,
+    };
 
-    let mut session_guard = session.lock().await;
+    let mut session_guard = lock_session(&session, &cancel).await?;
+    let notice = session_guard.take_restart_notice();
 
     // 构建输出
     let mut output_lines = Vec::new();
@@ -53,7 +73,7 @@ pub async fn handle_open_windbg_dump(
     debug!("Executing .lastevent command");
     output_lines.push("## Last Event".to_string());
     output_lines.push("```".to_string());
-    match session_guard.send_command(".lastevent").await {
+    match report_command(&mut session_guard, ".lastevent", &cancel).await? {
         Ok(lines) => {
             output_lines.extend(lines);
         }
@@ -66,10 +86,12 @@ pub async fn handle_open_windbg_dump(
 
     // 执行 !analyze -v 命令进行详细分析
     debug!("Executing !analyze -v command");
+    let mut analysis = None;
     output_lines.push("## Detailed Analysis".to_string());
     output_lines.push("```".to_string());
-    match session_guard.send_command("!analyze -v").await {
+    match report_command(&mut session_guard, "!analyze -v", &cancel).await? {
         Ok(lines) => {
+            analysis = Some(parse_analyze(&lines));
             output_lines.extend(lines);
         }
         Err(e) => {
@@ -80,12 +102,14 @@ pub async fn handle_open_windbg_dump(
     output_lines.push(String::new());
 
     // 根据参数执行可选命令
+    let mut stack = None;
     if params.include_stack_trace {
         debug!("Executing kb command (stack trace)");
         output_lines.push("## Stack Trace".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("kb").await {
+        match report_command(&mut session_guard, "kb", &cancel).await? {
             Ok(lines) => {
+                stack = parse_stack(&lines).into_iter().next();
                 output_lines.extend(lines);
             }
             Err(e) => {
@@ -96,12 +120,14 @@ pub async fn handle_open_windbg_dump(
         output_lines.push(String::new());
     }
 
+    let mut modules = None;
     if params.include_modules {
         debug!("Executing lm command (module list)");
         output_lines.push("## Loaded Modules".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("lm").await {
+        match report_command(&mut session_guard, "lm", &cancel).await? {
             Ok(lines) => {
+                modules = Some(parse_modules(&lines));
                 output_lines.extend(lines);
             }
             Err(e) => {
@@ -112,12 +138,14 @@ pub async fn handle_open_windbg_dump(
         output_lines.push(String::new());
     }
 
+    let mut threads = None;
     if params.include_threads {
         debug!("Executing ~ command (thread list)");
         output_lines.push("## Thread List".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("~").await {
+        match report_command(&mut session_guard, "~", &cancel).await? {
             Ok(lines) => {
+                threads = Some(parse_threads(&lines));
                 output_lines.extend(lines);
             }
             Err(e) => {
@@ -133,45 +161,77 @@ pub async fn handle_open_windbg_dump(
 
     info!("Dump file analysis completed");
 
-    Ok(ToolResponse::text(output))
+    let structured = serde_json::json!({
+        "dump_path": params.dump_path,
+        "analysis": analysis,
+        "stack": stack,
+        "modules": modules,
+        "threads": threads,
+    });
+
+    Ok(ToolResponse::text(output)
+        .with_structured(structured)
+        .with_notice(notice))
 }
 
 /// 处理 open_windbg_remote 工具调用
 ///
-/// 连接到远程调试会话。
+/// 连接到远程调试会话。`read_only` 为 true 时（或服务器配置了全局只读）
+/// 会话拒绝执行控制和修改内存的命令。
 ///
 /// # 参数
 /// * `manager` - 会话管理器
 /// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
 ///
 /// # 返回
 /// 返回包含连接信息的工具响应
 ///
 /// # 错误
-/// 如果连接失败，返回错误
+/// 如果连接失败或请求被取消，返回错误
 pub async fn handle_open_windbg_remote(
     manager: Arc<SessionManager>,
     params: OpenWindbgRemoteParams,
+    cancel: CancellationToken,
 ) -> Result<ToolResponse, ToolError> {
     info!("Connecting to remote target: {}", params.connection_string);
 
+    // 先标记只读再创建会话，避免会话发布后、标记之前有并发的命令绕过只读检查
+    if params.read_only {
+        manager.mark_read_only(&params.connection_string).await;
+    }
+
     // 获取或创建会话
     let session = manager
-        .get_or_create_remote_session(&params.connection_string, None, None)
+        .get_or_create_remote_session(&params.connection_string)
         .await?;
 
-    let mut session_guard = session.lock().await;
+    let read_only = manager.is_read_only(&params.connection_string).await;
+
+    let mut session_guard = lock_session(&session, &cancel).await?;
+    let notice = session_guard.take_restart_notice();
 
     // 构建输出
     let mut output_lines = Vec::new();
-    output_lines.push(format!("# Remote Debugging Session: {}", params.connection_string));
+    output_lines.push(format!(
+        "# Remote Debugging Session: {}",
+        params.connection_string
+    ));
+    output_lines.push(String::new());
+    if read_only {
+        output_lines.push(
+            "> Mode: read-only. Execution control (g, p, t, wt), breakpoints, .kill/.detach and memory or register writes are rejected; inspection commands are allowed.".to_string(),
+        );
+    } else {
+        output_lines.push("> Mode: full control".to_string());
+    }
     output_lines.push(String::new());
 
     // 执行 !peb 命令获取进程信息
     debug!("Executing !peb command");
     output_lines.push("## Process Environment Block (PEB)".to_string());
     output_lines.push("```".to_string());
-    match session_guard.send_command("!peb").await {
+    match report_command(&mut session_guard, "!peb", &cancel).await? {
         Ok(lines) => {
             output_lines.extend(lines);
         }
@@ -184,10 +244,12 @@ pub async fn handle_open_windbg_remote(
 
     // 执行 r 命令获取寄存器信息
     debug!("Executing r command");
+    let mut registers = None;
     output_lines.push("## Registers".to_string());
     output_lines.push("```".to_string());
-    match session_guard.send_command("r").await {
+    match report_command(&mut session_guard, "r", &cancel).await? {
         Ok(lines) => {
+            registers = Some(parse_registers(&lines));
             output_lines.extend(lines);
         }
         Err(e) => {
@@ -202,7 +264,7 @@ pub async fn handle_open_windbg_remote(
         debug!("Executing kb command (stack trace)");
         output_lines.push("## Stack Trace".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("kb").await {
+        match report_command(&mut session_guard, "kb", &cancel).await? {
             Ok(lines) => {
                 output_lines.extend(lines);
             }
@@ -218,7 +280,7 @@ pub async fn handle_open_windbg_remote(
         debug!("Executing lm command (module list)");
         output_lines.push("## Loaded Modules".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("lm").await {
+        match report_command(&mut session_guard, "lm", &cancel).await? {
             Ok(lines) => {
                 output_lines.extend(lines);
             }
@@ -234,7 +296,7 @@ pub async fn handle_open_windbg_remote(
         debug!("Executing ~ command (thread list)");
         output_lines.push("## Thread List".to_string());
         output_lines.push("```".to_string());
-        match session_guard.send_command("~").await {
+        match report_command(&mut session_guard, "~", &cancel).await? {
             Ok(lines) => {
                 output_lines.extend(lines);
             }
@@ -251,57 +313,368 @@ pub async fn handle_open_windbg_remote(
 
     info!("Remote session connection completed");
 
-    Ok(ToolResponse::text(output))
+    let structured = serde_json::json!({
+        "connection_string": params.connection_string,
+        "read_only": read_only,
+        "registers": registers,
+    });
+
+    Ok(ToolResponse::text(output)
+        .with_structured(structured)
+        .with_notice(notice))
 }
 
 /// 处理 run_windbg_cmd 工具调用
 ///
-/// 在现有会话中执行自定义 WinDbg 命令。
+/// 在现有会话中执行自定义 WinDbg 命令。命令先经过命令策略检查。
+/// 客户端取消请求时中断正在执行的命令，会话保持可用。
 ///
 /// # 参数
 /// * `manager` - 会话管理器
+/// * `policy` - 命令策略
 /// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
 ///
 /// # 返回
 /// 返回命令输出
 ///
 /// # 错误
-/// 如果参数无效或命令执行失败，返回错误
+/// 如果参数无效、命令被策略拒绝、请求被取消或命令执行失败，返回错误
 pub async fn handle_run_windbg_cmd(
     manager: Arc<SessionManager>,
+    policy: &CommandPolicy,
     params: RunWindbgCmdParams,
+    cancel: CancellationToken,
 ) -> Result<ToolResponse, ToolError> {
     // 验证参数
     params.validate().map_err(ToolError::InvalidParams)?;
 
+    // 在打开会话之前检查命令策略；只读的远程会话另外拒绝改变目标的命令
+    let mut allowed = policy.check(&params.command);
+    if let (Ok(()), Some(connection_string)) = (&allowed, params.connection_string.as_deref()) {
+        if manager.is_read_only(connection_string).await {
+            allowed = policy::check_read_only(&params.command);
+        }
+    }
+    if let Err(e) = allowed {
+        warn!("Rejected command {:?}: {}", params.command, e);
+        return Err(e);
+    }
+
     info!("Executing custom command: {}", params.command);
 
     // 根据参数类型获取会话
-    let session = if let Some(dump_path) = &params.dump_path {
-        let path = Path::new(dump_path);
-        manager.get_or_create_dump_session(path, None, None).await?
-    } else if let Some(connection_string) = &params.connection_string {
-        manager
-            .get_or_create_remote_session(connection_string, None, None)
-            .await?
-    } else {
-        return Err(ToolError::InvalidParams(
-            "Either dump_path or connection_string must be provided".to_string(),
-        ));
-    };
+    let session = get_session(
+        &manager,
+        params.dump_path.as_deref(),
+        params.connection_string.as_deref(),
+    )
+    .await?;
 
-    let mut session_guard = session.lock().await;
+    let mut session_guard = lock_session(&session, &cancel)
+        .await
+        .inspect_err(|_| info!("Command cancelled before it started: {}", params.command))?;
+    let notice = session_guard.take_restart_notice();
 
     // 执行命令
     debug!("Executing command: {}", params.command);
-    let output_lines = session_guard.send_command(&params.command).await?;
+    // 先收齐之前命令的 stderr，使位置标记之后只有本条命令的输出
+    if params.include_stderr {
+        session_guard.sync_stderr().await;
+    }
+    let stderr_mark = session_guard.stderr_mark();
+    let output_lines = session_guard
+        .send_command_cancellable(&params.command, params.timeout(), &cancel)
+        .await?;
 
     // 格式化输出
-    let output = format!("```\n{}\n```", output_lines.join("\n"));
+    let mut output = format!("```\n{}\n```", output_lines.join("\n"));
+
+    // 附加命令执行期间的 stderr 输出
+    if params.include_stderr {
+        session_guard.sync_stderr().await;
+        let stderr_lines = session_guard.stderr_since(stderr_mark);
+        if stderr_lines.is_empty() {
+            output.push_str("\n\nstderr: (empty)");
+        } else {
+            output.push_str(&format!(
+                "\n\nstderr:\n```\n{}\n```",
+                stderr_lines.join("\n")
+            ));
+        }
+    }
 
     info!("Command execution completed");
 
-    Ok(ToolResponse::text(output))
+    Ok(ToolResponse::text(output).with_notice(notice))
+}
+
+/// 处理 get_stack 工具调用
+///
+/// 获取指定线程（或所有线程）的调用栈，并以 JSON 形式返回解析后的帧。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+/// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
+///
+/// # 返回
+/// 返回按线程分组的调用栈
+///
+/// # 错误
+/// 如果参数无效或命令执行失败，返回错误
+pub async fn handle_get_stack(
+    manager: Arc<SessionManager>,
+    params: GetStackParams,
+    cancel: CancellationToken,
+) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
+
+    let session = get_session(
+        &manager,
+        params.dump_path.as_deref(),
+        params.connection_string.as_deref(),
+    )
+    .await?;
+
+    let command = params.command();
+    debug!("Executing command: {}", command);
+    let (output_lines, notice) = send_command(&session, &command, &cancel).await?;
+
+    let mut threads = parse_stack(&output_lines);
+    // 指定单个线程时 CDB 不输出线程头，使用请求的线程序号补全
+    if let (Some(thread), [stack]) = (params.thread, threads.as_mut_slice()) {
+        stack.thread.get_or_insert(thread);
+    }
+    if threads.is_empty() {
+        return Err(ToolError::InternalError(format!(
+            "No stack frames found in output of '{}': {}",
+            command,
+            output_lines.join("\n")
+        )));
+    }
+
+    info!("Stack retrieved for {} thread(s)", threads.len());
+
+    let structured = serde_json::json!({ "threads": threads });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text)
+        .with_structured(structured)
+        .with_notice(notice))
+}
+
+/// 处理 list_modules 工具调用
+///
+/// 获取已加载模块清单（`lm v` / `lmvm`），可筛选缺少符号的模块或第三方模块。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+/// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
+///
+/// # 返回
+/// 返回模块列表（JSON）
+///
+/// # 错误
+/// 如果参数无效或命令执行失败，返回错误
+pub async fn handle_list_modules(
+    manager: Arc<SessionManager>,
+    params: ListModulesParams,
+    cancel: CancellationToken,
+) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
+
+    let session = match get_session(
+        &manager,
+        params.dump_path.as_deref(),
+        params.connection_string.as_deref(),
+    )
+    .await
+    {
+        // 没有 CDB 时直接从 minidump 读取模块列表
+        Err(e) if e.is_cdb_unavailable() => match params.dump_path.as_deref() {
+            Some(dump_path) => return list_modules_offline(Path::new(dump_path), &params),
+            None => return Err(e),
+        },
+        result => result?,
+    };
+
+    let command = params.command();
+    debug!("Executing command: {}", command);
+    let (output_lines, notice) = send_command(&session, &command, &cancel).await?;
+
+    let modules = parse_modules(&output_lines);
+    let total = modules.len();
+    let modules: Vec<_> = modules
+        .into_iter()
+        .filter(|m| !params.missing_symbols || m.is_missing_symbols())
+        .filter(|m| !params.third_party || m.is_third_party())
+        .collect();
+
+    info!("Listed {} of {} module(s)", modules.len(), total);
+
+    let structured = serde_json::json!({
+        "total": total,
+        "matched": modules.len(),
+        "modules": modules,
+    });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text)
+        .with_structured(structured)
+        .with_notice(notice))
+}
+
+/// 处理 list_threads 工具调用
+///
+/// 获取线程列表（`~*`），并按需合并 `!runaway` CPU 时间和 `~*k1` 栈顶帧，每个线程返回一行。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+/// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
+///
+/// # 返回
+/// 返回线程汇总列表（JSON）
+///
+/// # 错误
+/// 如果参数无效或线程列表获取失败，返回错误
+pub async fn handle_list_threads(
+    manager: Arc<SessionManager>,
+    params: ListThreadsParams,
+    cancel: CancellationToken,
+) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
+
+    let session = match get_session(
+        &manager,
+        params.dump_path.as_deref(),
+        params.connection_string.as_deref(),
+    )
+    .await
+    {
+        // 没有 CDB 时直接从 minidump 读取线程列表
+        Err(e) if e.is_cdb_unavailable() => match params.dump_path.as_deref() {
+            Some(dump_path) => return list_threads_offline(Path::new(dump_path), &params),
+            None => return Err(e),
+        },
+        result => result?,
+    };
+    let mut session_guard = lock_session(&session, &cancel).await?;
+    let notice = session_guard.take_restart_notice();
+
+    debug!("Executing ~* command (thread list)");
+    let threads = parse_threads(
+        &session_guard
+            .send_command_cancellable("~*", None, &cancel)
+            .await?,
+    );
+
+    // CPU 时间和栈顶帧是附加信息，获取失败时不影响线程列表
+    let mut runaway = Vec::new();
+    if params.include_cpu_times {
+        debug!("Executing !runaway 7 command");
+        match report_command(&mut session_guard, "!runaway 7", &cancel).await? {
+            Ok(lines) => runaway = lines,
+            Err(e) => warn!("Failed to get thread CPU times: {}", e),
+        }
+    }
+
+    let mut top_frames = Vec::new();
+    if params.include_top_frame {
+        debug!("Executing ~* k1 command");
+        match report_command(&mut session_guard, "~* k1", &cancel).await? {
+            Ok(lines) => top_frames = lines,
+            Err(e) => warn!("Failed to get thread top frames: {}", e),
+        }
+    }
+    drop(session_guard);
+
+    let summary = summarize_threads(threads, &runaway, &top_frames);
+
+    info!("Listed {} thread(s)", summary.len());
+
+    let structured = serde_json::json!({ "threads": summary });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text)
+        .with_structured(structured)
+        .with_notice(notice))
+}
+
+/// 处理 get_registers 工具调用
+///
+/// 获取寄存器上下文，可指定线程或切换到异常上下文（`.ecxr`）/ 上下文记录（`.cxr`）。
+/// 切换到指定的上下文记录后会执行 `.cxr` 恢复默认上下文，避免影响会话中的后续命令；
+/// `.ecxr` 和线程寄存器不会重置会话中已有的上下文。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+/// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
+///
+/// # 返回
+/// 返回解析后的寄存器上下文（JSON）
+///
+/// # 错误
+/// 如果参数无效、命令执行失败或输出中没有寄存器，返回错误
+pub async fn handle_get_registers(
+    manager: Arc<SessionManager>,
+    params: GetRegistersParams,
+    cancel: CancellationToken,
+) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
+
+    let session = get_session(
+        &manager,
+        params.dump_path.as_deref(),
+        params.connection_string.as_deref(),
+    )
+    .await?;
+    let mut session_guard = lock_session(&session, &cancel).await?;
+    let notice = session_guard.take_restart_notice();
+
+    let command = params.command();
+    debug!("Executing command: {}", command);
+    let result = session_guard
+        .send_command_cancellable(&command, None, &cancel)
+        .await;
+
+    // 取消后同样恢复上下文，因此不使用取消令牌
+    if params.restores_context() {
+        if let Err(e) = session_guard.send_command(".cxr").await {
+            warn!("Failed to restore default register context: {}", e);
+        }
+    }
+    drop(session_guard);
+
+    let output_lines = result?;
+    let registers = parse_registers(&output_lines);
+    if registers.registers.is_empty() {
+        return Err(ToolError::InternalError(format!(
+            "No registers found in output of '{}': {}",
+            command,
+            output_lines.join("\n")
+        )));
+    }
+
+    info!("Registers retrieved ({:?})", registers.architecture);
+
+    let structured = serde_json::json!({
+        "command": command,
+        "thread": params.thread,
+        "registers": registers,
+    });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text)
+        .with_structured(structured)
+        .with_notice(notice))
 }
 
 /// 处理 close_windbg_dump 工具调用
@@ -324,12 +697,7 @@ pub async fn handle_close_windbg_dump(
     info!("Closing dump session: {}", params.dump_path);
 
     // 生成会话 ID（与创建时相同的逻辑）
-    let dump_path = Path::new(&params.dump_path);
-    let session_id = dump_path
-        .canonicalize()
-        .unwrap_or_else(|_| dump_path.to_path_buf())
-        .to_string_lossy()
-        .to_string();
+    let session_id = DebugTarget::Dump(PathBuf::from(&params.dump_path)).session_id();
 
     // 关闭会话
     manager.close_session(&session_id).await?;
@@ -372,30 +740,336 @@ pub async fn handle_close_windbg_remote(
     )))
 }
 
+/// 处理 list_windbg_sessions 工具调用
+///
+/// 列出会话管理器中的所有调试会话及其使用统计。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+///
+/// # 返回
+/// 返回会话列表（文本和 JSON）
+pub async fn handle_list_windbg_sessions(
+    manager: Arc<SessionManager>,
+) -> Result<ToolResponse, ToolError> {
+    let sessions = manager.list_sessions().await;
+
+    let mut output_lines = vec!["# Debugger Sessions".to_string(), String::new()];
+    if sessions.is_empty() {
+        output_lines.push("No active sessions.".to_string());
+    } else {
+        output_lines.push(format!("{} active sessions:", sessions.len()));
+        output_lines.push(String::new());
+        for (i, session) in sessions.iter().enumerate() {
+            let mut details = vec![
+                session.kind.as_str().to_string(),
+                format!(
+                    "created {}",
+                    crate::utils::format_timestamp(session.created_at)
+                ),
+                format!(
+                    "last used {}",
+                    crate::utils::format_timestamp(session.last_used_at)
+                ),
+                format!("{} commands", session.commands_executed),
+                format!("{:.1}s wall", session.wall_time.as_secs_f64()),
+            ];
+            if let Some(cpu_time) = session.cpu_time {
+                details.push(format!("{:.1}s CPU", cpu_time.as_secs_f64()));
+            }
+            if let Some(pid) = session.process_id {
+                details.push(format!("pid {}", pid));
+            }
+            if session.read_only {
+                details.push("read-only".to_string());
+            }
+            if session.restarts > 0 {
+                details.push(format!("restarted {}x", session.restarts));
+            }
+            if session.locked {
+                details.push("busy".to_string());
+            }
+            output_lines.push(format!(
+                "{}. {} ({})",
+                i + 1,
+                session.target,
+                details.join(", ")
+            ));
+        }
+    }
+
+    let structured = serde_json::json!({ "sessions": sessions });
+    Ok(ToolResponse::text(output_lines.join("\n")).with_structured(structured))
+}
+
 /// 处理 list_windbg_dumps 工具调用
 ///
-/// 列出目录中的转储文件。
+/// 列出目录中的转储文件，支持按修改时间、文件名和大小筛选。
+/// 每个转储附带从文件头部读取的转储类型和异常代码，以及从文件名推断的进程名。
 ///
 /// # 参数
 /// * `params` - 工具参数
 ///
 /// # 返回
-/// 返回转储文件列表
+/// 返回转储文件列表（文本和 JSON）
 ///
 /// # 错误
-/// 如果目录不存在或搜索失败，返回错误
+/// 如果参数无效、目录不存在或搜索失败，返回错误
 pub async fn handle_list_windbg_dumps(
     params: ListWindbgDumpsParams,
 ) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
     info!("Listing dump files");
 
-    // 确定搜索目录
-    let search_dir = if let Some(dir_path) = &params.directory_path {
+    let search_dir = resolve_dump_directory(params.directory_path.as_deref())?;
+
+    // 搜索并筛选转储文件
+    let (since, until) = params.time_range().map_err(ToolError::InvalidParams)?;
+    let mut dump_files: Vec<DumpFileInfo> =
+        crate::utils::find_dump_files(&search_dir, params.recursive)?
+            .into_iter()
+            .filter(|f| params.min_size.is_none_or(|min| f.size_bytes >= min))
+            .filter(|f| params.max_size.is_none_or(|max| f.size_bytes <= max))
+            .filter(|f| since.is_none_or(|since| f.modified.is_some_and(|m| m >= since)))
+            .filter(|f| until.is_none_or(|until| f.modified.is_some_and(|m| m <= until)))
+            .filter(|f| {
+                params.name_glob.as_deref().is_none_or(|glob| {
+                    f.path
+                        .file_name()
+                        .is_some_and(|name| crate::utils::glob_match(glob, &name.to_string_lossy()))
+                })
+            })
+            .collect();
+
+    match params.sort_by {
+        // find_dump_files 已按大小降序排序
+        DumpSortBy::Size => {}
+        DumpSortBy::Mtime => dump_files.sort_by_key(|f| std::cmp::Reverse(f.modified)),
+        DumpSortBy::Name => dump_files.sort_by(|a, b| a.path.cmp(&b.path)),
+    }
+    let matched = dump_files.len();
+    if let Some(limit) = params.limit {
+        dump_files.truncate(limit);
+    }
+
+    // 只为最终返回的转储读取文件头部
+    let entries: Vec<serde_json::Value> = dump_files
+        .iter()
+        .map(|file| {
+            let details = crate::minidump::inspect_dump(&file.path);
+            let mut entry = serde_json::json!({
+                "path": file.path,
+                "size_bytes": file.size_bytes,
+                "modified": file.modified.map(crate::utils::format_timestamp),
+                "process_name": crate::utils::process_name_from_dump_path(&file.path),
+            });
+            if let (Some(entry), serde_json::Value::Object(details)) =
+                (entry.as_object_mut(), serde_json::json!(details))
+            {
+                entry.extend(details);
+            }
+            entry
+        })
+        .collect();
+
+    // 格式化输出
+    let mut output_lines = Vec::new();
+    output_lines.push(format!("# Dump File List: {}", search_dir.display()));
+    output_lines.push(String::new());
+
+    if dump_files.is_empty() {
+        output_lines.push("No dump files found.".to_string());
+    } else {
+        if matched > dump_files.len() {
+            output_lines.push(format!(
+                "Found {} dump files (showing {}):",
+                matched,
+                dump_files.len()
+            ));
+        } else {
+            output_lines.push(format!("Found {} dump files:", dump_files.len()));
+        }
+        output_lines.push(String::new());
+
+        for (i, (file_info, entry)) in dump_files.iter().zip(&entries).enumerate() {
+            let size_mb = file_info.size_bytes as f64 / 1024.0 / 1024.0;
+            let mut details = vec![format!("{:.2} MB", size_mb)];
+            for key in [
+                "modified",
+                "dump_type",
+                "exception_code",
+                "bugcheck_code",
+                "process_name",
+            ] {
+                if let Some(value) = entry[key].as_str() {
+                    details.push(value.to_string());
+                }
+            }
+            output_lines.push(format!(
+                "{}. {} ({})",
+                i + 1,
+                file_info.path.display(),
+                details.join(", ")
+            ));
+        }
+    }
+
+    let output = output_lines.join("\n");
+
+    info!("Found {} dump files", dump_files.len());
+
+    let structured = serde_json::json!({
+        "directory": search_dir,
+        "matched": matched,
+        "dumps": entries,
+    });
+
+    Ok(ToolResponse::text(output).with_structured(structured))
+}
+
+/// 处理 triage_dumps 工具调用
+///
+/// 通过会话管理器逐个打开目录中的转储，根据异常代码、故障模块和栈顶帧生成崩溃签名并分桶。
+/// 为分诊而新建的会话在分析后立即关闭；已打开的会话保持不变。客户端取消请求时停止分诊。
+///
+/// # 参数
+/// * `manager` - 会话管理器
+/// * `params` - 工具参数
+/// * `cancel` - 请求的取消令牌
+///
+/// # 返回
+/// 返回按数量降序排序的崩溃桶和无法分析的转储（JSON）
+///
+/// # 错误
+/// 如果参数无效、目录不存在或请求被取消，返回错误；单个转储分析失败不会中断分诊
+pub async fn handle_triage_dumps(
+    manager: Arc<SessionManager>,
+    params: TriageDumpsParams,
+    cancel: CancellationToken,
+) -> Result<ToolResponse, ToolError> {
+    params.validate().map_err(ToolError::InvalidParams)?;
+
+    let search_dir = resolve_dump_directory(params.directory_path.as_deref())?;
+    let mut dump_files = crate::utils::find_dump_files(&search_dir, params.recursive)?;
+
+    // 优先分析最新的转储
+    dump_files.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.path.cmp(&b.path)));
+    if let Some(max_dumps) = params.max_dumps {
+        dump_files.truncate(max_dumps);
+    }
+
+    info!(
+        "Triaging {} dump files in {}",
+        dump_files.len(),
+        search_dir.display()
+    );
+
+    let mut triaged = Vec::with_capacity(dump_files.len());
+    let mut failed = Vec::new();
+    for file in &dump_files {
+        match triage_dump(&manager, file, params.frames, &cancel).await {
+            Ok(signature) => triaged.push(TriagedDump {
+                path: file.path.clone(),
+                modified: file.modified,
+                signature,
+            }),
+            Err(e) if cancel.is_cancelled() => {
+                info!("Triage cancelled at {}", file.path.display());
+                return Err(e);
+            }
+            Err(e) => {
+                warn!("Failed to triage {}: {}", file.path.display(), e);
+                failed.push(serde_json::json!({
+                    "path": file.path,
+                    "error": e.to_string(),
+                }));
+            }
+        }
+    }
+
+    let buckets = bucket_dumps(triaged);
+    info!(
+        "Triage completed: {} buckets, {} failed",
+        buckets.len(),
+        failed.len()
+    );
+
+    let structured = serde_json::json!({
+        "directory": search_dir,
+        "dump_count": dump_files.len(),
+        "bucket_count": buckets.len(),
+        "buckets": buckets,
+        "failed": failed,
+    });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text).with_structured(structured))
+}
+
+/// 分析单个转储并生成崩溃签名
+async fn triage_dump(
+    manager: &SessionManager,
+    file: &DumpFileInfo,
+    depth: usize,
+    cancel: &CancellationToken,
+) -> Result<CrashSignature, ToolError> {
+    let session_id = DebugTarget::Dump(file.path.clone()).session_id();
+    let existing = manager.has_session(&session_id).await;
+    let session = manager.get_or_create_dump_session(&file.path).await?;
+
+    let result = async {
+        let mut session_guard = lock_session(&session, cancel).await?;
+        let report = parse_analyze(
+            &session_guard
+                .send_command_cancellable("!analyze -v", None, cancel)
+                .await?,
+        );
+
+        // STACK_TEXT 缺失时使用当前线程的调用栈
+        let stack = if report.stack_text.is_empty() {
+            let output_lines = session_guard
+                .send_command_cancellable("kbn", None, cancel)
+                .await?;
+            parse_stack(&output_lines)
+                .into_iter()
+                .next()
+                .map(|s| s.frames)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let signature = CrashSignature::from_report(&report, &stack, depth);
+        if signature.exception_code.is_none() && signature.frames.is_empty() {
+            return Err(ToolError::InternalError(
+                "No exception or stack found in dump".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+    .await;
+    drop(session);
+
+    // 只关闭为分诊而新建的会话，避免同时保留大量 CDB 进程
+    if !existing {
+        if let Err(e) = manager.close_session(&session_id).await {
+            warn!("Failed to close triage session {}: {}", session_id, e);
+        }
+    }
+
+    result
+}
+
+/// 确定转储搜索目录（未指定时使用系统转储目录）
+fn resolve_dump_directory(directory_path: Option<&str>) -> Result<PathBuf, ToolError> {
+    let search_dir = if let Some(dir_path) = directory_path {
         Path::new(dir_path).to_path_buf()
     } else {
         // 使用默认转储路径
-        crate::utils::get_local_dumps_path()
-            .ok_or_else(|| ToolError::InternalError("Unable to determine default dump directory".to_string()))?
+        crate::utils::get_local_dumps_path().ok_or_else(|| {
+            ToolError::InternalError("Unable to determine default dump directory".to_string())
+        })?
     };
 
     debug!("Searching directory: {}", search_dir.display());
@@ -408,36 +1082,352 @@ pub async fn handle_list_windbg_dumps(
         )));
     }
 
-    // 搜索转储文件
-    let dump_files = crate::utils::find_dump_files(&search_dir, params.recursive)?;
+    Ok(search_dir)
+}
+
+/// 不使用 CDB 离线分析转储
+///
+/// 返回异常记录、故障线程的寄存器、栈指针附近的原始栈内存和模块列表，
+/// 不进行符号解析（地址只标注为 `模块+偏移`）；`include_threads` 时附带线程列表。
+///
+/// # 参数
+/// * `dump_path` - 转储文件路径
+/// * `params` - 工具参数
+///
+/// # 错误
+/// 如果文件不是用户模式 minidump，返回错误
+fn open_dump_offline(
+    dump_path: &Path,
+    params: &OpenWindbgDumpParams,
+) -> Result<ToolResponse, ToolError> {
+    warn!(
+        "CDB not available, analyzing dump offline: {}",
+        dump_path.display()
+    );
+
+    let mut dump = Minidump::open(dump_path)?;
+    let mut report = offline::analyze(&mut dump);
+    if !params.include_threads {
+        report.threads.clear();
+    }
+    for warning in &report.warnings {
+        warn!("{}", warning);
+    }
+
+    let width = match report.summary.architecture {
+        Some(Architecture::X86) => 8,
+        _ => 16,
+    };
+    let annotate = |address: u64, module: Option<&str>| match module {
+        Some(module) => format!("{:0width$x} ({})", address, module),
+        None => format!("{:0width$x}", address),
+    };
 
-    // 格式化输出
     let mut output_lines = Vec::new();
-    output_lines.push(format!("# Dump File List: {}", search_dir.display()));
+    output_lines.push(format!("# Crash Dump Analysis: {}", params.dump_path));
+    output_lines.push(String::new());
+    output_lines.push(
+        "> Offline analysis (cdb.exe not found): no symbolication. \
+         Addresses are shown as module+offset."
+            .to_string(),
+    );
     output_lines.push(String::new());
 
-    if dump_files.is_empty() {
-        output_lines.push("No dump files found.".to_string());
-    } else {
-        output_lines.push(format!("Found {} dump files:", dump_files.len()));
+    output_lines.push("## Exception".to_string());
+    output_lines.push("```".to_string());
+    match &report.exception {
+        Some(exception) => {
+            let code = format!("{:08x}", exception.record.code);
+            output_lines.push(match exception.name {
+                Some(name) => format!("ExceptionCode: {} ({})", code, name),
+                None => format!("ExceptionCode: {}", code),
+            });
+            output_lines.push(format!(
+                "ExceptionAddress: {}",
+                annotate(exception.record.address, exception.module.as_deref())
+            ));
+            if let Some(description) = &exception.description {
+                output_lines.push(description.clone());
+            }
+            output_lines.push(format!("Thread: {:#x}", exception.record.thread_id));
+        }
+        None => output_lines.push("No exception record".to_string()),
+    }
+    output_lines.push("```".to_string());
+    output_lines.push(String::new());
+
+    if let Some(thread) = &report.faulting_thread {
+        output_lines.push("## Faulting Thread".to_string());
+        output_lines.push("```".to_string());
+        output_lines.push(match &thread.thread.name {
+            Some(name) => format!("Thread {:#x} ({})", thread.thread.thread_id, name),
+            None => format!("Thread {:#x}", thread.thread.thread_id),
+        });
+        if let Some(registers) = &thread.registers {
+            if let Some(ip) = registers.instruction_pointer {
+                output_lines.push(format!(
+                    "Instruction pointer: {}",
+                    annotate(ip, thread.instruction_module.as_deref())
+                ));
+            }
+            let values: Vec<String> = registers
+                .registers
+                .iter()
+                .map(|(name, value)| format!("{}={:0width$x}", name, value))
+                .collect();
+            output_lines.extend(values.chunks(3).map(|row| row.join(" ")));
+        }
+        output_lines.push("```".to_string());
         output_lines.push(String::new());
+    }
 
-        for (i, file_info) in dump_files.iter().enumerate() {
-            let size_mb = file_info.size_bytes as f64 / 1024.0 / 1024.0;
+    if let Some(stack) = &report.stack_memory {
+        output_lines.push("## Stack Memory".to_string());
+        output_lines.push("```".to_string());
+        for word in &stack.words {
+            let marker = if word.address == stack.stack_pointer {
+                " <- SP"
+            } else {
+                ""
+            };
             output_lines.push(format!(
-                "{}. {} ({:.2} MB)",
-                i + 1,
-                file_info.path.display(),
-                size_mb
+                "{:0width$x}  {}{}",
+                word.address,
+                annotate(word.value, word.module.as_deref()),
+                marker
             ));
         }
+        output_lines.push("```".to_string());
+        output_lines.push(String::new());
     }
 
-    let output = output_lines.join("\n");
+    output_lines.push("## Loaded Modules".to_string());
+    output_lines.push("```".to_string());
+    for module in &report.modules {
+        let mut line = format!(
+            "{:0width$x} {:0width$x}   {}",
+            module.base,
+            module.end(),
+            module.name()
+        );
+        if let Some(codeview) = &module.codeview {
+            line.push_str(&format!("   {} {}", codeview.pdb_file, codeview.symbol_key));
+        }
+        output_lines.push(line);
+    }
+    output_lines.push("```".to_string());
+    output_lines.push(String::new());
 
-    info!("Found {} dump files", dump_files.len());
+    if params.include_threads {
+        output_lines.push("## Thread List".to_string());
+        output_lines.push("```".to_string());
+        for (index, thread) in report.threads.iter().enumerate() {
+            let mut line = format!(
+                "{:3}  Id: {:x} Suspend: {} Teb: {:0width$x}",
+                index, thread.thread_id, thread.suspend_count, thread.teb
+            );
+            if let Some(name) = &thread.name {
+                line.push_str(&format!(" \"{}\"", name));
+            }
+            output_lines.push(line);
+        }
+        output_lines.push("```".to_string());
+        output_lines.push(String::new());
+    }
+
+    if !report.warnings.is_empty() {
+        output_lines.push("## Warnings".to_string());
+        output_lines.extend(report.warnings.iter().map(|w| format!("- {}", w)));
+    }
+
+    info!("Offline dump analysis completed");
+
+    let mut structured =
+        serde_json::to_value(&report).map_err(|e| ToolError::InternalError(e.to_string()))?;
+    structured["dump_path"] = serde_json::json!(params.dump_path);
+    structured["source"] = serde_json::json!("minidump");
+
+    Ok(ToolResponse::text(output_lines.join("\n")).with_structured(structured))
+}
+
+/// 不使用 CDB，直接从 minidump 读取模块列表
+///
+/// 离线读取时没有加载符号，每个模块都附带 CodeView 信息（PDB 文件名、GUID、Age），
+/// 可用于确定需要下载哪些符号；`missing_symbols` 过滤条件因此不排除任何模块。
+///
+/// # 参数
+/// * `dump_path` - 转储文件路径
+/// * `params` - 工具参数
+///
+/// # 错误
+/// 如果文件不是有效的 minidump 或模块列表损坏，返回错误
+fn list_modules_offline(
+    dump_path: &Path,
+    params: &ListModulesParams,
+) -> Result<ToolResponse, ToolError> {
+    warn!(
+        "CDB not available, reading modules from minidump: {}",
+        dump_path.display()
+    );
+
+    let mut dump = Minidump::open(dump_path)?;
+    let modules = dump.modules()?;
+    let unloaded_modules = dump.unloaded_modules().unwrap_or_else(|e| {
+        warn!("Failed to read unloaded module list: {}", e);
+        Vec::new()
+    });
+
+    let total = modules.len();
+    let modules: Vec<_> = modules
+        .into_iter()
+        .filter(|m| {
+            let info = m.to_module_info();
+            params
+                .module
+                .as_deref()
+                .is_none_or(|pattern| crate::utils::glob_match(pattern, &info.name))
+                && (!params.third_party || info.is_third_party())
+        })
+        .collect();
+
+    info!(
+        "Listed {} of {} module(s) from minidump",
+        modules.len(),
+        total
+    );
+
+    let structured = serde_json::json!({
+        "source": "minidump",
+        "total": total,
+        "matched": modules.len(),
+        "modules": modules,
+        "unloaded_modules": unloaded_modules,
+    });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text).with_structured(structured))
+}
+
+/// 不使用 CDB，直接从 minidump 读取线程列表
+///
+/// 线程序号按线程列表顺序编号，触发异常的线程标记为当前线程和最后事件线程；
+/// CPU 时间和栈顶帧需要调试器，离线时为空。
+///
+/// # 参数
+/// * `dump_path` - 转储文件路径
+/// * `params` - 工具参数
+///
+/// # 错误
+/// 如果文件不是有效的 minidump 或线程列表损坏，返回错误
+fn list_threads_offline(
+    dump_path: &Path,
+    params: &ListThreadsParams,
+) -> Result<ToolResponse, ToolError> {
+    warn!(
+        "CDB not available, reading threads from minidump: {}",
+        dump_path.display()
+    );
+    if params.include_cpu_times || params.include_top_frame {
+        debug!("CPU times and top frames are not available without CDB");
+    }
+
+    let mut dump = Minidump::open(dump_path)?;
+    let process_id = dump
+        .misc_info()
+        .ok()
+        .flatten()
+        .and_then(|m| m.process_id)
+        .unwrap_or_default();
+    let event_thread = dump.exception().ok().flatten().map(|e| e.thread_id);
 
-    Ok(ToolResponse::text(output))
+    let threads: Vec<_> = dump
+        .threads()?
+        .iter()
+        .enumerate()
+        .map(|(index, thread)| {
+            let mut info = thread.to_thread_info(index as u32, process_id);
+            info.current = event_thread == Some(thread.thread_id);
+            info.last_event = info.current;
+            info
+        })
+        .collect();
+    let summary = summarize_threads::<&str>(threads, &[], &[]);
+
+    info!("Listed {} thread(s) from minidump", summary.len());
+
+    let structured = serde_json::json!({
+        "source": "minidump",
+        "threads": summary,
+    });
+    let text = serde_json::to_string_pretty(&structured)
+        .map_err(|e| ToolError::InternalError(e.to_string()))?;
+
+    Ok(ToolResponse::text(text).with_structured(structured))
+}
+
+/// 根据转储文件路径或远程连接字符串获取（或创建）会话
+async fn get_session(
+    manager: &SessionManager,
+    dump_path: Option<&str>,
+    connection_string: Option<&str>,
+) -> Result<SharedBackend, ToolError> {
+    if let Some(dump_path) = dump_path {
+        Ok(manager
+            .get_or_create_dump_session(Path::new(dump_path))
+            .await?)
+    } else if let Some(connection_string) = connection_string {
+        Ok(manager
+            .get_or_create_remote_session(connection_string)
+            .await?)
+    } else {
+        Err(ToolError::InvalidParams(
+            "Either dump_path or connection_string must be provided".to_string(),
+        ))
+    }
+}
+
+/// 锁定会话；等待其他命令执行完成期间也可以取消
+async fn lock_session<'a>(
+    session: &'a SharedBackend,
+    cancel: &CancellationToken,
+) -> Result<MutexGuard<'a, Box<dyn DebuggerBackend>>, ToolError> {
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(CdbError::Cancelled.into()),
+        guard = session.lock() => Ok(guard),
+    }
+}
+
+/// 在会话中执行单条命令，同时取出会话重启提示
+async fn send_command(
+    session: &SharedBackend,
+    command: &str,
+    cancel: &CancellationToken,
+) -> Result<(Vec<String>, Option<String>), ToolError> {
+    let mut session_guard = lock_session(session, cancel).await?;
+    let notice = session_guard.take_restart_notice();
+    let output_lines = session_guard
+        .send_command_cancellable(command, None, cancel)
+        .await?;
+    Ok((output_lines, notice))
+}
+
+/// 执行报告中的一节命令
+///
+/// 命令失败时返回内层错误，由调用方写入报告；请求被取消时返回外层错误，中止整个工具调用。
+async fn report_command(
+    session_guard: &mut Box<dyn DebuggerBackend>,
+    command: &str,
+    cancel: &CancellationToken,
+) -> Result<Result<Vec<String>, CdbError>, ToolError> {
+    match session_guard
+        .send_command_cancellable(command, None, cancel)
+        .await
+    {
+        Err(CdbError::Cancelled) => Err(CdbError::Cancelled.into()),
+        result => Ok(result),
+    }
 }
 
 #[cfg(test)]
@@ -447,7 +1437,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_open_windbg_dump_file_not_found() {
-        let manager = Arc::new(SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false));
+        let manager = Arc::new(SessionManager::new(
+            Duration::from_secs(30),
+            Duration::from_secs(120),
+            false,
+        ));
         let params = OpenWindbgDumpParams {
             dump_path: "nonexistent.dmp".to_string(),
             include_stack_trace: false,
@@ -455,26 +1449,42 @@ mod tests {
             include_threads: false,
         };
 
-        let result = handle_open_windbg_dump(manager, params).await;
+        let result = handle_open_windbg_dump(manager, params, CancellationToken::new()).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_handle_run_windbg_cmd_invalid_params() {
-        let manager = Arc::new(SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false));
+        let manager = Arc::new(SessionManager::new(
+            Duration::from_secs(30),
+            Duration::from_secs(120),
+            false,
+        ));
         let params = RunWindbgCmdParams {
             dump_path: None,
             connection_string: None,
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
 
-        let result = handle_run_windbg_cmd(manager, params).await;
+        let result = handle_run_windbg_cmd(
+            manager,
+            &CommandPolicy::default(),
+            params,
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_handle_close_windbg_dump_not_found() {
-        let manager = Arc::new(SessionManager::new(Duration::from_secs(30), Duration::from_secs(120), false));
+        let manager = Arc::new(SessionManager::new(
+            Duration::from_secs(30),
+            Duration::from_secs(120),
+            false,
+        ));
         let params = CloseWindbgDumpParams {
             dump_path: "nonexistent.dmp".to_string(),
         };
@@ -488,6 +1498,7 @@ mod tests {
         let params = ListWindbgDumpsParams {
             directory_path: Some("nonexistent_dir".to_string()),
             recursive: false,
+            ..Default::default()
         };
 
         let result = handle_list_windbg_dumps(params).await;
@@ -0,0 +1,329 @@
+//! 崩溃签名与分桶模块
+//!
+//! 根据异常代码、故障模块和规范化后的栈顶若干帧生成稳定的崩溃签名，
+//! 并将多个转储按签名归入同一个桶，用于找出最常见的崩溃。
+
+use crate::parser::analyze::AnalyzeReport;
+use crate::parser::stack::StackFrame;
+use crate::parser::{hex, parse_hex};
+use crate::utils::format_timestamp;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// 无法解析的帧（裸地址）在签名中的占位符
+const UNKNOWN_FRAME: &str = "<unknown>";
+
+/// 崩溃签名
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct CrashSignature {
+    /// 异常代码（内核转储为蓝屏代码）
+    #[serde(with = "hex::option")]
+    pub exception_code: Option<u64>,
+    /// 故障模块名（小写）
+    pub faulting_module: Option<String>,
+    /// 规范化后的栈顶帧（去除偏移，连续重复的递归帧合并为一帧）
+    pub frames: Vec<String>,
+}
+
+impl CrashSignature {
+    /// 从 `!analyze -v` 结果生成签名
+    ///
+    /// 优先使用 STACK_TEXT；为空时使用 `stack`（例如 `kbn` 的解析结果）。
+    ///
+    /// # 参数
+    /// * `report` - `!analyze -v` 解析结果
+    /// * `stack` - 备用调用栈
+    /// * `depth` - 参与签名的栈顶帧数
+    pub fn from_report(report: &AnalyzeReport, stack: &[StackFrame], depth: usize) -> Self {
+        let call_sites: Vec<&str> = if report.stack_text.is_empty() {
+            stack.iter().map(|f| f.call_site.as_str()).collect()
+        } else {
+            report
+                .stack_text
+                .iter()
+                .map(|f| f.call_site.as_str())
+                .collect()
+        };
+
+        let mut frames: Vec<String> = Vec::with_capacity(depth);
+        for frame in call_sites.iter().map(|c| normalize_frame(c)) {
+            if frames.len() == depth {
+                break;
+            }
+            if frames.last() != Some(&frame) {
+                frames.push(frame);
+            }
+        }
+
+        let exception_code = report
+            .exception_code
+            .or_else(|| report.exception_record.as_ref().and_then(|r| r.code))
+            .or(report.bugcheck_code);
+        let faulting_module = report
+            .module_name
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .or_else(|| {
+                frames
+                    .iter()
+                    .find(|f| f.as_str() != UNKNOWN_FRAME)
+                    .map(|f| f.split('!').next().unwrap_or(f).to_string())
+            });
+
+        Self {
+            exception_code,
+            faulting_module,
+            frames,
+        }
+    }
+
+    /// 签名的稳定标识（FNV-1a 64 位哈希，与进程和平台无关）
+    pub fn id(&self) -> String {
+        let code = self
+            .exception_code
+            .map(|c| format!("{:#x}", c))
+            .unwrap_or_default();
+        let module = self.faulting_module.as_deref().unwrap_or_default();
+        let key = format!("{}|{}|{}", code, module, self.frames.join("|"));
+
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{:016x}", hash)
+    }
+}
+
+/// 规范化单个调用位置
+///
+/// 去除 `+0x..` 偏移、模块名转为小写；裸地址（没有符号）替换为 `<unknown>`，
+/// 使同一位置的崩溃在不同 ASLR 基址和构建之间得到相同的签名。
+///
+/// # 参数
+/// * `call_site` - 调用位置文本（例如 `contoso!Widget::Render+0x24`）
+pub fn normalize_frame(call_site: &str) -> String {
+    let call_site = call_site.trim();
+    // 裸地址：`0x0` 或完整宽度的 ``00007ffb`1c2a7034``（避免把 `cafe` 这类模块名误判为地址）
+    let is_address =
+        (call_site.starts_with("0x") || call_site.len() >= 8) && parse_hex(call_site).is_some();
+    if call_site.is_empty() || is_address {
+        return UNKNOWN_FRAME.to_string();
+    }
+
+    let symbol = match call_site.rsplit_once('+') {
+        Some((symbol, offset)) if parse_hex(offset).is_some() => symbol,
+        _ => call_site,
+    };
+
+    match symbol.split_once('!') {
+        Some((module, function)) => format!("{}!{}", module.to_ascii_lowercase(), function),
+        None => symbol.to_ascii_lowercase(),
+    }
+}
+
+/// 已分析的转储
+#[derive(Debug, Clone)]
+pub struct TriagedDump {
+    /// 转储文件路径
+    pub path: PathBuf,
+    /// 转储文件的最后修改时间
+    pub modified: Option<SystemTime>,
+    /// 崩溃签名
+    pub signature: CrashSignature,
+}
+
+/// 崩溃桶
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrashBucket {
+    /// 签名标识
+    pub id: String,
+    /// 崩溃签名
+    #[serde(flatten)]
+    pub signature: CrashSignature,
+    /// 桶内转储数量
+    pub count: usize,
+    /// 最早的转储时间（RFC 3339）
+    pub first_seen: Option<String>,
+    /// 最晚的转储时间（RFC 3339）
+    pub last_seen: Option<String>,
+    /// 桶内转储路径（按时间排序）
+    pub dumps: Vec<PathBuf>,
+}
+
+/// 将转储按签名分桶
+///
+/// # 参数
+/// * `dumps` - 已分析的转储
+///
+/// # 返回
+/// 返回按数量降序排序的桶；数量相同时最近出现的桶排在前面
+pub fn bucket_dumps(dumps: Vec<TriagedDump>) -> Vec<CrashBucket> {
+    let mut groups: HashMap<CrashSignature, Vec<TriagedDump>> = HashMap::new();
+    for dump in dumps {
+        groups.entry(dump.signature.clone()).or_default().push(dump);
+    }
+
+    let mut buckets: Vec<(Option<SystemTime>, CrashBucket)> = groups
+        .into_iter()
+        .map(|(signature, mut dumps)| {
+            dumps.sort_by(|a, b| a.modified.cmp(&b.modified).then(a.path.cmp(&b.path)));
+            let first_seen = dumps.iter().filter_map(|d| d.modified).min();
+            let last_seen = dumps.iter().filter_map(|d| d.modified).max();
+            let bucket = CrashBucket {
+                id: signature.id(),
+                count: dumps.len(),
+                first_seen: first_seen.map(format_timestamp),
+                last_seen: last_seen.map(format_timestamp),
+                dumps: dumps.into_iter().map(|d| d.path).collect(),
+                signature,
+            };
+            (last_seen, bucket)
+        })
+        .collect();
+
+    buckets.sort_by(|(a_seen, a), (b_seen, b)| {
+        b.count
+            .cmp(&a.count)
+            .then(b_seen.cmp(a_seen))
+            .then(a.id.cmp(&b.id))
+    });
+    buckets.into_iter().map(|(_, bucket)| bucket).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::analyze::parse_analyze;
+    use crate::parser::stack::parse_stack;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn report(sample: &str) -> AnalyzeReport {
+        let lines: Vec<&str> = sample.lines().collect();
+        parse_analyze(&lines)
+    }
+
+    fn dump(path: &str, secs: u64, signature: &CrashSignature) -> TriagedDump {
+        TriagedDump {
+            path: PathBuf::from(path),
+            modified: Some(UNIX_EPOCH + Duration::from_secs(secs)),
+            signature: signature.clone(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_frame() {
+        assert_eq!(
+            normalize_frame("contoso!Widget::Render+0x24"),
+            "contoso!Widget::Render"
+        );
+        assert_eq!(normalize_frame("Contoso!main+120"), "contoso!main");
+        assert_eq!(normalize_frame("ThirdParty+0x1a2b"), "thirdparty");
+        assert_eq!(
+            normalize_frame("ntdll!NtWaitForSingleObject"),
+            "ntdll!NtWaitForSingleObject"
+        );
+        assert_eq!(normalize_frame("0x0"), UNKNOWN_FRAME);
+        assert_eq!(normalize_frame("00007ffb`1c2a7034"), UNKNOWN_FRAME);
+        assert_eq!(normalize_frame("contoso!operator+"), "contoso!operator+");
+    }
+
+    #[test]
+    fn test_signature_from_report() {
+        let report = report(include_str!("../tests/fixtures/analyze/user_av_x64.txt"));
+        let signature = CrashSignature::from_report(&report, &[], 3);
+        assert_eq!(signature.exception_code, Some(0xc000_0005));
+        assert_eq!(signature.faulting_module.as_deref(), Some("contoso"));
+        assert_eq!(
+            signature.frames,
+            [
+                "contoso!Widget::Render",
+                "contoso!Window::Paint",
+                "contoso!main"
+            ]
+        );
+        assert_eq!(signature.id().len(), 16);
+        assert_eq!(signature.id(), signature.clone().id());
+
+        let json = serde_json::to_value(&signature).unwrap();
+        assert_eq!(json["exception_code"], "0xc0000005");
+    }
+
+    #[test]
+    fn test_signature_falls_back_to_stack() {
+        let stack = parse_stack(
+            &include_str!("../tests/fixtures/cdb/kbn.txt")
+                .lines()
+                .collect::<Vec<_>>(),
+        );
+        let report = AnalyzeReport {
+            exception_code: Some(0x8000_0003),
+            ..Default::default()
+        };
+        let signature = CrashSignature::from_report(&report, &stack[0].frames, 2);
+        assert_eq!(signature.frames.len(), 2);
+        assert_eq!(signature.frames[0], "contoso!Widget::Render");
+        assert_eq!(signature.faulting_module.as_deref(), Some("contoso"));
+    }
+
+    #[test]
+    fn test_signature_collapses_recursion() {
+        let stack: Vec<StackFrame> = [
+            "app!Recurse+0x10",
+            "app!Recurse+0x10",
+            "app!Recurse+0x10",
+            "app!main+0x5",
+        ]
+        .iter()
+        .map(|c| StackFrame {
+            call_site: c.to_string(),
+            ..Default::default()
+        })
+        .collect();
+        let signature = CrashSignature::from_report(&AnalyzeReport::default(), &stack, 5);
+        assert_eq!(signature.frames, ["app!Recurse", "app!main"]);
+        assert_eq!(signature.exception_code, None);
+    }
+
+    #[test]
+    fn test_bucket_dumps() {
+        let av = CrashSignature {
+            exception_code: Some(0xc000_0005),
+            faulting_module: Some("contoso".to_string()),
+            frames: vec!["contoso!Widget::Render".to_string()],
+        };
+        let stack_overflow = CrashSignature {
+            exception_code: Some(0xc000_00fd),
+            faulting_module: Some("contoso".to_string()),
+            frames: vec!["contoso!Recurse".to_string()],
+        };
+
+        let buckets = bucket_dumps(vec![
+            dump("b.dmp", 200, &av),
+            dump("c.dmp", 300, &stack_overflow),
+            dump("a.dmp", 100, &av),
+        ]);
+        assert_eq!(buckets.len(), 2);
+
+        assert_eq!(buckets[0].signature, av);
+        assert_eq!(buckets[0].id, av.id());
+        assert_eq!(buckets[0].count, 2);
+        assert_eq!(
+            buckets[0].first_seen.as_deref(),
+            Some("1970-01-01T00:01:40Z")
+        );
+        assert_eq!(
+            buckets[0].last_seen.as_deref(),
+            Some("1970-01-01T00:03:20Z")
+        );
+        assert_eq!(
+            buckets[0].dumps,
+            [PathBuf::from("a.dmp"), PathBuf::from("b.dmp")]
+        );
+
+        assert_eq!(buckets[1].count, 1);
+        let json = serde_json::to_value(&buckets[1]).unwrap();
+        assert_eq!(json["exception_code"], "0xc00000fd");
+        assert_eq!(json["frames"][0], "contoso!Recurse");
+    }
+}
@@ -3,12 +3,16 @@
 //! 本模块包含用于 MCP 通信和工具参数定义的所有数据结构。
 
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
 
 /// MCP 工具响应
 #[derive(Debug, Serialize, Clone)]
 pub struct ToolResponse {
     /// 响应内容列表
     pub content: Vec<ContentItem>,
+    /// 结构化内容（作为 MCP `structured_content` 返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
 }
 
 impl ToolResponse {
@@ -16,6 +20,7 @@ impl ToolResponse {
     pub fn text(text: impl Into<String>) -> Self {
         Self {
             content: vec![ContentItem::Text { text: text.into() }],
+            structured_content: None,
         }
     }
 
@@ -26,8 +31,23 @@ impl ToolResponse {
                 .into_iter()
                 .map(|text| ContentItem::Text { text })
                 .collect(),
+            structured_content: None,
         }
     }
+
+    /// 附加结构化内容
+    pub fn with_structured(mut self, value: serde_json::Value) -> Self {
+        self.structured_content = Some(value);
+        self
+    }
+
+    /// 在内容最前面插入提示（例如会话已重新启动）；`notice` 为 None 时保持不变
+    pub fn with_notice(mut self, notice: Option<String>) -> Self {
+        if let Some(text) = notice {
+            self.content.insert(0, ContentItem::Text { text });
+        }
+        self
+    }
 }
 
 /// 内容项类型
@@ -80,6 +100,9 @@ pub struct OpenWindbgRemoteParams {
     /// 是否包含线程信息
     #[serde(default)]
     pub include_threads: bool,
+    /// 是否以只读模式打开（拒绝执行控制和修改内存的命令）
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 /// run_windbg_cmd 工具的参数
@@ -91,16 +114,38 @@ pub struct RunWindbgCmdParams {
     pub connection_string: Option<String>,
     /// 要执行的 WinDbg 命令
     pub command: String,
+    /// 是否附加命令执行期间调试器写入 stderr 的内容
+    #[serde(default)]
+    pub include_stderr: bool,
+    /// 本次命令的超时时间（秒），未提供时按命令类别选择
+    pub timeout_secs: Option<u64>,
 }
 
 impl RunWindbgCmdParams {
-    /// 验证参数：确保 dump_path 和 connection_string 互斥
+    /// 超时时间上限（秒）
+    pub const MAX_TIMEOUT_SECS: u64 = 3600;
+
+    /// 验证参数：确保 dump_path 和 connection_string 互斥，命令只占一行，超时时间在允许范围内
     pub fn validate(&self) -> Result<(), String> {
-        match (&self.dump_path, &self.connection_string) {
-            (None, None) => Err("Either dump_path or connection_string must be provided".to_string()),
-            (Some(_), Some(_)) => Err("dump_path and connection_string cannot be provided together".to_string()),
-            _ => Ok(()),
+        validate_target(&self.dump_path, &self.connection_string)?;
+        // CDB 逐行执行输入，换行符会让后续内容作为独立命令执行并绕过命令策略
+        if self.command.chars().any(|c| c.is_control() && c != '\t') {
+            return Err("command must not contain line breaks or control characters".to_string());
         }
+        if let Some(secs) = self.timeout_secs {
+            if secs == 0 || secs > Self::MAX_TIMEOUT_SECS {
+                return Err(format!(
+                    "timeout_secs must be between 1 and {}",
+                    Self::MAX_TIMEOUT_SECS
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 本次命令的超时时间（未提供时返回 None）
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
     }
 
     /// 获取会话标识符（转储路径或连接字符串）
@@ -126,13 +171,302 @@ pub struct CloseWindbgRemoteParams {
 }
 
 /// list_windbg_dumps 工具的参数
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct ListWindbgDumpsParams {
     /// 要搜索的目录路径（可选，默认使用系统转储目录）
     pub directory_path: Option<String>,
     /// 是否递归搜索子目录
     #[serde(default)]
     pub recursive: bool,
+    /// 只列出此时间之后修改的转储（RFC 3339 时间或日期）
+    pub since: Option<String>,
+    /// 只列出此时间之前修改的转储（日期表示包含当天）
+    pub until: Option<String>,
+    /// 文件名通配符（例如 `contoso*.dmp`）
+    pub name_glob: Option<String>,
+    /// 最小文件大小（字节）
+    pub min_size: Option<u64>,
+    /// 最大文件大小（字节）
+    pub max_size: Option<u64>,
+    /// 排序方式
+    #[serde(default)]
+    pub sort_by: DumpSortBy,
+    /// 最多返回的转储数量
+    pub limit: Option<usize>,
+}
+
+impl ListWindbgDumpsParams {
+    /// 验证参数
+    pub fn validate(&self) -> Result<(), String> {
+        let (since, until) = self.time_range()?;
+        if let (Some(since), Some(until)) = (since, until) {
+            if since > until {
+                return Err("since must not be later than until".to_string());
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_size, self.max_size) {
+            if min > max {
+                return Err("min_size must not be greater than max_size".to_string());
+            }
+        }
+        if self.limit == Some(0) {
+            return Err("limit must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 解析 since/until 时间范围（均为闭区间）
+    pub fn time_range(&self) -> Result<(Option<SystemTime>, Option<SystemTime>), String> {
+        let parse = |name: &str, text: &Option<String>| {
+            text.as_deref()
+                .map(|t| {
+                    crate::utils::parse_timestamp(t)
+                        .ok_or_else(|| format!("Invalid {} time: {}", name, t))
+                })
+                .transpose()
+        };
+        let since = parse("since", &self.since)?;
+        let mut until = parse("until", &self.until)?;
+        // 只有日期时包含当天
+        if self.until.as_deref().is_some_and(|t| !t.contains(':')) {
+            until = until.map(|t| t + Duration::from_secs(86_399));
+        }
+        Ok((since, until))
+    }
+}
+
+/// list_windbg_dumps 的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpSortBy {
+    /// 按文件大小降序
+    #[default]
+    Size,
+    /// 按修改时间降序（最新的在前）
+    Mtime,
+    /// 按路径升序
+    Name,
+}
+
+/// triage_dumps 工具的参数
+#[derive(Debug, Deserialize)]
+pub struct TriageDumpsParams {
+    /// 要分诊的目录路径（可选，默认使用系统转储目录）
+    pub directory_path: Option<String>,
+    /// 是否递归搜索子目录
+    #[serde(default)]
+    pub recursive: bool,
+    /// 参与签名的栈顶帧数
+    #[serde(default = "default_signature_frames")]
+    pub frames: usize,
+    /// 最多分析的转储数量（按修改时间取最新的）
+    pub max_dumps: Option<usize>,
+}
+
+impl TriageDumpsParams {
+    /// 签名帧数上限
+    pub const MAX_FRAMES: usize = 32;
+
+    /// 验证参数
+    pub fn validate(&self) -> Result<(), String> {
+        if self.frames == 0 || self.frames > Self::MAX_FRAMES {
+            return Err(format!("frames must be between 1 and {}", Self::MAX_FRAMES));
+        }
+        if self.max_dumps == Some(0) {
+            return Err("max_dumps must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_signature_frames() -> usize {
+    5
+}
+
+/// get_stack 工具的参数
+#[derive(Debug, Deserialize)]
+pub struct GetStackParams {
+    /// 转储文件路径（与 connection_string 互斥）
+    pub dump_path: Option<String>,
+    /// 远程连接字符串（与 dump_path 互斥）
+    pub connection_string: Option<String>,
+    /// 线程序号（默认为当前线程）
+    pub thread: Option<u32>,
+    /// 是否获取所有线程的调用栈
+    #[serde(default)]
+    pub all_threads: bool,
+    /// 每个线程的最大帧数
+    pub max_frames: Option<u32>,
+}
+
+impl GetStackParams {
+    /// 验证参数：会话标识互斥，thread 与 all_threads 互斥
+    pub fn validate(&self) -> Result<(), String> {
+        validate_target(&self.dump_path, &self.connection_string)?;
+        if self.thread.is_some() && self.all_threads {
+            return Err("thread and all_threads cannot be provided together".to_string());
+        }
+        if self.max_frames == Some(0) {
+            return Err("max_frames must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 构建 CDB 命令（`kbn` 输出帧号、参数和源文件信息）
+    pub fn command(&self) -> String {
+        let mut command = match (self.all_threads, self.thread) {
+            (true, _) => "~* kbn".to_string(),
+            (false, Some(thread)) => format!("~{} kbn", thread),
+            (false, None) => "kbn".to_string(),
+        };
+        if let Some(max_frames) = self.max_frames {
+            command.push_str(&format!(" {}", max_frames));
+        }
+        command
+    }
+}
+
+/// list_modules 工具的参数
+#[derive(Debug, Deserialize)]
+pub struct ListModulesParams {
+    /// 转储文件路径（与 connection_string 互斥）
+    pub dump_path: Option<String>,
+    /// 远程连接字符串（与 dump_path 互斥）
+    pub connection_string: Option<String>,
+    /// 模块名模式（使用 `lmvm`，支持 `*` 通配符）
+    pub module: Option<String>,
+    /// 只返回缺少符号的模块（仅导出符号或没有符号）
+    #[serde(default)]
+    pub missing_symbols: bool,
+    /// 只返回第三方（非 Microsoft）模块
+    #[serde(default)]
+    pub third_party: bool,
+}
+
+impl ListModulesParams {
+    /// 验证参数：会话标识互斥，模块名只能包含安全字符
+    pub fn validate(&self) -> Result<(), String> {
+        validate_target(&self.dump_path, &self.connection_string)?;
+        if let Some(module) = &self.module {
+            let valid = !module.is_empty()
+                && module
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '*' | '?'));
+            if !valid {
+                return Err(format!("Invalid module name pattern: {}", module));
+            }
+        }
+        Ok(())
+    }
+
+    /// 构建 CDB 命令
+    pub fn command(&self) -> String {
+        match &self.module {
+            Some(module) => format!("lmvm {}", module),
+            None => "lm v".to_string(),
+        }
+    }
+}
+
+/// list_threads 工具的参数
+#[derive(Debug, Deserialize)]
+pub struct ListThreadsParams {
+    /// 转储文件路径（与 connection_string 互斥）
+    pub dump_path: Option<String>,
+    /// 远程连接字符串（与 dump_path 互斥）
+    pub connection_string: Option<String>,
+    /// 是否合并 `!runaway` 的 CPU 时间
+    #[serde(default = "default_true")]
+    pub include_cpu_times: bool,
+    /// 是否合并 `~*k1` 的栈顶帧
+    #[serde(default = "default_true")]
+    pub include_top_frame: bool,
+}
+
+impl ListThreadsParams {
+    /// 验证参数：确保 dump_path 和 connection_string 互斥
+    pub fn validate(&self) -> Result<(), String> {
+        validate_target(&self.dump_path, &self.connection_string)
+    }
+}
+
+/// get_registers 工具的参数
+#[derive(Debug, Deserialize)]
+pub struct GetRegistersParams {
+    /// 转储文件路径（与 connection_string 互斥）
+    pub dump_path: Option<String>,
+    /// 远程连接字符串（与 dump_path 互斥）
+    pub connection_string: Option<String>,
+    /// 线程序号（默认为当前线程）
+    pub thread: Option<u32>,
+    /// 是否切换到异常上下文（`.ecxr`）
+    #[serde(default)]
+    pub exception_context: bool,
+    /// 上下文记录地址（`.cxr <address>`）
+    pub context_record: Option<String>,
+}
+
+impl GetRegistersParams {
+    /// 验证参数：会话标识互斥，thread、exception_context、context_record 三者互斥
+    pub fn validate(&self) -> Result<(), String> {
+        validate_target(&self.dump_path, &self.connection_string)?;
+        let selectors = [
+            self.thread.is_some(),
+            self.exception_context,
+            self.context_record.is_some(),
+        ];
+        if selectors.iter().filter(|&&s| s).count() > 1 {
+            return Err(
+                "thread, exception_context and context_record cannot be provided together"
+                    .to_string(),
+            );
+        }
+        if let Some(address) = &self.context_record {
+            if crate::parser::parse_hex(address).is_none() {
+                return Err(format!("Invalid context record address: {}", address));
+            }
+        }
+        Ok(())
+    }
+
+    /// 构建 CDB 命令（`.ecxr` 和 `.cxr` 本身会输出切换后的寄存器）
+    pub fn command(&self) -> String {
+        if self.exception_context {
+            ".ecxr".to_string()
+        } else if let Some(address) = &self.context_record {
+            format!(".cxr {}", address)
+        } else if let Some(thread) = self.thread {
+            format!("~{} r", thread)
+        } else {
+            "r".to_string()
+        }
+    }
+
+    /// 执行后是否需要用 `.cxr` 恢复默认上下文
+    ///
+    /// 只有调用方指定的上下文记录会被恢复；`.ecxr` 切换到的异常上下文通常正是会话需要的上下文，
+    /// 恢复反而会覆盖用户之前通过 `.cxr` / `.ecxr` 设置的上下文。
+    pub fn restores_context(&self) -> bool {
+        self.context_record.is_some()
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 验证会话标识：dump_path 和 connection_string 必须且只能提供一个
+fn validate_target(
+    dump_path: &Option<String>,
+    connection_string: &Option<String>,
+) -> Result<(), String> {
+    match (dump_path, connection_string) {
+        (None, None) => Err("Either dump_path or connection_string must be provided".to_string()),
+        (Some(_), Some(_)) => {
+            Err("dump_path and connection_string cannot be provided together".to_string())
+        }
+        _ => Ok(()),
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +495,8 @@ mod tests {
             dump_path: None,
             connection_string: None,
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert!(params.validate().is_err());
 
@@ -169,6 +505,8 @@ mod tests {
             dump_path: Some("test.dmp".to_string()),
             connection_string: Some("tcp:Port=5005".to_string()),
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert!(params.validate().is_err());
 
@@ -177,6 +515,8 @@ mod tests {
             dump_path: Some("test.dmp".to_string()),
             connection_string: None,
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert!(params.validate().is_ok());
 
@@ -185,8 +525,44 @@ mod tests {
             dump_path: None,
             connection_string: Some("tcp:Port=5005".to_string()),
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert!(params.validate().is_ok());
+
+        // 命令不能包含换行符或其他控制字符
+        for (command, valid) in [
+            ("k\n.shell dir", false),
+            ("k\r.shell dir", false),
+            ("k\u{0}", false),
+            ("dq @rsp\tL8", true),
+        ] {
+            let params = RunWindbgCmdParams {
+                dump_path: Some("test.dmp".to_string()),
+                connection_string: None,
+                command: command.to_string(),
+                include_stderr: false,
+                timeout_secs: None,
+            };
+            assert_eq!(params.validate().is_ok(), valid, "{:?}", command);
+        }
+
+        // 超时时间必须在 1 到 3600 秒之间
+        for (timeout_secs, valid) in [
+            (Some(1), true),
+            (Some(3600), true),
+            (Some(0), false),
+            (Some(3601), false),
+        ] {
+            let params = RunWindbgCmdParams {
+                dump_path: Some("test.dmp".to_string()),
+                connection_string: None,
+                command: "!analyze -v".to_string(),
+                include_stderr: false,
+                timeout_secs,
+            };
+            assert_eq!(params.validate().is_ok(), valid, "{:?}", timeout_secs);
+        }
     }
 
     #[test]
@@ -195,6 +571,8 @@ mod tests {
             dump_path: Some("test.dmp".to_string()),
             connection_string: None,
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert_eq!(params.session_identifier(), Some("test.dmp"));
 
@@ -202,6 +580,8 @@ mod tests {
             dump_path: None,
             connection_string: Some("tcp:Port=5005".to_string()),
             command: "test".to_string(),
+            include_stderr: false,
+            timeout_secs: None,
         };
         assert_eq!(params.session_identifier(), Some("tcp:Port=5005"));
     }
@@ -226,6 +606,189 @@ mod tests {
         let params: ListWindbgDumpsParams = serde_json::from_str(json).unwrap();
         assert!(params.directory_path.is_none());
         assert!(params.recursive);
+        assert_eq!(params.sort_by, DumpSortBy::Size);
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_list_windbg_dumps_params_filters() {
+        let params: ListWindbgDumpsParams = serde_json::from_value(serde_json::json!({
+            "since": "2026-10-01",
+            "until": "2026-10-14",
+            "sort_by": "mtime",
+            "limit": 10
+        }))
+        .unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.sort_by, DumpSortBy::Mtime);
+        let (since, until) = params.time_range().unwrap();
+        assert_eq!(
+            since.map(crate::utils::format_timestamp).as_deref(),
+            Some("2026-10-01T00:00:00Z")
+        );
+        assert_eq!(
+            until.map(crate::utils::format_timestamp).as_deref(),
+            Some("2026-10-14T23:59:59Z")
+        );
+
+        let params: ListWindbgDumpsParams =
+            serde_json::from_value(serde_json::json!({ "until": "2026-10-14T08:00:00Z" })).unwrap();
+        let (_, until) = params.time_range().unwrap();
+        assert_eq!(
+            until.map(crate::utils::format_timestamp).as_deref(),
+            Some("2026-10-14T08:00:00Z")
+        );
+
+        for invalid in [
+            serde_json::json!({ "since": "last week" }),
+            serde_json::json!({ "since": "2026-10-14", "until": "2026-10-01" }),
+            serde_json::json!({ "min_size": 10, "max_size": 1 }),
+            serde_json::json!({ "limit": 0 }),
+        ] {
+            let params: ListWindbgDumpsParams = serde_json::from_value(invalid).unwrap();
+            assert!(params.validate().is_err());
+        }
+        assert!(serde_json::from_value::<ListWindbgDumpsParams>(
+            serde_json::json!({ "sort_by": "color" })
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_triage_dumps_params() {
+        let params: TriageDumpsParams = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(params.frames, 5);
+        assert!(!params.recursive);
+        assert!(params.validate().is_ok());
+
+        let params: TriageDumpsParams =
+            serde_json::from_value(serde_json::json!({ "frames": 0 })).unwrap();
+        assert!(params.validate().is_err());
+
+        let params: TriageDumpsParams =
+            serde_json::from_value(serde_json::json!({ "max_dumps": 0 })).unwrap();
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_get_stack_params() {
+        let params: GetStackParams =
+            serde_json::from_value(serde_json::json!({ "dump_path": "a.dmp" })).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.command(), "kbn");
+
+        let params: GetStackParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "thread": 3,
+            "max_frames": 20
+        }))
+        .unwrap();
+        assert_eq!(params.command(), "~3 kbn 20");
+
+        let params: GetStackParams = serde_json::from_value(serde_json::json!({
+            "connection_string": "tcp:Port=5005",
+            "all_threads": true
+        }))
+        .unwrap();
+        assert_eq!(params.command(), "~* kbn");
+
+        let params: GetStackParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "thread": 1,
+            "all_threads": true
+        }))
+        .unwrap();
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_list_modules_params() {
+        let params: ListModulesParams =
+            serde_json::from_value(serde_json::json!({ "dump_path": "a.dmp" })).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.command(), "lm v");
+
+        let params: ListModulesParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "module": "contoso*"
+        }))
+        .unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.command(), "lmvm contoso*");
+
+        let params: ListModulesParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "module": "ntdll; .kill"
+        }))
+        .unwrap();
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_list_threads_params_defaults() {
+        let params: ListThreadsParams =
+            serde_json::from_value(serde_json::json!({ "dump_path": "a.dmp" })).unwrap();
+        assert!(params.validate().is_ok());
+        assert!(params.include_cpu_times);
+        assert!(params.include_top_frame);
+    }
+
+    #[test]
+    fn test_get_registers_params() {
+        let params: GetRegistersParams =
+            serde_json::from_value(serde_json::json!({ "dump_path": "a.dmp" })).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.command(), "r");
+        assert!(!params.restores_context());
+
+        let params: GetRegistersParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "thread": 2
+        }))
+        .unwrap();
+        assert_eq!(params.command(), "~2 r");
+
+        let params: GetRegistersParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "context_record": "000000a5`e6cfe1c0"
+        }))
+        .unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.command(), ".cxr 000000a5`e6cfe1c0");
+        assert!(params.restores_context());
+
+        let params: GetRegistersParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "exception_context": true
+        }))
+        .unwrap();
+        assert_eq!(params.command(), ".ecxr");
+        assert!(!params.restores_context());
+
+        let params: GetRegistersParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "thread": 1,
+            "exception_context": true
+        }))
+        .unwrap();
+        assert!(params.validate().is_err());
+
+        let params: GetRegistersParams = serde_json::from_value(serde_json::json!({
+            "dump_path": "a.dmp",
+            "context_record": "0x1234; .kill"
+        }))
+        .unwrap();
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_tool_response_with_structured() {
+        let response = ToolResponse::text("text").with_structured(serde_json::json!({"a": 1}));
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["structured_content"]["a"], 1);
+
+        let json = serde_json::to_value(ToolResponse::text("text")).unwrap();
+        assert!(json.get("structured_content").is_none());
     }
 
     #[test]
@@ -3,6 +3,7 @@
 //! 提供 CDB 可执行文件查找、Windows 注册表访问和文件搜索等实用功能。
 
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// 转储文件信息
 #[derive(Debug, Clone)]
@@ -11,6 +12,8 @@ pub struct DumpFileInfo {
     pub path: PathBuf,
     /// 文件大小（字节）
     pub size_bytes: u64,
+    /// 最后修改时间
+    pub modified: Option<SystemTime>,
 }
 
 /// 查找 CDB 可执行文件
@@ -127,6 +130,112 @@ pub fn get_local_dumps_path() -> Option<PathBuf> {
     None
 }
 
+/// 读取进程累计消耗的 CPU 时间（用户态 + 内核态）
+///
+/// # 参数
+/// * `pid` - 进程 ID
+///
+/// # 返回
+/// 进程不存在或无权访问时返回 None
+#[cfg(windows)]
+pub fn process_cpu_time(pid: u32) -> Option<Duration> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let zero = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+
+    // SAFETY: 句柄在使用后立即关闭，输出参数都指向有效的 FILETIME
+    let ok = unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let ok = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user);
+        CloseHandle(handle);
+        ok
+    };
+    if ok == 0 {
+        return None;
+    }
+
+    // FILETIME 以 100 纳秒为单位
+    let ticks = |t: FILETIME| (u64::from(t.dwHighDateTime) << 32) | u64::from(t.dwLowDateTime);
+    Some(Duration::from_nanos((ticks(kernel) + ticks(user)) * 100))
+}
+
+/// 读取进程累计消耗的 CPU 时间（用户态 + 内核态，来自 `/proc/<pid>/stat`）
+#[cfg(target_os = "linux")]
+pub fn process_cpu_time(pid: u32) -> Option<Duration> {
+    // USER_HZ 在 Linux 上固定为 100
+    const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 进程名可能包含空格和括号，从最后一个 `)` 之后开始按字段解析
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    // 去掉 pid 和进程名后，utime 和 stime 是第 12、13 个字段
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(Duration::from_millis(
+        (utime + stime) * 1_000 / CLOCK_TICKS_PER_SEC,
+    ))
+}
+
+/// 其他平台的占位实现
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn process_cpu_time(_pid: u32) -> Option<Duration> {
+    None
+}
+
+/// 向调试器进程发送中断请求（相当于在控制台按下 Ctrl+Break）
+///
+/// Windows 上向进程组发送 `CTRL_BREAK_EVENT`（CDB 进程以 `CREATE_NEW_PROCESS_GROUP` 启动，
+/// 进程组 ID 即进程 ID），CDB 收到后中止当前命令或中断正在运行的目标。
+/// 控制台事件只能发给与调用者共用控制台的进程；服务器没有控制台时（例如由编辑器通过 stdio 启动）
+/// 调用会失败或事件不会送达，调用者需要自行处理中断不生效的情况。
+///
+/// # 参数
+/// * `pid` - 进程 ID
+///
+/// # 错误
+/// 如果无法发送中断，返回系统错误
+#[cfg(windows)]
+pub fn interrupt_process(pid: u32) -> std::io::Result<()> {
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+    // SAFETY: 只传递整数参数
+    if unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// 向调试器进程发送 `SIGINT`（CDB 替身程序收到后中止当前命令）
+#[cfg(unix)]
+pub fn interrupt_process(pid: u32) -> std::io::Result<()> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: kill 只接收整数参数
+    if unsafe { libc::kill(pid, libc::SIGINT) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// 其他平台的占位实现
+#[cfg(not(any(windows, unix)))]
+pub fn interrupt_process(_pid: u32) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "interrupting processes is not supported on this platform",
+    ))
+}
+
 /// 在目录中搜索转储文件
 ///
 /// 搜索指定目录中的 .dmp 文件。
@@ -163,7 +272,7 @@ pub fn find_dump_files(
     search_directory(directory, recursive, &mut dump_files)?;
 
     // 按文件大小降序排序（大文件通常更重要）
-    dump_files.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+    dump_files.sort_by_key(|f| std::cmp::Reverse(f.size_bytes));
 
     Ok(dump_files)
 }
@@ -187,6 +296,7 @@ fn search_directory(
                         dump_files.push(DumpFileInfo {
                             path: path.clone(),
                             size_bytes: metadata.len(),
+                            modified: metadata.modified().ok(),
                         });
                     }
                 }
@@ -200,6 +310,140 @@ fn search_directory(
     Ok(())
 }
 
+/// 将时间格式化为 RFC 3339 UTC 字符串（例如 `2026-10-14T18:20:31Z`）
+///
+/// # 参数
+/// * `time` - 要格式化的时间（早于 Unix 纪元时按纪元处理）
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // 由天数推算公历日期（Howard Hinnant 的 civil_from_days 算法）
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// 解析 RFC 3339 UTC 时间（`2026-10-14T18:20:31Z`）或日期（`2026-10-14`，视为当天 00:00:00）
+///
+/// # 参数
+/// * `text` - 时间文本（`T` 也可以写作空格，`Z` 可省略）
+///
+/// # 返回
+/// 格式无效时返回 None
+pub fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let text = text.trim();
+    let text = text.strip_suffix('Z').unwrap_or(text);
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut seconds = 0;
+    if let Some(time) = time {
+        let mut parts = time.split(':');
+        let hours: u64 = parts.next()?.parse().ok()?;
+        let minutes: u64 = parts.next()?.parse().ok()?;
+        let secs: u64 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+        if parts.next().is_some() || hours > 23 || minutes > 59 || secs > 59 {
+            return None;
+        }
+        seconds = hours * 3_600 + minutes * 60 + secs;
+    }
+
+    // 由公历日期推算天数（Howard Hinnant 的 days_from_civil 算法）
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + seconds))
+}
+
+/// 不区分大小写的通配符匹配（`*` 匹配任意字符序列，`?` 匹配单个字符）
+///
+/// # 参数
+/// * `pattern` - 通配符模式（例如 `contoso*.dmp`）
+/// * `text` - 要匹配的文本
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // 回溯到最近一个 `*` 的贪心匹配
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 从 Windows 错误报告的转储文件名（`<进程名>.<PID>.dmp`）中提取进程名
+///
+/// # 参数
+/// * `path` - 转储文件路径
+///
+/// # 返回
+/// 文件名不符合该格式时返回 None
+pub fn process_name_from_dump_path(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let (name, pid) = stem.rsplit_once('.')?;
+    let is_pid = !pid.is_empty() && pid.chars().all(|c| c.is_ascii_digit());
+    // 按字节切片前先确认位置在字符边界上，非 ASCII 文件名不会 panic
+    let is_exe = name.len() > 4
+        && name
+            .get(name.len() - 4..)
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(".exe"));
+    (is_pid && is_exe).then(|| name.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +457,14 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    #[cfg(any(windows, target_os = "linux"))]
+    fn test_process_cpu_time() {
+        // 当前进程一定存在
+        assert!(process_cpu_time(std::process::id()).is_some());
+        assert!(process_cpu_time(u32::MAX).is_none());
+    }
+
     #[test]
     fn test_find_cdb_executable_default() {
         // 这个测试在没有安装 CDB 的系统上会失败，所以只检查返回类型
@@ -234,6 +486,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_timestamp() {
+        use std::time::Duration;
+
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(1_792_002_031)),
+            "2026-10-14T18:20:31Z"
+        );
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("2026-10-14T18:20:31Z").map(format_timestamp),
+            Some("2026-10-14T18:20:31Z".to_string())
+        );
+        assert_eq!(
+            parse_timestamp("2026-10-14").map(format_timestamp),
+            Some("2026-10-14T00:00:00Z".to_string())
+        );
+        assert_eq!(
+            parse_timestamp("2000-02-29 08:05").map(format_timestamp),
+            Some("2000-02-29T08:05:00Z".to_string())
+        );
+        assert_eq!(parse_timestamp("1970-01-01"), Some(UNIX_EPOCH));
+        assert_eq!(parse_timestamp("2026-13-01"), None);
+        assert_eq!(parse_timestamp("2026-10-14T25:00:00Z"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("1969-12-31"), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.dmp", "contoso.exe.1234.dmp"));
+        assert!(glob_match("contoso*", "Contoso.exe.1234.dmp"));
+        assert!(glob_match("app?.dmp", "app1.dmp"));
+        assert!(glob_match("*render*crash*", "render-thread-crash.dmp"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("app?.dmp", "app.dmp"));
+        assert!(!glob_match("*.txt", "app.dmp"));
+        assert!(!glob_match("contoso", "contoso.dmp"));
+    }
+
+    #[test]
+    fn test_process_name_from_dump_path() {
+        assert_eq!(
+            process_name_from_dump_path(Path::new("dumps/contoso.exe.6700.dmp")).as_deref(),
+            Some("contoso.exe")
+        );
+        assert_eq!(
+            process_name_from_dump_path(Path::new("Widget.Host.EXE.12.dmp")).as_deref(),
+            Some("Widget.Host.EXE")
+        );
+        assert_eq!(process_name_from_dump_path(Path::new("MEMORY.DMP")), None);
+        assert_eq!(process_name_from_dump_path(Path::new("app.1234.dmp")), None);
+        // 非 ASCII 文件名
+        assert_eq!(
+            process_name_from_dump_path(Path::new("xéabc.1234.dmp")),
+            None
+        );
+        assert_eq!(
+            process_name_from_dump_path(Path::new("应用.exe.42.dmp")).as_deref(),
+            Some("应用.exe")
+        );
+    }
+
     #[test]
     fn test_find_dump_files_nonexistent_directory() {
         let result = find_dump_files(Path::new("nonexistent_dir"), false);
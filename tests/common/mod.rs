@@ -0,0 +1,212 @@
+//! 集成测试共享的辅助函数
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// fake-cdb 替身程序路径
+///
+/// fake-cdb 是单独的工作区成员，与测试程序构建到同一目标目录：
+/// 测试程序位于 `target/<profile>/deps`，fake-cdb 位于 `target/<profile>`。
+pub fn fake_cdb_path() -> PathBuf {
+    let mut dir = std::env::current_exe().unwrap();
+    dir.pop();
+    if dir.ends_with("deps") {
+        dir.pop();
+    }
+    let path = dir.join(format!("fake-cdb{}", std::env::consts::EXE_SUFFIX));
+    assert!(
+        path.is_file(),
+        "{} not found; run the tests with `cargo test --workspace` so that fake-cdb is built",
+        path.display()
+    );
+    path
+}
+
+/// 在临时目录中创建转储文件
+///
+/// `content` 可以包含 `fake-cdb: startup=...` 指令以模拟启动故障，
+/// 或 `fake-cdb: fixture <命令> => <文件名>` 指令覆盖该转储的命令输出。
+pub fn create_dump(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+/// 去除行首的 CDB 提示符（例如 `0:000> `）
+pub fn strip_prompt(line: &str) -> &str {
+    match line.find("> ") {
+        Some(pos) if line[..pos].chars().all(|c| c.is_ascii_digit() || c == ':') => {
+            &line[pos + 2..]
+        }
+        _ => line,
+    }
+}
+
+/// 合成 minidump 写入器
+///
+/// 数据按追加顺序排列在头部之后，流目录放在文件末尾。
+pub struct MinidumpWriter {
+    data: Vec<u8>,
+    streams: Vec<(u32, u32, u32)>,
+    flags: u64,
+}
+
+impl MinidumpWriter {
+    pub fn new(flags: u64) -> Self {
+        Self {
+            data: vec![0; 32],
+            streams: Vec::new(),
+            flags,
+        }
+    }
+
+    /// 追加原始数据（4 字节对齐），返回其 RVA
+    pub fn append(&mut self, bytes: &[u8]) -> u32 {
+        while self.data.len() & 3 != 0 {
+            self.data.push(0);
+        }
+        let rva = self.data.len() as u32;
+        self.data.extend_from_slice(bytes);
+        rva
+    }
+
+    /// 追加 MINIDUMP_STRING，返回其 RVA
+    pub fn add_string(&mut self, text: &str) -> u32 {
+        let units: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut bytes = (units.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&units);
+        bytes.extend_from_slice(&[0, 0]);
+        self.append(&bytes)
+    }
+
+    /// 追加流数据并登记到流目录
+    pub fn add_stream(&mut self, stream_type: u32, bytes: &[u8]) {
+        let rva = self.append(bytes);
+        self.streams.push((stream_type, bytes.len() as u32, rva));
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory: Vec<u8> = self
+            .streams
+            .iter()
+            .flat_map(|(t, size, rva)| [*t, *size, *rva])
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        let directory_rva = self.append(&directory);
+        let header = [
+            0x504d_444d,
+            0xa793,
+            self.streams.len() as u32,
+            directory_rva,
+            0,
+            1_792_002_031,
+        ];
+        for (i, value) in header.iter().enumerate() {
+            self.data[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        self.data[24..32].copy_from_slice(&self.flags.to_le_bytes());
+        self.data
+    }
+}
+
+fn le(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// x64 访问违例 minidump：contoso.exe（带 RSDS 记录）和 ntdll.dll 两个模块、
+/// 一个已卸载模块，线程 0x2b40（名为 `RenderThread`，触发异常，带栈内存和上下文）和 0x1f0c
+pub fn sample_minidump() -> Vec<u8> {
+    let mut writer = MinidumpWriter::new(0x1);
+
+    // 模块列表
+    let contoso = writer.add_string(r"C:\Program Files\Contoso\contoso.exe");
+    let ntdll = writer.add_string(r"C:\Windows\System32\ntdll.dll");
+    let mut cv = b"RSDS".to_vec();
+    cv.extend_from_slice(&[
+        0xe0, 0x04, 0x25, 0x3f, 0x89, 0x4f, 0xd3, 0x11, 0x9a, 0x0c, 0x03, 0x05, 0xe8, 0x2c, 0x33,
+        0x01,
+    ]);
+    cv.extend_from_slice(&1u32.to_le_bytes());
+    cv.extend_from_slice(b"C:\\build\\contoso.pdb\0");
+    let cv_rva = writer.append(&cv);
+    let mut modules = le(&[2]);
+    for (base, size, name, cv_record) in [
+        (
+            0x7ff6_a1b2_0000_u64,
+            0x2f000,
+            contoso,
+            [cv.len() as u32, cv_rva],
+        ),
+        (0x7ffb_1d8c_0000, 0x1f_8000, ntdll, [0, 0]),
+    ] {
+        modules.extend_from_slice(&base.to_le_bytes());
+        modules.extend(le(&[size, 0, 0x6a2e_0f6f, name]));
+        modules.extend_from_slice(&[0; 52]); // VS_FIXEDFILEINFO
+        modules.extend(le(&cv_record));
+        modules.extend_from_slice(&[0; 24]); // MiscRecord + Reserved
+    }
+    writer.add_stream(4, &modules);
+
+    // 已卸载模块列表
+    let hook = writer.add_string("hook64.dll");
+    let mut unloaded = le(&[12, 24, 1]);
+    unloaded.extend_from_slice(&0x7ffb_0000_0000_u64.to_le_bytes());
+    unloaded.extend(le(&[0x4000, 0, 0x5f00_0000, hook]));
+    writer.add_stream(14, &unloaded);
+
+    // 线程 0x2b40 的栈内存（0xd12ff000 起 0x1000 字节，SP+8 处为 contoso 内的返回地址）
+    let mut stack = vec![0u8; 0x1000];
+    stack[0x7f8..0x800].copy_from_slice(&0x7ff6_a1b2_5678_u64.to_le_bytes());
+    let stack_rva = writer.append(&stack);
+
+    // x64 CONTEXT：rsp = 0xd12ff7f0，rip = 异常地址
+    let mut context = vec![0u8; 0x4d0];
+    context[0x98..0xa0].copy_from_slice(&0xd12f_f7f0_u64.to_le_bytes());
+    context[0xf8..0x100].copy_from_slice(&0x7ff6_a1b2_1234_u64.to_le_bytes());
+    let context_rva = writer.append(&context);
+
+    // 线程列表和线程名
+    let mut threads = le(&[2]);
+    for (tid, teb, stack_start, stack) in [
+        (
+            0x2b40_u32,
+            0x7ff6_1000_0000_u64,
+            0xd12f_f000_u64,
+            [0x1000, stack_rva],
+        ),
+        (0x1f0c, 0x7ff6_1000_2000, 0, [0, 0]),
+    ] {
+        threads.extend(le(&[tid, 0, 0x20, 0]));
+        threads.extend_from_slice(&teb.to_le_bytes());
+        threads.extend_from_slice(&stack_start.to_le_bytes());
+        threads.extend(le(&stack));
+        threads.extend_from_slice(&[0; 8]); // ThreadContext
+    }
+    writer.add_stream(3, &threads);
+    let name = writer.add_string("RenderThread");
+    let mut names = le(&[1, 0x2b40]);
+    names.extend_from_slice(&u64::from(name).to_le_bytes());
+    writer.add_stream(24, &names);
+
+    // SystemInfo（x64，Windows 10 19045）
+    let mut system_info = le(&[9 | (6 << 16), 0x0108, 10, 0, 19045, 2, 0]);
+    system_info.resize(56, 0);
+    writer.add_stream(7, &system_info);
+
+    // MiscInfo（进程 ID 0x1a2c）和异常流（线程 0x2b40 读取地址 8 时访问违例）
+    writer.add_stream(15, &le(&[24, 0x1, 0x1a2c, 0, 0, 0]));
+    let mut exception = le(&[0x2b40, 0, 0xc000_0005, 0]);
+    exception.extend_from_slice(&0u64.to_le_bytes());
+    exception.extend_from_slice(&0x7ff6_a1b2_1234_u64.to_le_bytes());
+    exception.extend(le(&[2, 0]));
+    for i in 0..15u64 {
+        let parameter: u64 = if i == 1 { 8 } else { 0 };
+        exception.extend_from_slice(&parameter.to_le_bytes());
+    }
+    exception.extend(le(&[0x4d0, context_rva]));
+    writer.add_stream(6, &exception);
+
+    writer.finish()
+}
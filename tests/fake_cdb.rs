@@ -0,0 +1,388 @@
+//! 基于 fake-cdb 替身程序的 CDB 会话端到端测试
+
+mod common;
+
+use common::{create_dump, fake_cdb_path, strip_prompt};
+use mcp_windbg_rs::backend::{DebuggerBackend, SpawnOptions};
+use mcp_windbg_rs::cdb::CdbSession;
+use mcp_windbg_rs::error::{CdbError, SessionError};
+use mcp_windbg_rs::session::SessionManager;
+use mcp_windbg_rs::timeouts::CommandTimeouts;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+fn manager() -> SessionManager {
+    SessionManager::new(Duration::from_secs(5), Duration::from_secs(5), false)
+        .with_cdb_path(Some(fake_cdb_path()))
+}
+
+fn options(timeout: Duration, init_timeout: Duration) -> SpawnOptions {
+    SpawnOptions {
+        cdb_path: Some(fake_cdb_path()),
+        symbols_path: None,
+        extra_args: Vec::new(),
+        timeout,
+        init_timeout,
+        verbose: false,
+        command_timeouts: CommandTimeouts::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_dump_session_returns_fixture_output() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "crash.dmp", "");
+    let manager = manager();
+    let session = manager.get_or_create_dump_session(&dump).await.unwrap();
+    let mut guard = session.lock().await;
+
+    let output = guard.send_command("kb").await.unwrap();
+    assert_eq!(output.len(), 6);
+    assert!(strip_prompt(&output[0]).contains("RetAddr"));
+    assert!(output[1].contains("contoso!Widget::Render+0x24"));
+
+    // 会话可以继续执行后续命令
+    let output = guard.send_command("~").await.unwrap();
+    assert_eq!(output.len(), 3);
+    assert!(output[0].contains("Id: 1a2c.2b40"));
+
+    let output = guard.send_command("!analyze -v").await.unwrap();
+    assert!(output
+        .iter()
+        .any(|l| l.contains("FAILURE_BUCKET_ID:  NULL_CLASS_PTR_READ_c0000005")));
+}
+
+#[tokio::test]
+async fn test_remote_session_and_unknown_command() {
+    let manager = manager();
+    let session = manager
+        .get_or_create_remote_session("tcp:Port=5005,Server=127.0.0.1")
+        .await
+        .unwrap();
+
+    let output = session
+        .lock()
+        .await
+        .send_command("!nosuchext")
+        .await
+        .unwrap();
+    assert_eq!(output.len(), 1);
+    assert!(output[0].contains("Couldn't resolve error at '!nosuchext'"));
+
+    drop(session);
+    manager
+        .close_session("tcp:Port=5005,Server=127.0.0.1")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_close_dump_session_exits_process() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "close.dmp", "");
+    let manager = manager();
+    let session = manager.get_or_create_dump_session(&dump).await.unwrap();
+    let session_id = session.lock().await.session_id().to_string();
+    drop(session);
+
+    manager.close_session(&session_id).await.unwrap();
+    assert_eq!(manager.active_session_count().await, 0);
+}
+
+#[tokio::test]
+async fn test_command_hang_times_out() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "hang.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_millis(500), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    let result = session.send_command("!fake.hang 30").await;
+    assert!(matches!(result, Err(CdbError::CommandTimeout { .. })));
+}
+
+#[tokio::test]
+async fn test_timeout_reports_partial_output() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "partial.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_millis(500), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    match session.send_command(".echo first; !fake.hang 30").await {
+        Err(err @ CdbError::CommandTimeout { .. }) => {
+            assert!(
+                err.to_string().contains("partial output (1 lines)"),
+                "{}",
+                err
+            );
+            let CdbError::CommandTimeout {
+                timeout,
+                partial_output,
+            } = err
+            else {
+                unreachable!()
+            };
+            assert_eq!(timeout, Duration::from_millis(500));
+            assert_eq!(partial_output.len(), 1, "{:?}", partial_output);
+            assert_eq!(strip_prompt(&partial_output[0]), "first");
+        }
+        other => panic!("Expected CommandTimeout, got {:?}", other),
+    }
+
+    // 单条命令的超时覆盖默认超时
+    let started = Instant::now();
+    let result = session
+        .send_command_cancellable(
+            "!fake.hang 30",
+            Some(Duration::from_millis(200)),
+            &CancellationToken::new(),
+        )
+        .await;
+    match result {
+        Err(CdbError::CommandTimeout { timeout, .. }) => {
+            assert_eq!(timeout, Duration::from_millis(200))
+        }
+        other => panic!("Expected CommandTimeout, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let output = session.send_command(".echo next").await.unwrap();
+    assert_eq!(strip_prompt(&output[0]), "next");
+}
+
+#[tokio::test]
+async fn test_command_class_timeouts() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "classes.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &SpawnOptions {
+            command_timeouts: CommandTimeouts {
+                overrides: vec![("!fake.hang*".to_string(), Duration::from_millis(300))],
+            },
+            ..options(Duration::from_secs(30), Duration::from_secs(5))
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(session.timeout_for("k"), Duration::from_secs(30));
+    assert_eq!(session.timeout_for("!analyze -v"), Duration::from_secs(300));
+    assert_eq!(session.timeout_for("!heap -s -v"), Duration::from_secs(600));
+
+    // 配置的模式缩短了 `!fake.hang` 的超时
+    let started = Instant::now();
+    let result = session.send_command("!fake.hang 30").await;
+    match result {
+        Err(CdbError::CommandTimeout { timeout, .. }) => {
+            assert_eq!(timeout, Duration::from_millis(300))
+        }
+        other => panic!("Expected CommandTimeout, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_output_after_timeout_is_not_mixed_into_next_command() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "slow.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_millis(500), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    // 超时命令稍后输出的内容属于它自己，不应出现在下一条命令的结果中
+    let result = session.send_command("!fake.hang 1; .echo late").await;
+    assert!(matches!(result, Err(CdbError::CommandTimeout { .. })));
+    tokio::time::sleep(Duration::from_millis(700)).await;
+
+    let output = session.send_command(".echo next").await.unwrap();
+    assert_eq!(output.len(), 1, "{:?}", output);
+    assert_eq!(strip_prompt(&output[0]), "next");
+
+    let output = session.send_command(".echo after").await.unwrap();
+    assert_eq!(output.len(), 1, "{:?}", output);
+    assert_eq!(strip_prompt(&output[0]), "after");
+}
+
+#[tokio::test]
+async fn test_cancelled_command_is_interrupted_and_session_reusable() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "cancel.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(30), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        trigger.cancel();
+    });
+
+    // 中断后 CDB 跳过同一行中剩余的命令，远早于超时返回
+    let started = Instant::now();
+    let result = session
+        .send_command_cancellable("!fake.hang 30; .echo skipped", None, &cancel)
+        .await;
+    assert!(matches!(result, Err(CdbError::Cancelled)), "{:?}", result);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(session.is_alive());
+
+    let output = session.send_command(".echo next").await.unwrap();
+    assert_eq!(output.len(), 1, "{:?}", output);
+    assert_eq!(strip_prompt(&output[0]), "next");
+}
+
+#[tokio::test]
+async fn test_uninterruptible_command_restarts_session() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "wedged.dmp", "fake-cdb: interrupt=ignore");
+    let manager = SessionManager::new(Duration::from_millis(300), Duration::from_secs(5), false)
+        .with_cdb_path(Some(fake_cdb_path()));
+
+    // 超时后中断不起作用，CDB 进程在重新同步超时后被结束，会话被标记为已终止
+    let session = manager.get_or_create_dump_session(&dump).await.unwrap();
+    {
+        let mut guard = session.lock().await;
+        let result = guard.send_command("!fake.hang 30").await;
+        assert!(
+            matches!(result, Err(CdbError::CommandTimeout { .. })),
+            "{:?}",
+            result
+        );
+        assert!(!guard.is_alive());
+    }
+    drop(session);
+
+    // 下次使用时重新启动 CDB，而不是在仍忙于失控命令的进程上等待超时
+    let session = manager.get_or_create_dump_session(&dump).await.unwrap();
+    let mut guard = session.lock().await;
+    assert!(guard.take_restart_notice().is_some());
+    let output = guard.send_command(".echo next").await.unwrap();
+    assert_eq!(strip_prompt(&output[0]), "next");
+}
+
+#[tokio::test]
+async fn test_sentinel_like_output_does_not_end_command() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "echo.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(5), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    let output = session
+        .send_command(".echo MCPWDBG_0_1_END; .echo CMD_DONE_0; .echo tail")
+        .await
+        .unwrap();
+    let lines: Vec<_> = output.iter().map(|l| strip_prompt(l)).collect();
+    assert_eq!(lines, vec!["MCPWDBG_0_1_END", "CMD_DONE_0", "tail"]);
+}
+
+#[tokio::test]
+async fn test_process_crash_is_reported() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "crash.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(5), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    let result = session.send_command("!fake.crash").await;
+    assert!(matches!(result, Err(CdbError::ProcessTerminated)));
+}
+
+#[tokio::test]
+async fn test_huge_output_is_collected() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "huge.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(10), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    let output = session.send_command("!fake.huge 20000").await.unwrap();
+    assert_eq!(output.len(), 20000);
+    assert!(output[19999].ends_with("fake output line 19999"));
+}
+
+#[tokio::test]
+async fn test_startup_crash_fails_session_creation() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "bad.dmp", "fake-cdb: startup=crash");
+    let result = manager().get_or_create_dump_session(&dump).await;
+    match result {
+        Err(SessionError::CreationFailed(CdbError::ProcessStartFailed(message))) => {
+            // 错误信息包含退出状态和 stderr 最后几行
+            assert!(message.contains("exited during startup"), "{}", message);
+            assert!(
+                message.contains("ERROR: fake-cdb cannot map the dump file"),
+                "{}",
+                message
+            );
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn test_stderr_is_captured_per_command() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "stderr.dmp", "");
+    let mut session = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(5), Duration::from_secs(5)),
+    )
+    .await
+    .unwrap();
+
+    session.send_command("!fake.stderr before").await.unwrap();
+    session.sync_stderr().await;
+    let mark = DebuggerBackend::stderr_mark(&session);
+
+    // 超过管道容量（约 100 KB）的 stderr 输出也不会阻塞 CDB
+    let command = format!(
+        "{}; .echo done",
+        ["!fake.stderr symbol server unreachable"; 4000].join("; ")
+    );
+    let output = session.send_command(&command).await.unwrap();
+    assert!(strip_prompt(output.last().unwrap()).ends_with("done"));
+    session.sync_stderr().await;
+
+    let lines = DebuggerBackend::stderr_since(&session, mark);
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|l| l == "symbol server unreachable"));
+    assert_eq!(session.stderr().tail(1), vec!["symbol server unreachable"]);
+}
+
+#[tokio::test]
+async fn test_startup_hang_times_out() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "slow.dmp", "fake-cdb: startup=hang");
+    let result = CdbSession::new_dump(
+        &dump,
+        &options(Duration::from_secs(5), Duration::from_millis(500)),
+    )
+    .await;
+    assert!(matches!(result, Err(CdbError::CommandTimeout { .. })));
+}
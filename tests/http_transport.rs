@@ -0,0 +1,347 @@
+//! 通过本地 HTTP 客户端驱动 streamable HTTP 传输的端到端测试
+
+mod common;
+
+use common::{create_dump, fake_cdb_path};
+use mcp_windbg_rs::server::{McpServer, ServerConfig, Transport, HTTP_ENDPOINT};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+const ACCEPT: &str = "application/json, text/event-stream";
+const SESSION_HEADER: &str = "mcp-session-id";
+
+const TOKEN: &str = "test-token";
+
+/// 在随机端口上启动 HTTP 传输，返回端点 URL 和会话管理器
+async fn start_server() -> (String, Arc<mcp_windbg_rs::session::SessionManager>) {
+    start_server_with(ServerConfig::default()).await
+}
+
+/// 使用指定配置（CDB 和超时设置除外）启动 HTTP 传输
+async fn start_server_with(
+    config: ServerConfig,
+) -> (String, Arc<mcp_windbg_rs::session::SessionManager>) {
+    let server = McpServer::new(ServerConfig {
+        cdb_path: Some(fake_cdb_path()),
+        timeout: Duration::from_secs(5),
+        init_timeout: Duration::from_secs(5),
+        transport: Transport::Http,
+        ..config
+    });
+    let manager = server.session_manager().clone();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), HTTP_ENDPOINT);
+    tokio::spawn(server.serve_http(listener));
+    (url, manager)
+}
+
+/// 单个 MCP 客户端连接
+struct Client {
+    http: reqwest::Client,
+    url: String,
+    session_id: String,
+    next_id: u64,
+}
+
+/// initialize 请求
+fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "http-test", "version": "0.0.0" }
+        }
+    })
+}
+
+impl Client {
+    /// 发送 initialize 请求并完成握手
+    async fn connect(url: &str) -> Self {
+        Self::connect_with(url, reqwest::header::HeaderMap::new()).await
+    }
+
+    /// 携带额外请求头（例如 `Authorization`）完成握手
+    async fn connect_with(url: &str, headers: reqwest::header::HeaderMap) -> Self {
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+        let response = http
+            .post(url)
+            .header("accept", ACCEPT)
+            .json(&initialize_request())
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success(), "{}", response.status());
+        let session_id = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let message = read_message(response).await;
+        assert_eq!(message["result"]["serverInfo"]["name"], "mcp-windbg-rs");
+
+        let client = Self {
+            http,
+            url: url.to_string(),
+            session_id,
+            next_id: 1,
+        };
+        let response = client
+            .post(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+        client
+    }
+
+    async fn post(&self, body: Value) -> reqwest::Response {
+        self.http
+            .post(&self.url)
+            .header("accept", ACCEPT)
+            .header(SESSION_HEADER, &self.session_id)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    /// 发送请求并返回 `result`
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        let response = self
+            .post(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await;
+        assert!(response.status().is_success(), "{}", response.status());
+        let message = read_message(response).await;
+        assert_eq!(message["id"], id);
+        message["result"].clone()
+    }
+
+    /// 关闭 MCP 会话
+    async fn close(self) {
+        let response = self
+            .http
+            .delete(&self.url)
+            .header(SESSION_HEADER, &self.session_id)
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success(), "{}", response.status());
+    }
+}
+
+/// 从 SSE 响应中读取第一条 JSON-RPC 消息
+async fn read_message(response: reqwest::Response) -> Value {
+    let content_type = response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        content_type.starts_with("text/event-stream"),
+        "{}",
+        content_type
+    );
+
+    let mut response = response;
+    let mut buffer = String::new();
+    while let Some(chunk) = response.chunk().await.unwrap() {
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        let message = buffer
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim)
+            .find(|data| !data.is_empty())
+            .and_then(|data| serde_json::from_str::<Value>(data).ok());
+        if let Some(message) = message {
+            return message;
+        }
+    }
+    panic!("No JSON-RPC message in SSE stream: {}", buffer);
+}
+
+#[tokio::test]
+async fn test_http_list_tools_and_call() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let (url, _manager) = start_server().await;
+    let mut client = Client::connect(&url).await;
+
+    let tools = client.request("tools/list", json!({})).await;
+    let names: Vec<&str> = tools["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"run_windbg_cmd"));
+    assert!(names.contains(&"open_windbg_dump"));
+
+    let result = client
+        .request(
+            "tools/call",
+            json!({
+                "name": "run_windbg_cmd",
+                "arguments": { "dump_path": dump, "command": ".echo hello" }
+            }),
+        )
+        .await;
+    assert_ne!(result["isError"], true);
+    assert!(result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("hello"));
+    client.close().await;
+}
+
+#[tokio::test]
+async fn test_http_sessions_shared_across_clients() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let (url, manager) = start_server().await;
+
+    // 第一个客户端打开转储后断开，调试会话仍然保留
+    let mut first = Client::connect(&url).await;
+    first
+        .request(
+            "tools/call",
+            json!({
+                "name": "run_windbg_cmd",
+                "arguments": { "dump_path": dump, "command": ".echo first" }
+            }),
+        )
+        .await;
+    first.close().await;
+    assert_eq!(manager.active_session_count().await, 1);
+
+    // 第二个客户端复用同一个调试会话
+    let mut second = Client::connect(&url).await;
+    let result = second
+        .request(
+            "tools/call",
+            json!({
+                "name": "run_windbg_cmd",
+                "arguments": { "dump_path": dump, "command": ".echo second" }
+            }),
+        )
+        .await;
+    assert!(result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("second"));
+    assert_eq!(manager.active_session_count().await, 1);
+    second.close().await;
+}
+
+#[tokio::test]
+async fn test_http_rejects_request_without_session() {
+    let (url, _manager) = start_server().await;
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header("accept", ACCEPT)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_client_error(), "{}", response.status());
+}
+
+fn bearer(token: &str) -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    headers
+}
+
+#[tokio::test]
+async fn test_http_bearer_auth_rejects_initialize() {
+    let (url, _manager) = start_server_with(ServerConfig {
+        auth_token: Some(TOKEN.to_string()),
+        ..Default::default()
+    })
+    .await;
+    let http = reqwest::Client::new();
+
+    for authorization in [None, Some("Bearer wrong-token"), Some(TOKEN)] {
+        let mut request = http
+            .post(&url)
+            .header("accept", ACCEPT)
+            .json(&initialize_request());
+        if let Some(value) = authorization {
+            request = request.header("authorization", value);
+        }
+        let response = request.send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get(SESSION_HEADER).is_none());
+        let message: Value = response.json().await.unwrap();
+        assert_eq!(message["id"], 0);
+        assert_eq!(message["error"]["code"], -32001);
+        assert!(message["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("bearer token"));
+    }
+}
+
+#[tokio::test]
+async fn test_http_bearer_auth_from_token_file() {
+    let dir = TempDir::new().unwrap();
+    let token_file = dir.path().join("token");
+    std::fs::write(&token_file, format!("{}\n", TOKEN)).unwrap();
+    let (url, _manager) = start_server_with(ServerConfig {
+        auth_token_file: Some(token_file),
+        ..Default::default()
+    })
+    .await;
+
+    let mut client = Client::connect_with(&url, bearer(TOKEN)).await;
+    let tools = client.request("tools/list", json!({})).await;
+    assert!(!tools["tools"].as_array().unwrap().is_empty());
+
+    // 已建立的 MCP 会话也不能在没有令牌时使用
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header("accept", ACCEPT)
+        .header(SESSION_HEADER, &client.session_id)
+        .json(&json!({ "jsonrpc": "2.0", "id": 9, "method": "tools/list" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    client.close().await;
+}
+
+#[tokio::test]
+async fn test_http_missing_token_file_fails_to_start() {
+    let dir = TempDir::new().unwrap();
+    let server = McpServer::new(ServerConfig {
+        transport: Transport::Http,
+        auth_token_file: Some(dir.path().join("missing")),
+        ..Default::default()
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let err = server.serve_http(listener).await.unwrap_err();
+    assert!(err.to_string().contains("missing"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_http_non_loopback_without_token_fails_to_start() {
+    let server = McpServer::new(ServerConfig {
+        transport: Transport::Http,
+        ..Default::default()
+    });
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
+    let err = server.serve_http(listener).await.unwrap_err();
+    assert!(
+        err.to_string().contains("requires a bearer token"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,421 @@
+//! 脚本化的 CDB 替身程序
+//!
+//! 模拟 cdb.exe 的 stdin/stdout 交互协议，使完整的 MCP 工具链可以在没有
+//! Windows 调试工具的环境（例如 Linux CI）中进行端到端测试。
+//!
+//! 支持的行为：
+//! * `-z <dump>` / `-remote <conn>` / `-c <cmds>` / `-y <sympath>` 命令行参数
+//! * `.echo`、`.sympath`、`q` 等内置命令
+//! * 通过 `manifest.txt` 将命令映射到固定输出文件（目录可由 `FAKE_CDB_FIXTURES` 覆盖）
+//! * 故障模拟命令：`!fake.hang [秒]`、`!fake.crash [退出码]`、`!fake.huge <行数>`
+//! * 中断（SIGINT / Ctrl+Break）结束正在执行的 `!fake.hang`，并跳过同一行中剩余的命令
+//! * `!fake.stderr <文本>` 将文本写入 stderr
+//! * 诊断命令：`!fake.argv` 输出自身路径和命令行参数
+//! * 转储文件中的 `fake-cdb: startup=hang|crash` 指令用于模拟启动阶段故障
+//! * 转储文件中的 `fake-cdb: fixture <命令> => <文件名>` 指令覆盖该转储的命令映射
+//! * 转储文件中的 `fake-cdb: hang <命令>` 指令使该命令像 `!fake.hang` 一样挂起
+//! * 转储文件中的 `fake-cdb: interrupt=ignore` 指令使替身程序忽略中断，用于模拟无法中断的命令
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// 提示符（与 cdb.exe 用户模式目标一致）
+const PROMPT: &str = "0:000> ";
+
+/// 是否收到了中断请求
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// 安装 SIGINT 处理函数（只设置标志，由正在执行的命令检查；`ignore` 为 true 时忽略 SIGINT）
+#[cfg(unix)]
+fn install_interrupt_handler(ignore: bool) {
+    extern "C" fn on_interrupt(_signal: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+
+    let handler = if ignore {
+        libc::SIG_IGN
+    } else {
+        on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t
+    };
+    // SAFETY: 处理函数只写入原子变量
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+    }
+}
+
+/// 安装 Ctrl+Break 处理函数（只设置标志，由正在执行的命令检查；`ignore` 为 true 时忽略中断）
+#[cfg(windows)]
+fn install_interrupt_handler(ignore: bool) {
+    use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+
+    unsafe extern "system" fn on_interrupt(_ctrl_type: u32) -> i32 {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        1
+    }
+
+    unsafe extern "system" fn on_ignored_interrupt(_ctrl_type: u32) -> i32 {
+        1
+    }
+
+    let handler = if ignore {
+        on_ignored_interrupt
+    } else {
+        on_interrupt
+    };
+    // SAFETY: 处理函数只写入原子变量
+    unsafe {
+        SetConsoleCtrlHandler(Some(handler), 1);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn install_interrupt_handler(_ignore: bool) {}
+
+/// 启动阶段的模拟行为
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartupMode {
+    /// 正常启动
+    Normal,
+    /// 启动时挂起，不输出就绪标记
+    Hang,
+    /// 启动时崩溃退出
+    Crash,
+}
+
+/// 解析后的命令行参数
+#[derive(Debug, Default)]
+struct Args {
+    /// 转储文件路径（-z）
+    dump_path: Option<PathBuf>,
+    /// 远程连接字符串（-remote）
+    remote: Option<String>,
+    /// 初始命令（-c）
+    initial_commands: Option<String>,
+    /// 符号路径（-y）
+    symbols_path: Option<String>,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = Args::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-z" => args.dump_path = iter.next().map(PathBuf::from),
+                "-remote" => args.remote = iter.next(),
+                "-c" => args.initial_commands = iter.next(),
+                "-y" => args.symbols_path = iter.next(),
+                _ => {}
+            }
+        }
+        args
+    }
+}
+
+/// 替身调试器状态
+struct FakeCdb {
+    /// 命令 -> 固定输出文件
+    fixtures: HashMap<String, PathBuf>,
+    /// 挂起直到被中断的命令
+    hanging: HashSet<String>,
+    /// 符号路径
+    symbols_path: Option<String>,
+}
+
+impl FakeCdb {
+    fn new(args: &Args) -> Self {
+        let fixtures_dir = std::env::var_os("FAKE_CDB_FIXTURES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/cdb"));
+
+        let symbols_path = args
+            .symbols_path
+            .clone()
+            .or_else(|| std::env::var("_NT_SYMBOL_PATH").ok());
+
+        let mut fixtures = load_manifest(&fixtures_dir);
+        fixtures.extend(dump_fixtures(args.dump_path.as_deref(), &fixtures_dir));
+
+        Self {
+            fixtures,
+            hanging: dump_hangs(args.dump_path.as_deref()),
+            symbols_path,
+        }
+    }
+
+    /// 执行一行输入（可能包含以 `;` 分隔的多个命令）
+    ///
+    /// 返回 false 表示调试器应当退出。
+    fn execute_line(&self, out: &mut impl Write, line: &str) -> bool {
+        // 远程会话使用 CTRL+B 分离
+        let line = line.trim_start_matches('\x02');
+        for command in split_commands(line) {
+            if !self.execute(out, &command) {
+                return false;
+            }
+            // 与 cdb.exe 一样，中断后丢弃同一行中剩余的命令
+            if INTERRUPTED.swap(false, Ordering::SeqCst) {
+                break;
+            }
+        }
+        true
+    }
+
+    /// 执行单条命令
+    fn execute(&self, out: &mut impl Write, command: &str) -> bool {
+        let command = normalize(command);
+        let (name, rest) = match command.split_once(' ') {
+            Some((name, rest)) => (name, rest.trim()),
+            None => (command.as_str(), ""),
+        };
+
+        if self.hanging.contains(&command) {
+            hang(out, Duration::from_secs(3600));
+            return true;
+        }
+
+        match name {
+            "" => {}
+            "q" | "qq" | "qd" => return false,
+            ".echo" => {
+                let _ = writeln!(out, "{}", rest.trim_matches('"'));
+            }
+            ".sympath" => {
+                let _ = writeln!(
+                    out,
+                    "Symbol search path is: {}",
+                    self.symbols_path.as_deref().unwrap_or("<empty>")
+                );
+            }
+            "!fake.argv" => {
+                let exe = std::env::current_exe().unwrap_or_default();
+                let _ = writeln!(out, "exe: {}", exe.display());
+                for arg in std::env::args().skip(1) {
+                    let _ = writeln!(out, "arg: {}", arg);
+                }
+            }
+            "!fake.hang" => {
+                hang(out, Duration::from_secs(rest.parse().unwrap_or(3600)));
+            }
+            "!fake.crash" => {
+                let _ = out.flush();
+                std::process::exit(rest.parse().unwrap_or(3));
+            }
+            "!fake.stderr" => {
+                eprintln!("{}", rest);
+            }
+            "!fake.huge" => {
+                let count: usize = rest.parse().unwrap_or(1000);
+                for i in 0..count {
+                    let _ = writeln!(out, "{:016x}  fake output line {}", i, i);
+                }
+            }
+            _ => match self.fixtures.get(&command) {
+                Some(path) => match std::fs::read_to_string(path) {
+                    Ok(text) => {
+                        let _ = write!(out, "{}", text);
+                        if !text.ends_with('\n') {
+                            let _ = writeln!(out);
+                        }
+                    }
+                    Err(e) => {
+                        let _ = writeln!(out, "fake-cdb: failed to read {}: {}", path.display(), e);
+                    }
+                },
+                None => {
+                    let _ = writeln!(out, "Couldn't resolve error at '{}'", command);
+                }
+            },
+        }
+
+        true
+    }
+}
+
+/// 挂起指定时间，收到中断时提前返回
+fn hang(out: &mut impl Write, duration: Duration) {
+    let _ = out.flush();
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            let _ = writeln!(out, "^C Debugger interrupted");
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// 加载命令映射清单
+fn load_manifest(dir: &Path) -> HashMap<String, PathBuf> {
+    let mut fixtures = HashMap::new();
+    let Ok(text) = std::fs::read_to_string(dir.join("manifest.txt")) else {
+        return fixtures;
+    };
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((command, file)) = line.split_once("=>") {
+            fixtures.insert(normalize(command), dir.join(file.trim()));
+        }
+    }
+
+    fixtures
+}
+
+/// 从转储文件内容中读取命令映射覆盖（使不同转储可以返回不同的输出）
+fn dump_fixtures(dump_path: Option<&Path>, dir: &Path) -> HashMap<String, PathBuf> {
+    let mut fixtures = HashMap::new();
+    let Some(content) = dump_path.and_then(|p| std::fs::read(p).ok()) else {
+        return fixtures;
+    };
+
+    for line in String::from_utf8_lossy(&content).lines() {
+        let Some(mapping) = line.trim().strip_prefix("fake-cdb: fixture ") else {
+            continue;
+        };
+        if let Some((command, file)) = mapping.split_once("=>") {
+            fixtures.insert(normalize(command), dir.join(file.trim()));
+        }
+    }
+
+    fixtures
+}
+
+/// 从转储文件内容中读取需要挂起的命令
+fn dump_hangs(dump_path: Option<&Path>) -> HashSet<String> {
+    let Some(content) = dump_path.and_then(|p| std::fs::read(p).ok()) else {
+        return HashSet::new();
+    };
+
+    String::from_utf8_lossy(&content)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("fake-cdb: hang "))
+        .map(normalize)
+        .collect()
+}
+
+/// 规范化命令：去除首尾空白并合并连续空白
+fn normalize(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 按 `;` 拆分命令（忽略引号内的分号）
+fn split_commands(line: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ';' if !in_quotes => commands.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    commands.push(current);
+
+    commands
+}
+
+/// 从转储文件内容中读取启动指令
+fn startup_mode(dump_path: Option<&Path>) -> StartupMode {
+    let Some(content) = dump_path.and_then(|p| std::fs::read(p).ok()) else {
+        return StartupMode::Normal;
+    };
+    let text = String::from_utf8_lossy(&content);
+    if text.contains("fake-cdb: startup=hang") {
+        StartupMode::Hang
+    } else if text.contains("fake-cdb: startup=crash") {
+        StartupMode::Crash
+    } else {
+        StartupMode::Normal
+    }
+}
+
+/// 转储文件是否要求忽略中断
+fn ignores_interrupts(dump_path: Option<&Path>) -> bool {
+    dump_path
+        .and_then(|p| std::fs::read(p).ok())
+        .is_some_and(|content| {
+            String::from_utf8_lossy(&content).contains("fake-cdb: interrupt=ignore")
+        })
+}
+
+fn main() {
+    let args = Args::parse();
+    install_interrupt_handler(ignores_interrupts(args.dump_path.as_deref()));
+    let cdb = FakeCdb::new(&args);
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    let _ = writeln!(
+        out,
+        "Microsoft (R) Windows Debugger Version 10.0.22621.2428 AMD64"
+    );
+    let _ = writeln!(
+        out,
+        "Copyright (c) Microsoft Corporation. All rights reserved."
+    );
+    let _ = writeln!(out);
+
+    if let Some(dump_path) = &args.dump_path {
+        let _ = writeln!(out, "Loading Dump File [{}]", dump_path.display());
+        let _ = writeln!(
+            out,
+            "User Mini Dump File: Only registers, stack and portions of memory are available"
+        );
+    } else if let Some(remote) = &args.remote {
+        let _ = writeln!(out, "Connected to server with '{}'", remote);
+    }
+    let _ = writeln!(out);
+    let _ = out.flush();
+
+    match startup_mode(args.dump_path.as_deref()) {
+        StartupMode::Normal => {}
+        StartupMode::Hang => std::thread::sleep(Duration::from_secs(3600)),
+        StartupMode::Crash => {
+            eprintln!("ERROR: fake-cdb cannot map the dump file");
+            let _ = writeln!(
+                out,
+                "Could not open dump file [{}]",
+                args.dump_path.unwrap_or_default().display()
+            );
+            let _ = out.flush();
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(initial) = &args.initial_commands {
+        let _ = write!(out, "{}", PROMPT);
+        if !cdb.execute_line(&mut out, initial) {
+            return;
+        }
+    }
+
+    let stdin = std::io::stdin();
+    let mut line = String::new();
+    loop {
+        let _ = write!(out, "{}", PROMPT);
+        let _ = out.flush();
+
+        line.clear();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                if !cdb.execute_line(&mut out, line.trim_end_matches(['\r', '\n'])) {
+                    break;
+                }
+            }
+        }
+        let _ = out.flush();
+    }
+}
@@ -0,0 +1,1264 @@
+//! 基于 fake-cdb 替身程序的 MCP 工具端到端测试
+
+mod common;
+
+use common::{create_dump, fake_cdb_path, sample_minidump};
+use mcp_windbg_rs::error::{ServerError, ToolError};
+use mcp_windbg_rs::policy::{CommandPolicy, PolicyProfile};
+use mcp_windbg_rs::server::{McpServer, ServerConfig};
+use mcp_windbg_rs::tools;
+use mcp_windbg_rs::types::{ContentItem, ToolResponse};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+/// 将 fake-cdb 复制到临时目录中的新名称，用于证明实际启动的是配置的路径
+fn copy_stub_cdb(dir: &TempDir, name: &str) -> PathBuf {
+    let source = fake_cdb_path();
+    let mut target = dir.path().join(name);
+    if let Some(ext) = source.extension() {
+        target.set_extension(ext);
+    }
+    std::fs::copy(&source, &target).unwrap();
+    target
+}
+
+fn server(cdb_path: &Path, symbols_path: Option<&str>, cdb_args: &[&str]) -> McpServer {
+    McpServer::new(ServerConfig {
+        cdb_path: Some(cdb_path.to_path_buf()),
+        symbols_path: symbols_path.map(str::to_string),
+        cdb_args: cdb_args.iter().map(|s| s.to_string()).collect(),
+        timeout: Duration::from_secs(5),
+        init_timeout: Duration::from_secs(5),
+        ..Default::default()
+    })
+}
+
+fn response_text(response: &ToolResponse) -> String {
+    response
+        .content
+        .iter()
+        .map(|item| match item {
+            ContentItem::Text { text } => text.as_str(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn test_open_windbg_dump_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "open_windbg_dump",
+            json!({
+                "dump_path": dump,
+                "include_stack_trace": true,
+                "include_modules": true,
+                "include_threads": true
+            }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+
+    assert!(text.contains("## Last Event"));
+    assert!(text.contains("Access violation - code c0000005"));
+    assert!(text.contains("FAILURE_BUCKET_ID:  NULL_CLASS_PTR_READ_c0000005"));
+    assert!(text.contains("contoso!Window::Paint+0x50"));
+    assert!(text.contains("vcruntime140"));
+    assert!(text.contains("Id: 1a2c.0c3c"));
+
+    let structured = response.structured_content.as_ref().unwrap();
+    let analysis = &structured["analysis"];
+    assert_eq!(
+        analysis["failure_bucket_id"],
+        "NULL_CLASS_PTR_READ_c0000005_contoso.exe!Widget::Render"
+    );
+    assert_eq!(analysis["exception_code"], "0xc0000005");
+    assert_eq!(analysis["module_name"], "contoso");
+    assert_eq!(analysis["stack_text"].as_array().unwrap().len(), 5);
+    assert_eq!(structured["stack"]["frames"].as_array().unwrap().len(), 5);
+    assert_eq!(structured["modules"].as_array().unwrap().len(), 4);
+    assert_eq!(structured["threads"].as_array().unwrap().len(), 3);
+
+    server
+        .handle_tool_call(
+            "close_windbg_dump",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(server.session_manager().active_session_count().await, 0);
+}
+
+#[tokio::test]
+async fn test_run_windbg_cmd_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo hello; .echo world" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+    assert!(text.contains("hello"));
+    assert!(text.contains("world"));
+}
+
+#[tokio::test]
+async fn test_run_windbg_cmd_include_stderr() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let command = "!fake.stderr SYMSRV: unreachable; .echo done";
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": command }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+    assert!(text.contains("done"));
+    assert!(!text.contains("stderr"), "{}", text);
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": command, "include_stderr": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+    assert!(
+        text.contains("stderr:\n```\nSYMSRV: unreachable\n```"),
+        "{}",
+        text
+    );
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo quiet", "include_stderr": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("stderr: (empty)"));
+}
+
+#[tokio::test]
+async fn test_run_windbg_cmd_timeout_secs() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    // 超时错误包含已收集的部分输出，会话保持可用
+    let started = std::time::Instant::now();
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({
+                "dump_path": dump,
+                "command": ".echo collected so far; !fake.hang 30",
+                "timeout_secs": 1
+            }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("Command timeout after 1s"), "{}", message);
+    assert!(message.contains("collected so far"), "{}", message);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo recovered" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("recovered"));
+
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": "k", "timeout_secs": 0 }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ServerError::ToolError(ToolError::InvalidParams(_))),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_run_windbg_cmd_policy_violation() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = McpServer::new(ServerConfig {
+        cdb_path: Some(fake_cdb_path()),
+        timeout: Duration::from_secs(5),
+        init_timeout: Duration::from_secs(5),
+        policy: CommandPolicy {
+            deny: vec![".echo secret*".to_string()],
+            ..CommandPolicy::with_profile(PolicyProfile::Safe)
+        },
+        ..Default::default()
+    });
+
+    let description = server
+        .list_tools()
+        .into_iter()
+        .find(|t| t.name == "run_windbg_cmd")
+        .unwrap()
+        .description;
+    assert!(description.contains("profile: safe"));
+
+    for (command, rejected) in [
+        (".echo hello; .shell dir", ".shell dir"),
+        ("~*e eb 1000 90", "eb 1000 90"),
+        (".echo secret stuff", ".echo secret stuff"),
+    ] {
+        let err = server
+            .handle_tool_call(
+                "run_windbg_cmd",
+                json!({ "dump_path": dump, "command": command }),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap_err();
+        match err {
+            ServerError::ToolError(ToolError::PolicyViolation { command, .. }) => {
+                assert_eq!(command, rejected)
+            }
+            other => panic!("Expected PolicyViolation, got {:?}", other),
+        }
+    }
+    // 被拒绝的命令不会打开会话
+    assert_eq!(server.session_manager().active_session_count().await, 0);
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo hello; .echo world" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("world"));
+}
+
+#[tokio::test]
+async fn test_read_only_remote_session() {
+    const REMOTE: &str = "tcp:Port=5005,Server=127.0.0.1";
+    let server = server(&fake_cdb_path(), None, &[]);
+    let description = server
+        .list_tools()
+        .into_iter()
+        .find(|t| t.name == "open_windbg_remote")
+        .unwrap()
+        .description;
+    assert!(description.contains("read_only"));
+
+    let response = server
+        .handle_tool_call(
+            "open_windbg_remote",
+            json!({ "connection_string": REMOTE, "read_only": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("Mode: read-only"));
+    assert_eq!(
+        response.structured_content.as_ref().unwrap()["read_only"],
+        true
+    );
+
+    // 检查命令可以执行，改变目标的命令被拒绝
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "connection_string": REMOTE, "command": ".echo inspect" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("inspect"));
+    for (command, rejected) in [("g", "g"), (".echo x; bp contoso!main", "bp contoso!main")] {
+        let err = server
+            .handle_tool_call(
+                "run_windbg_cmd",
+                json!({ "connection_string": REMOTE, "command": command }),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap_err();
+        match err {
+            ServerError::ToolError(ToolError::PolicyViolation { command, reason }) => {
+                assert_eq!(command, rejected);
+                assert!(reason.contains("read-only"));
+            }
+            other => panic!("Expected PolicyViolation, got {:?}", other),
+        }
+    }
+
+    // 关闭后重新打开的会话恢复完全控制
+    server
+        .handle_tool_call(
+            "close_windbg_remote",
+            json!({ "connection_string": REMOTE }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let response = server
+        .handle_tool_call(
+            "open_windbg_remote",
+            json!({ "connection_string": REMOTE }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("Mode: full control"));
+    assert_eq!(
+        response.structured_content.as_ref().unwrap()["read_only"],
+        false
+    );
+}
+
+#[tokio::test]
+async fn test_global_read_only_remote() {
+    const REMOTE: &str = "tcp:Port=5006,Server=127.0.0.1";
+    let server = McpServer::new(ServerConfig {
+        cdb_path: Some(fake_cdb_path()),
+        timeout: Duration::from_secs(5),
+        init_timeout: Duration::from_secs(5),
+        read_only_remote: true,
+        ..Default::default()
+    });
+    let description = server
+        .list_tools()
+        .into_iter()
+        .find(|t| t.name == "run_windbg_cmd")
+        .unwrap()
+        .description;
+    assert!(description.contains("All remote sessions are read-only"));
+
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "connection_string": REMOTE, "command": "eb 1000 90" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("read-only"), "{}", err);
+
+    // 转储会话不受只读模式影响
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": "p; .echo stepped" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("stepped"));
+}
+
+#[tokio::test]
+async fn test_read_only_marked_before_session_starts() {
+    const REMOTE: &str = "tcp:Port=5007,Server=127.0.0.1";
+    let dir = TempDir::new().unwrap();
+    let server = server(&dir.path().join("missing-cdb"), None, &[]);
+
+    // 会话启动失败时只读标记也已经生效，并发的命令不会在会话发布后、标记之前执行
+    assert!(server
+        .handle_tool_call(
+            "open_windbg_remote",
+            json!({ "connection_string": REMOTE, "read_only": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .is_err());
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "connection_string": REMOTE, "command": "g" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ServerError::ToolError(ToolError::PolicyViolation { .. })
+        ),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_list_windbg_sessions() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call("list_windbg_sessions", json!({}), CancellationToken::new())
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("No active sessions."));
+
+    for command in [".echo one", ".echo two"] {
+        server
+            .handle_tool_call(
+                "run_windbg_cmd",
+                json!({ "dump_path": dump, "command": command }),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+    }
+
+    let response = server
+        .handle_tool_call("list_windbg_sessions", json!({}), CancellationToken::new())
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("2 commands"));
+    let sessions = &response.structured_content.as_ref().unwrap()["sessions"];
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session["kind"], "dump");
+    assert!(session["target"].as_str().unwrap().ends_with("app.dmp"));
+    assert_eq!(session["commands_executed"], 2);
+    assert_eq!(session["locked"], false);
+    assert_eq!(session["read_only"], false);
+    assert!(session["process_id"].as_u64().is_some());
+    assert!(session["wall_seconds"].as_f64().unwrap() > 0.0);
+    assert!(session["created_at"].as_str().unwrap().ends_with('Z'));
+    if cfg!(any(windows, target_os = "linux")) {
+        assert!(session["cpu_seconds"].as_f64().is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_evicted_sessions_reopen_transparently() {
+    let dir = TempDir::new().unwrap();
+    let dumps: Vec<_> = ["a.dmp", "b.dmp"]
+        .iter()
+        .map(|name| create_dump(&dir, name, ""))
+        .collect();
+    let server = McpServer::new(ServerConfig {
+        cdb_path: Some(fake_cdb_path()),
+        timeout: Duration::from_secs(5),
+        init_timeout: Duration::from_secs(5),
+        session_idle_ttl: Some(Duration::from_millis(100)),
+        max_sessions: Some(1),
+        ..Default::default()
+    });
+    let manager = server.session_manager();
+
+    // 打开第二个转储时关闭第一个
+    for dump in &dumps {
+        server
+            .handle_tool_call(
+                "run_windbg_cmd",
+                json!({ "dump_path": dump, "command": ".echo open" }),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+    }
+    assert_eq!(manager.active_session_count().await, 1);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(manager.evict_idle_sessions().await.len(), 1);
+    assert_eq!(manager.active_session_count().await, 0);
+
+    // 被回收的会话在下次使用时重新打开
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dumps[0], "command": ".echo reopened" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("reopened"));
+    assert_eq!(manager.active_session_count().await, 1);
+}
+
+#[tokio::test]
+async fn test_dead_cdb_session_is_restarted() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    // 命令执行期间 CDB 退出
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": "!fake.crash" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("terminated"), "{}", err);
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo back" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let ContentItem::Text { text: notice } = &response.content[0];
+    assert!(notice.contains("was restarted"), "{}", notice);
+    assert!(notice.contains("`.frame`"), "{}", notice);
+    assert!(response_text(&response).contains("back"));
+
+    // 提示只出现一次
+    let response = server
+        .handle_tool_call(
+            "get_stack",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(!response_text(&response).contains("was restarted"));
+
+    let sessions = server.session_manager().list_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].restarts, 1);
+}
+
+#[tokio::test]
+async fn test_run_windbg_cmd_cancellation() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+    let manager = server.session_manager().clone();
+    let params = |command: &str| {
+        serde_json::from_value(json!({ "dump_path": dump, "command": command })).unwrap()
+    };
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        trigger.cancel();
+    });
+    let err = tools::handle_run_windbg_cmd(
+        manager.clone(),
+        &CommandPolicy::default(),
+        params("!fake.hang 30"),
+        cancel,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("cancelled"), "{}", err);
+
+    // 同一个调试器进程继续服务后续命令
+    let response = tools::handle_run_windbg_cmd(
+        manager.clone(),
+        &CommandPolicy::default(),
+        params(".echo still here"),
+        CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+    assert!(response_text(&response).contains("still here"));
+    assert!(!response_text(&response).contains("was restarted"));
+
+    let sessions = manager.list_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].restarts, 0);
+}
+
+#[tokio::test]
+async fn test_open_windbg_dump_cancellation() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "fake-cdb: hang !analyze -v");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        trigger.cancel();
+    });
+    let err = server
+        .handle_tool_call("open_windbg_dump", json!({ "dump_path": dump }), cancel)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cancelled"), "{}", err);
+
+    // 被中断的 `!analyze -v` 不影响会话中的后续命令
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo still here" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("still here"));
+
+    let sessions = server.session_manager().list_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].restarts, 0);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_killed_idle_cdb_session_is_restarted() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".echo first" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let pid = server.session_manager().list_sessions().await[0]
+        .process_id
+        .unwrap();
+    let status = std::process::Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // 空闲期间被杀死的进程在下次使用前被发现并重新启动
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("was restarted"));
+    let sessions = server.session_manager().list_sessions().await;
+    assert_ne!(sessions[0].process_id, Some(pid));
+}
+
+#[tokio::test]
+async fn test_get_stack_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "get_stack",
+            json!({ "dump_path": dump, "thread": 0 }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let threads = &response.structured_content.as_ref().unwrap()["threads"];
+    assert_eq!(threads.as_array().unwrap().len(), 1);
+    assert_eq!(threads[0]["thread"], 0);
+    let top = &threads[0]["frames"][0];
+    assert_eq!(top["module"], "contoso");
+    assert_eq!(top["function"], "Widget::Render");
+    assert_eq!(top["offset"], "0x24");
+    assert_eq!(top["line"], 88);
+    assert_eq!(threads[0]["frames"][1]["inline"], true);
+
+    // 文本内容同样是 JSON
+    let text: serde_json::Value = serde_json::from_str(&response_text(&response)).unwrap();
+    assert_eq!(&text["threads"], threads);
+
+    let response = server
+        .handle_tool_call(
+            "get_stack",
+            json!({ "dump_path": dump, "all_threads": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let threads = &response.structured_content.as_ref().unwrap()["threads"];
+    assert_eq!(threads.as_array().unwrap().len(), 3);
+    assert_eq!(threads[2]["id"], "1a2c.0c3c");
+    assert_eq!(threads[2]["frames"][0]["call_site"], "0x0");
+    assert_eq!(threads[2]["warnings"][0]["frame_index"], 2);
+}
+
+#[tokio::test]
+async fn test_list_modules_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["total"], 6);
+    assert_eq!(structured["modules"][0]["symbol_status"], "pdb");
+    assert_eq!(structured["modules"][0]["company"], "Contoso Ltd.");
+
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump, "missing_symbols": true, "third_party": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    let names: Vec<&str> = structured["modules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["thirdparty", "hook64"]);
+
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump, "module": "contoso" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["matched"], 1);
+    assert_eq!(structured["modules"][0]["file_version"], "1.4.2.0");
+}
+
+#[tokio::test]
+async fn test_list_threads_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "hang.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "list_threads",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let threads = &response.structured_content.as_ref().unwrap()["threads"];
+    assert_eq!(threads.as_array().unwrap().len(), 3);
+    assert_eq!(threads[0]["tid"], "0x2b40");
+    assert_eq!(threads[0]["current"], true);
+    assert_eq!(threads[0]["user_ms"], 1234);
+    assert_eq!(
+        threads[0]["top_frame"]["call_site"],
+        "contoso!Widget::Render+0x24"
+    );
+    assert_eq!(threads[1]["frozen"], true);
+    assert_eq!(threads[2]["name"], "Render worker");
+    assert_eq!(threads[2]["user_ms"], 12765);
+
+    let response = server
+        .handle_tool_call(
+            "list_threads",
+            json!({ "dump_path": dump, "include_cpu_times": false, "include_top_frame": false }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let threads = &response.structured_content.as_ref().unwrap()["threads"];
+    assert!(threads[0]["user_ms"].is_null());
+    assert!(threads[0]["top_frame"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_registers_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "get_registers",
+            json!({ "dump_path": dump, "exception_context": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["command"], ".ecxr");
+    let registers = &structured["registers"];
+    assert_eq!(registers["architecture"], "x64");
+    assert_eq!(registers["instruction_pointer"], "0x7ff6a1b21234");
+    assert_eq!(registers["symbol"], "contoso!Widget::Render+0x24");
+    assert_eq!(registers["disassembly"]["mnemonic"], "mov");
+    // `.ecxr` 之后不执行 `.cxr`，保留会话中的异常上下文
+    let commands_executed =
+        || async { server.session_manager().list_sessions().await[0].commands_executed };
+    assert_eq!(commands_executed().await, 1);
+
+    let response = server
+        .handle_tool_call(
+            "get_registers",
+            json!({ "dump_path": dump, "thread": 1 }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let registers = &response.structured_content.as_ref().unwrap()["registers"];
+    assert_eq!(
+        registers["symbol"],
+        "ntdll!NtWaitForWorkViaWorkerFactory+0x14"
+    );
+    assert_eq!(registers["flags"]["mnemonics"][4], "nz");
+
+    // 没有寄存器输出时返回错误
+    let err = server
+        .handle_tool_call(
+            "get_registers",
+            json!({ "dump_path": dump, "context_record": "0x1234" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("No registers found"));
+    // 切换到指定的上下文记录后执行 `.cxr` 恢复默认上下文
+    assert_eq!(commands_executed().await, 4);
+}
+
+/// 生成只有头部的 minidump（没有流）
+fn minidump_header(flags: u64) -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in [0x504d_444d_u32, 0xa793, 0, 32, 0, 1_792_002_031] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&flags.to_le_bytes());
+    data
+}
+
+/// 设置文件修改时间
+fn set_modified(path: &Path, secs: u64) {
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(std::time::UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_windbg_dumps_metadata_and_filters() {
+    let dir = TempDir::new().unwrap();
+    let mini = dir.path().join("contoso.exe.6700.dmp");
+    std::fs::write(&mini, minidump_header(0x1)).unwrap();
+    let mut full_data = minidump_header(0x2);
+    full_data.resize(4096, 0);
+    let full = dir.path().join("widget.exe.12.dmp");
+    std::fs::write(&full, full_data).unwrap();
+    let mut kernel_data = b"PAGEDU64".to_vec();
+    kernel_data.resize(8192, 0);
+    kernel_data[0x38..0x3c].copy_from_slice(&0xd1u32.to_le_bytes());
+    let kernel = dir.path().join("MEMORY.DMP");
+    std::fs::write(&kernel, kernel_data).unwrap();
+
+    // 2026-10-12、2026-10-13、2026-10-14
+    set_modified(&kernel, 1_791_763_200);
+    set_modified(&full, 1_791_849_600);
+    set_modified(&mini, 1_791_936_000);
+
+    let server = server(&fake_cdb_path(), None, &[]);
+    let response = server
+        .handle_tool_call(
+            "list_windbg_dumps",
+            json!({ "directory_path": dir.path(), "sort_by": "mtime" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["matched"], 3);
+    let dumps = structured["dumps"].as_array().unwrap();
+    assert!(dumps[0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("contoso.exe.6700.dmp"));
+    assert_eq!(dumps[0]["modified"], "2026-10-14T00:00:00Z");
+    assert_eq!(dumps[0]["dump_type"], "mini");
+    assert_eq!(dumps[0]["process_name"], "contoso.exe");
+    assert_eq!(dumps[1]["dump_type"], "full");
+    assert_eq!(dumps[2]["dump_type"], "kernel");
+    assert_eq!(dumps[2]["bugcheck_code"], "0xd1");
+    assert!(dumps[2]["process_name"].is_null());
+    assert!(response_text(&response).contains("mini, contoso.exe"));
+
+    let response = server
+        .handle_tool_call(
+            "list_windbg_dumps",
+            json!({
+                "directory_path": dir.path(),
+                "since": "2026-10-13",
+                "until": "2026-10-14",
+                "name_glob": "*.exe.*",
+                "sort_by": "name",
+                "limit": 1
+            }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["matched"], 2);
+    let dumps = structured["dumps"].as_array().unwrap();
+    assert_eq!(dumps.len(), 1);
+    assert!(dumps[0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("contoso.exe.6700.dmp"));
+    assert!(response_text(&response).contains("Found 2 dump files (showing 1)"));
+
+    let response = server
+        .handle_tool_call(
+            "list_windbg_dumps",
+            json!({ "directory_path": dir.path(), "min_size": 1000, "max_size": 5000 }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let dumps = &response.structured_content.as_ref().unwrap()["dumps"];
+    assert_eq!(dumps.as_array().unwrap().len(), 1);
+    assert_eq!(dumps[0]["dump_type"], "full");
+
+    let err = server
+        .handle_tool_call(
+            "list_windbg_dumps",
+            json!({ "directory_path": dir.path(), "since": "last tuesday" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid since time"));
+}
+
+#[tokio::test]
+async fn test_triage_dumps_end_to_end() {
+    let dir = TempDir::new().unwrap();
+    create_dump(&dir, "a.dmp", "");
+    create_dump(&dir, "b.dmp", "");
+    create_dump(
+        &dir,
+        "legacy.dmp",
+        "fake-cdb: fixture !analyze -v => ../analyze/legacy_x86.txt",
+    );
+    create_dump(&dir, "bad.dmp", "fake-cdb: startup=crash");
+    let server = server(&fake_cdb_path(), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "triage_dumps",
+            json!({ "directory_path": dir.path(), "frames": 2 }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let structured = response.structured_content.as_ref().unwrap();
+    assert_eq!(structured["dump_count"], 4);
+    assert_eq!(structured["bucket_count"], 2);
+
+    let buckets = structured["buckets"].as_array().unwrap();
+    assert_eq!(buckets[0]["count"], 2);
+    assert_eq!(buckets[0]["exception_code"], "0xc0000005");
+    assert_eq!(buckets[0]["faulting_module"], "contoso");
+    assert_eq!(
+        buckets[0]["frames"],
+        json!(["contoso!Widget::Render", "contoso!Window::Paint"])
+    );
+    assert!(buckets[0]["first_seen"].as_str().unwrap().ends_with('Z'));
+    assert_eq!(buckets[0]["dumps"].as_array().unwrap().len(), 2);
+
+    assert_eq!(buckets[1]["count"], 1);
+    assert_eq!(buckets[1]["faulting_module"], "legacy");
+    assert_ne!(buckets[0]["id"], buckets[1]["id"]);
+
+    let failed = structured["failed"].as_array().unwrap();
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0]["path"].as_str().unwrap().ends_with("bad.dmp"));
+
+    // 分诊新建的会话在分析后关闭
+    let err = server
+        .handle_tool_call(
+            "close_windbg_dump",
+            json!({ "dump_path": dir.path().join("a.dmp") }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Session not found"));
+}
+
+#[tokio::test]
+async fn test_remote_tools_end_to_end() {
+    let server = server(&fake_cdb_path(), None, &[]);
+    let connection_string = "tcp:Port=5005,Server=127.0.0.1";
+
+    let response = server
+        .handle_tool_call(
+            "open_windbg_remote",
+            json!({ "connection_string": connection_string }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    assert!(response_text(&response).contains("# Remote Debugging Session"));
+    let registers = &response.structured_content.as_ref().unwrap()["registers"];
+    assert_eq!(registers["architecture"], "x64");
+
+    server
+        .handle_tool_call(
+            "close_windbg_remote",
+            json!({ "connection_string": connection_string }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_configured_cdb_path_is_launched() {
+    let dir = TempDir::new().unwrap();
+    let stub = copy_stub_cdb(&dir, "custom-cdb");
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&stub, None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": "!fake.argv" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+
+    let exe_line = text
+        .lines()
+        .find_map(|l| l.split_once("exe: ").map(|(_, exe)| exe.trim()))
+        .unwrap();
+    assert_eq!(
+        Path::new(exe_line).canonicalize().unwrap(),
+        stub.canonicalize().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_configured_symbols_path_and_extra_args_are_applied() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(
+        &fake_cdb_path(),
+        Some("SRV*C:\\Symbols*https://msdl.microsoft.com/download/symbols"),
+        &["-lines", "-n"],
+    );
+
+    let response = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": ".sympath; !fake.argv" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+
+    assert!(text.contains(
+        "Symbol search path is: SRV*C:\\Symbols*https://msdl.microsoft.com/download/symbols"
+    ));
+    let args: Vec<&str> = text
+        .lines()
+        .filter_map(|l| l.strip_prefix("arg: "))
+        .collect();
+    assert_eq!(&args[..3], &["-lines", "-n", "-z"]);
+}
+
+#[tokio::test]
+async fn test_missing_configured_cdb_is_reported() {
+    let dir = TempDir::new().unwrap();
+    let dump = create_dump(&dir, "app.dmp", "");
+    let server = server(&dir.path().join("missing-cdb.exe"), None, &[]);
+
+    let err = server
+        .handle_tool_call(
+            "run_windbg_cmd",
+            json!({ "dump_path": dump, "command": "kb" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("CDB executable not found"));
+}
+
+#[tokio::test]
+async fn test_list_modules_and_threads_without_cdb() {
+    let dir = TempDir::new().unwrap();
+    let dump = dir.path().join("contoso.exe.6700.dmp");
+    std::fs::write(&dump, sample_minidump()).unwrap();
+    let server = server(&dir.path().join("missing-cdb.exe"), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let modules = response.structured_content.unwrap();
+    assert_eq!(modules["source"], "minidump");
+    assert_eq!(modules["total"], 2);
+    assert_eq!(
+        modules["modules"][0]["path"],
+        r"C:\Program Files\Contoso\contoso.exe"
+    );
+    assert_eq!(modules["modules"][0]["base"], "0x7ff6a1b20000");
+    let codeview = &modules["modules"][0]["codeview"];
+    assert_eq!(codeview["pdb_file"], r"C:\build\contoso.pdb");
+    assert_eq!(codeview["guid"], "{3F2504E0-4F89-11D3-9A0C-0305E82C3301}");
+    assert_eq!(codeview["age"], 1);
+    assert!(modules["modules"][1]["codeview"].is_null());
+    assert_eq!(modules["unloaded_modules"][0]["path"], "hook64.dll");
+
+    let response = server
+        .handle_tool_call(
+            "list_modules",
+            json!({ "dump_path": dump, "module": "nt*" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let modules = response.structured_content.unwrap();
+    assert_eq!(modules["matched"], 1);
+    assert_eq!(
+        modules["modules"][0]["path"],
+        r"C:\Windows\System32\ntdll.dll"
+    );
+
+    let response = server
+        .handle_tool_call(
+            "list_threads",
+            json!({ "dump_path": dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let threads = response.structured_content.unwrap();
+    assert_eq!(threads["source"], "minidump");
+    let threads = threads["threads"].as_array().unwrap();
+    assert_eq!(threads.len(), 2);
+    assert_eq!(threads[0]["tid"], "0x2b40");
+    assert_eq!(threads[0]["pid"], "0x1a2c");
+    assert_eq!(threads[0]["name"], "RenderThread");
+    assert_eq!(threads[0]["last_event"], true);
+    assert_eq!(threads[1]["index"], 1);
+    assert_eq!(threads[1]["last_event"], false);
+
+    // 远程会话没有可读取的文件，仍然报告 CDB 缺失
+    let err = server
+        .handle_tool_call(
+            "list_threads",
+            json!({ "connection_string": "tcp:Port=5005" }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("CDB executable not found"));
+}
+
+#[tokio::test]
+async fn test_open_windbg_dump_offline_without_cdb() {
+    let dir = TempDir::new().unwrap();
+    let dump = dir.path().join("contoso.exe.6700.dmp");
+    std::fs::write(&dump, sample_minidump()).unwrap();
+    let server = server(&dir.path().join("missing-cdb.exe"), None, &[]);
+
+    let response = server
+        .handle_tool_call(
+            "open_windbg_dump",
+            json!({ "dump_path": dump, "include_threads": true }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+    let text = response_text(&response);
+    assert!(text.contains("no symbolication"));
+    assert!(text.contains("ExceptionCode: c0000005 (ACCESS_VIOLATION)"));
+    assert!(text.contains("ExceptionAddress: 00007ff6a1b21234 (contoso+0x1234)"));
+    assert!(text.contains("Attempt to read from address 0x8"));
+    assert!(text.contains("Thread 0x2b40 (RenderThread)"));
+    assert!(text.contains("00000000d12ff7f0  0000000000000000 <- SP"));
+    assert!(text.contains("00000000d12ff7f8  00007ff6a1b25678 (contoso+0x5678)"));
+    assert!(text.contains("contoso.pdb 3F2504E04F8911D39A0C0305E82C33011"));
+    assert!(text.contains("## Thread List"));
+
+    let report = response.structured_content.unwrap();
+    assert_eq!(report["source"], "minidump");
+    assert_eq!(report["symbolication"], "none");
+    assert_eq!(report["exception"]["name"], "ACCESS_VIOLATION");
+    assert_eq!(report["exception"]["module"], "contoso+0x1234");
+    assert_eq!(report["faulting_thread"]["thread_id"], "0x2b40");
+    assert_eq!(
+        report["faulting_thread"]["registers"]["stack_pointer"],
+        "0xd12ff7f0"
+    );
+    assert_eq!(
+        report["stack_memory"]["words"][9]["module"],
+        "contoso+0x5678"
+    );
+    assert_eq!(report["modules"].as_array().unwrap().len(), 2);
+    assert_eq!(report["threads"].as_array().unwrap().len(), 2);
+    assert_eq!(report["summary"]["os_version"], "10.0.19045");
+
+    // 不是 minidump 的文件无法离线分析
+    let text_dump = create_dump(&dir, "notes.dmp", "not a dump");
+    let err = server
+        .handle_tool_call(
+            "open_windbg_dump",
+            json!({ "dump_path": text_dump }),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Minidump error"));
+}